@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use log::info;
+use tauri::State;
+
+use crate::db::audit::AuditLog;
+use crate::error::AppError;
+
+/// Write the audit log to `file_path` as JSONL, optionally limited to entries
+/// in `[since, until)` (Unix ms) for one connection. Returns the entry count.
+#[tauri::command]
+pub async fn export_audit_log(
+    file_path: String,
+    since: Option<i64>,
+    until: Option<i64>,
+    connection_id: Option<String>,
+    audit: State<'_, AuditLog>,
+) -> Result<u64, AppError> {
+    let written = audit.export(
+        &PathBuf::from(&file_path),
+        since,
+        until,
+        connection_id.as_deref(),
+    )?;
+    info!("Exported {} audit log entries to {}", written, file_path);
+    Ok(written)
+}
@@ -1,9 +1,11 @@
+use crate::db::history::QueryHistory;
 use crate::error::AppError;
 use crate::models::backup::BackupEntry;
+use crate::models::history::HistoryEntry;
 use chrono::Local;
 use serde_json::Value;
 use std::fs;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, State};
 
 fn backup_dir(app: &AppHandle) -> Result<std::path::PathBuf, AppError> {
     let data_dir = app
@@ -25,7 +27,10 @@ fn store_dir(app: &AppHandle) -> Result<std::path::PathBuf, AppError> {
 }
 
 #[tauri::command]
-pub async fn backup_configs(app: AppHandle) -> Result<String, AppError> {
+pub async fn backup_configs(
+    app: AppHandle,
+    history: State<'_, QueryHistory>,
+) -> Result<String, AppError> {
     let store_path = store_dir(&app)?;
     let backup_path = backup_dir(&app)?;
 
@@ -54,6 +59,13 @@ pub async fn backup_configs(app: AppHandle) -> Result<String, AppError> {
         }
     }
 
+    // Query history lives in its own SQLite database
+    let entries = history.export().await?;
+    combined.insert(
+        "history".to_string(),
+        serde_json::to_value(entries).map_err(|e| AppError::Serialization(e.to_string()))?,
+    );
+
     let backup_content = serde_json::to_string_pretty(&Value::Object(combined))
         .map_err(|e| AppError::Serialization(e.to_string()))?;
 
@@ -115,7 +127,11 @@ pub async fn list_backups(app: AppHandle) -> Result<Vec<BackupEntry>, AppError>
 }
 
 #[tauri::command]
-pub async fn restore_backup(app: AppHandle, filename: String) -> Result<(), AppError> {
+pub async fn restore_backup(
+    app: AppHandle,
+    filename: String,
+    history: State<'_, QueryHistory>,
+) -> Result<(), AppError> {
     let backup_path = backup_dir(&app)?;
     let store_path = store_dir(&app)?;
     let file_path = backup_path.join(&filename);
@@ -149,6 +165,13 @@ pub async fn restore_backup(app: AppHandle, filename: String) -> Result<(), AppE
             .map_err(|e| AppError::Database(format!("Failed to write settings.json: {}", e)))?;
     }
 
+    // Restore query history; backups taken before it was recorded have none
+    if let Some(entries) = combined.get("history") {
+        let entries: Vec<HistoryEntry> = serde_json::from_value(entries.clone())
+            .map_err(|e| AppError::Serialization(e.to_string()))?;
+        history.import(&entries).await?;
+    }
+
     Ok(())
 }
 
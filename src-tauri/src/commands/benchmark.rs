@@ -0,0 +1,203 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use futures::future;
+use futures::stream::{self, StreamExt};
+use log::{info, warn};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::commands::query::run_with_cancel;
+use crate::db::audit::{AuditDetails, AuditLog};
+use crate::db::benchmark::latency_stats;
+use crate::db::cancel::CancellationRegistry;
+use crate::db::guardrails::Guardrails;
+use crate::db::pool::PoolManager;
+use crate::db::read_only::is_write;
+use crate::error::AppError;
+use crate::models::benchmark::{BenchmarkProgress, BenchmarkResult};
+
+/// Event carrying a `BenchmarkProgress` snapshot while a benchmark runs.
+const BENCHMARK_EVENT: &str = "benchmark-progress";
+const BENCHMARK_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const DEFAULT_RUN_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_ITERATIONS: usize = 100_000;
+const MAX_REPORTED_ERRORS: usize = 10;
+
+/// Run `sql` `iterations` times through `execute_raw`, after `warmup`
+/// unmeasured runs, keeping up to `concurrency` runs in flight. The timeout
+/// applies to each run. Cancelling `query_id` stops new runs from starting and
+/// returns the figures gathered so far. A write is audited once, with the
+/// number of runs and the rows affected across all of them.
+#[tauri::command]
+pub async fn benchmark_query(
+    app: AppHandle,
+    connection_id: String,
+    sql: String,
+    iterations: usize,
+    warmup: Option<usize>,
+    concurrency: Option<usize>,
+    timeout_secs: Option<u64>,
+    query_id: Option<String>,
+    confirm_token: Option<String>,
+    pool_manager: State<'_, PoolManager>,
+    cancel_registry: State<'_, CancellationRegistry>,
+    guardrails: State<'_, Guardrails>,
+    audit: State<'_, AuditLog>,
+) -> Result<BenchmarkResult, AppError> {
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return Err(AppError::InvalidConfig(format!(
+            "Iterations must be between 1 and {}",
+            MAX_ITERATIONS
+        )));
+    }
+    let handle = pool_manager.get(&connection_id).await?;
+    guardrails
+        .check_query(&connection_id, handle.base(), &sql, confirm_token.as_deref())
+        .await?;
+    let warmup = warmup.unwrap_or(0);
+    let concurrency = concurrency.unwrap_or(1).max(1);
+    let duration = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_RUN_TIMEOUT);
+    let total = warmup + iterations;
+    info!(
+        "Benchmarking query on '{}': {} iterations, {} warm-up, concurrency {}",
+        connection_id, iterations, warmup, concurrency
+    );
+
+    let stopped = AtomicBool::new(false);
+    let cancel_rx = query_id
+        .as_ref()
+        .map(|qid| cancel_registry.register(qid.clone()));
+    let cancelled = async move {
+        if let Some(rx) = cancel_rx {
+            if rx.await.is_ok() {
+                return;
+            }
+        }
+        std::future::pending::<()>().await
+    };
+    tokio::pin!(cancelled);
+    let (handle, sql, cancel_registry) = (&handle, &sql, &*cancel_registry);
+    let connection = connection_id.as_str();
+    let mut runs = stream::iter(0..total)
+        .take_while(|_| future::ready(!stopped.load(Ordering::Relaxed)))
+        .map(|index| async move {
+            let started = Instant::now();
+            let result = run_with_cancel(
+                handle,
+                connection,
+                None,
+                duration,
+                cancel_registry,
+                handle.base().execute_raw(sql),
+            )
+            .await;
+            (index, started.elapsed(), result)
+        })
+        .buffer_unordered(concurrency);
+
+    let started = Instant::now();
+    let mut completed = 0;
+    let mut failed = 0;
+    let mut samples = Vec::with_capacity(iterations);
+    let mut rows_returned = None;
+    let mut errors: Vec<String> = Vec::new();
+    let mut affected_rows = 0;
+    let mut succeeded_runs = 0;
+    let mut first_error = None;
+    let mut last_progress = Instant::now();
+
+    loop {
+        let next = tokio::select! {
+            next = runs.next() => next,
+            _ = &mut cancelled, if !stopped.load(Ordering::Relaxed) => {
+                warn!("Benchmark on '{}' cancelled after {} runs", connection_id, completed);
+                stopped.store(true, Ordering::Relaxed);
+                continue;
+            }
+        };
+        let Some((index, elapsed, result)) = next else {
+            break;
+        };
+        completed += 1;
+        let measured = index >= warmup;
+        if let Ok(response) = &result {
+            succeeded_runs += 1;
+            affected_rows += response.affected_rows.unwrap_or(0);
+        }
+        match result {
+            Ok(response) if measured => {
+                samples.push(elapsed.as_secs_f64() * 1000.0);
+                rows_returned = Some(if response.columns.is_empty() {
+                    response.affected_rows.unwrap_or(0)
+                } else {
+                    response.row_count as u64
+                });
+            }
+            Ok(_) => {}
+            Err(e) => {
+                if measured {
+                    failed += 1;
+                }
+                let message = e.to_string();
+                if errors.len() < MAX_REPORTED_ERRORS && !errors.contains(&message) {
+                    errors.push(message);
+                }
+                first_error.get_or_insert(e);
+            }
+        }
+
+        if completed == total || last_progress.elapsed() >= BENCHMARK_PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            let progress = BenchmarkProgress {
+                connection_id: connection_id.clone(),
+                query_id: query_id.clone(),
+                completed,
+                total,
+                failed,
+                warming_up: completed < warmup,
+            };
+            app.emit(BENCHMARK_EVENT, &progress).ok();
+        }
+    }
+
+    if let Some(qid) = &query_id {
+        cancel_registry.remove(qid);
+    }
+    if completed > 0 && is_write(sql, handle.base().dialect_hint()) {
+        let outcome = match &first_error {
+            Some(e) if succeeded_runs == 0 => Err(e),
+            _ => Ok(Some(affected_rows)),
+        };
+        audit.record(
+            &connection_id,
+            "benchmark_query",
+            AuditDetails {
+                statement: Some(format!("-- {} benchmark runs\n{}", completed, sql)),
+                ..Default::default()
+            },
+            outcome,
+        );
+    }
+    let latency = latency_stats(&samples);
+    info!(
+        "Benchmark on '{}' finished: {} succeeded, {} failed, p50 {:?}ms",
+        connection_id,
+        samples.len(),
+        failed,
+        latency.as_ref().map(|l| l.p50_ms)
+    );
+    Ok(BenchmarkResult {
+        iterations,
+        warmup,
+        concurrency,
+        succeeded: samples.len(),
+        failed,
+        latency,
+        rows_returned,
+        errors,
+        total_time_ms: started.elapsed().as_millis() as u64,
+        cancelled: stopped.load(Ordering::Relaxed),
+    })
+}
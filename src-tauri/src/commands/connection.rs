@@ -3,7 +3,9 @@ use std::sync::Arc;
 use log::{error, info, warn};
 use tauri::State;
 
+use crate::db::audit::AuditLog;
 use crate::db::drivers;
+use crate::db::guardrails::Guardrails;
 use crate::db::handle::DriverHandle;
 use crate::db::keychain;
 use crate::db::pool::PoolManager;
@@ -62,6 +64,15 @@ fn resolve_keychain_password(config: &mut ConnectionConfig) {
 
 /// Factory function: creates the appropriate driver handle based on database type.
 async fn create_driver_handle(config: &ConnectionConfig) -> Result<DriverHandle, AppError> {
+    let handle = connect_driver(config).await?;
+    if config.read_only {
+        info!("Connection '{}' is read-only", config.id);
+        return Ok(handle.into_read_only());
+    }
+    Ok(handle)
+}
+
+async fn connect_driver(config: &ConnectionConfig) -> Result<DriverHandle, AppError> {
     match config.db_type {
         DatabaseType::PostgreSQL => {
             let driver = drivers::postgres::PostgresDriver::connect(config).await?;
@@ -140,6 +151,8 @@ pub async fn connect_db(
     config: ConnectionConfig,
     pool_manager: State<'_, PoolManager>,
     tunnel_manager: State<'_, TunnelManager>,
+    guardrails: State<'_, Guardrails>,
+    audit: State<'_, AuditLog>,
 ) -> Result<String, AppError> {
     let id = config.id.clone();
     info!("Connecting to {:?} '{}'", config.db_type, id);
@@ -158,6 +171,8 @@ pub async fn connect_db(
     })?;
 
     pool_manager.add(id.clone(), handle).await;
+    guardrails.set_level(&id, config.guardrail_level());
+    audit.set_connection_name(&id, &config.name);
     info!("Connected to '{}'", id);
     Ok(id)
 }
@@ -167,10 +182,14 @@ pub async fn disconnect_db(
     connection_id: String,
     pool_manager: State<'_, PoolManager>,
     tunnel_manager: State<'_, TunnelManager>,
+    guardrails: State<'_, Guardrails>,
+    audit: State<'_, AuditLog>,
 ) -> Result<(), AppError> {
     info!("Disconnecting '{}'", connection_id);
     pool_manager.remove(&connection_id).await?;
     tunnel_manager.remove_tunnel(&connection_id).await;
+    guardrails.remove(&connection_id);
+    audit.remove(&connection_id);
     info!("Disconnected '{}'", connection_id);
     Ok(())
 }
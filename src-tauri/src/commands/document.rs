@@ -1,8 +1,18 @@
 use tauri::State;
 
+use crate::db::audit::{AuditDetails, AuditLog};
+use crate::db::guardrails::Guardrails;
 use crate::db::pool::PoolManager;
 use crate::error::AppError;
 
+fn audit_details(container: &str, collection: &str, statement: String) -> AuditDetails {
+    AuditDetails {
+        target: Some(format!("{}.{}", container, collection)),
+        statement: Some(statement),
+        ..Default::default()
+    }
+}
+
 #[tauri::command]
 pub async fn insert_document(
     connection_id: String,
@@ -10,10 +20,19 @@ pub async fn insert_document(
     collection: String,
     document: serde_json::Value,
     pool_manager: State<'_, PoolManager>,
+    audit: State<'_, AuditLog>,
 ) -> Result<String, AppError> {
     let handle = pool_manager.get(&connection_id).await?;
     let driver = handle.as_document()?;
-    driver.insert_document(&container, &collection, document).await
+    let statement = document.to_string();
+    let result = driver.insert_document(&container, &collection, document).await;
+    audit.record(
+        &connection_id,
+        "insert_document",
+        audit_details(&container, &collection, statement),
+        result.as_ref().map(|_| Some(1)),
+    );
+    result
 }
 
 #[tauri::command]
@@ -24,10 +43,19 @@ pub async fn update_document(
     filter: serde_json::Value,
     update: serde_json::Value,
     pool_manager: State<'_, PoolManager>,
+    audit: State<'_, AuditLog>,
 ) -> Result<u64, AppError> {
     let handle = pool_manager.get(&connection_id).await?;
     let driver = handle.as_document()?;
-    driver.update_document(&container, &collection, filter, update).await
+    let statement = serde_json::json!({ "filter": filter, "update": update }).to_string();
+    let result = driver.update_document(&container, &collection, filter, update).await;
+    audit.record(
+        &connection_id,
+        "update_document",
+        audit_details(&container, &collection, statement),
+        result.as_ref().map(|n| Some(*n)),
+    );
+    result
 }
 
 #[tauri::command]
@@ -36,9 +64,30 @@ pub async fn delete_documents(
     container: String,
     collection: String,
     filter: serde_json::Value,
+    confirm_token: Option<String>,
     pool_manager: State<'_, PoolManager>,
+    guardrails: State<'_, Guardrails>,
+    audit: State<'_, AuditLog>,
 ) -> Result<u64, AppError> {
     let handle = pool_manager.get(&connection_id).await?;
     let driver = handle.as_document()?;
-    driver.delete_documents(&container, &collection, filter).await
+    guardrails
+        .check_delete_documents(
+            &connection_id,
+            handle.base(),
+            &container,
+            &collection,
+            &filter,
+            confirm_token.as_deref(),
+        )
+        .await?;
+    let statement = filter.to_string();
+    let result = driver.delete_documents(&container, &collection, filter).await;
+    audit.record(
+        &connection_id,
+        "delete_documents",
+        audit_details(&container, &collection, statement),
+        result.as_ref().map(|n| Some(*n)),
+    );
+    result
 }
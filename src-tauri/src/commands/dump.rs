@@ -5,7 +5,8 @@ use chrono::Local;
 use log::info;
 use tauri::{AppHandle, Emitter, State};
 
-use crate::commands::export::{cell_value_to_sql_literal, generate_create_table};
+use crate::commands::export::{cell_value_to_sql_literal, generate_create_table, PAGE_SIZE};
+use crate::db::keyset::TableScan;
 use crate::db::pool::PoolManager;
 use crate::error::AppError;
 use crate::models::dump::{DumpProgress, DumpResult};
@@ -172,18 +173,12 @@ async fn stream_insert_statements<W: Write>(
     let handle = pool_manager.get(connection_id).await?;
     let driver = handle.as_sql()?;
 
-    let page_size: i64 = 5000;
-    let mut offset: i64 = 0;
+    let mut scan = TableScan::new(driver, schema, table, PAGE_SIZE).await?;
     let mut total: u64 = 0;
     let mut columns: Vec<ColumnDef> = Vec::new();
 
-    loop {
-        let response = driver.get_table_data(schema, table, page_size, offset).await?;
-        if response.rows.is_empty() {
-            break;
-        }
-
-        if offset == 0 {
+    while let Some(response) = scan.next_page().await? {
+        if total == 0 {
             columns = response.columns.clone();
         }
 
@@ -210,11 +205,6 @@ async fn stream_insert_statements<W: Write>(
         }
 
         total += response.rows.len() as u64;
-        let count = response.rows.len() as i64;
-        if count < page_size {
-            break;
-        }
-        offset += page_size;
     }
 
     if total > 0 {
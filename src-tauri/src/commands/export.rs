@@ -4,12 +4,18 @@ use std::io::{BufWriter, Write};
 use log::{debug, info};
 use tauri::State;
 
+use crate::db::audit::{AuditDetails, AuditLog};
+use crate::db::keyset::TableScan;
 use crate::db::pool::PoolManager;
+use crate::db::types::{cell_text, decimal_text};
 use crate::error::AppError;
 use crate::models::export::ImportResult;
 use crate::models::query::{CellValue, ColumnDef};
 use crate::models::schema::{ColumnInfo, ForeignKeyInfo, IndexInfo};
 
+/// Rows fetched per round trip when streaming a whole table.
+pub(crate) const PAGE_SIZE: i64 = 5000;
+
 // === Helpers ===
 
 fn cell_value_to_string(cell: &CellValue) -> String {
@@ -25,6 +31,10 @@ fn cell_value_to_string(cell: &CellValue) -> String {
         CellValue::LargeText { preview, .. } => preview.clone(),
         CellValue::LargeJson { preview, .. } => preview.clone(),
         CellValue::LargeBinary { full_length, .. } => format!("[{} bytes]", full_length),
+        CellValue::Array(items) => {
+            serde_json::Value::Array(items.iter().map(cell_value_to_json).collect()).to_string()
+        }
+        _ => cell_text(cell).unwrap_or_default(),
     }
 }
 
@@ -41,6 +51,8 @@ pub(crate) fn cell_value_to_sql_literal(cell: &CellValue) -> String {
         CellValue::LargeText { preview, .. } => format!("'{}'", preview.replace('\'', "''")),
         CellValue::LargeJson { preview, .. } => format!("'{}'", preview.replace('\'', "''")),
         CellValue::LargeBinary { full_length, .. } => format!("'[{} bytes]'", full_length),
+        CellValue::Decimal { digits, scale } => decimal_text(digits, *scale),
+        _ => format!("'{}'", cell_text(cell).unwrap_or_default().replace('\'', "''")),
     }
 }
 
@@ -65,6 +77,12 @@ fn cell_value_to_json(cell: &CellValue) -> serde_json::Value {
         CellValue::LargeBinary { full_length, .. } => {
             serde_json::Value::String(format!("[{} bytes]", full_length))
         }
+        CellValue::Array(items) => {
+            serde_json::Value::Array(items.iter().map(cell_value_to_json).collect())
+        }
+        // Decimals are written as strings: JSON numbers are read back as
+        // doubles by most consumers.
+        _ => serde_json::Value::String(cell_text(cell).unwrap_or_default()),
     }
 }
 
@@ -179,18 +197,12 @@ async fn stream_table_csv<W: Write>(
     let handle = pool_manager.get(connection_id).await?;
     let driver = handle.as_sql()?;
 
-    let page_size: i64 = 5000;
-    let mut offset: i64 = 0;
+    let mut scan = TableScan::new(driver, schema, table, PAGE_SIZE).await?;
     let mut total: u64 = 0;
 
-    loop {
-        let response = driver.get_table_data(schema, table, page_size, offset).await?;
-        if response.rows.is_empty() {
-            break;
-        }
-
+    while let Some(response) = scan.next_page().await? {
         // Write header on first page
-        if offset == 0 {
+        if total == 0 {
             let headers: Vec<String> = response.columns.iter().map(|c| c.name.clone()).collect();
             writer
                 .write_record(&headers)
@@ -205,11 +217,6 @@ async fn stream_table_csv<W: Write>(
         }
 
         total += response.rows.len() as u64;
-        let count = response.rows.len() as i64;
-        if count < page_size {
-            break;
-        }
-        offset += page_size;
     }
 
     writer
@@ -229,8 +236,7 @@ async fn stream_table_json<W: Write>(
     let handle = pool_manager.get(connection_id).await?;
     let driver = handle.as_sql()?;
 
-    let page_size: i64 = 5000;
-    let mut offset: i64 = 0;
+    let mut scan = TableScan::new(driver, schema, table, PAGE_SIZE).await?;
     let mut total: u64 = 0;
     let mut columns: Vec<ColumnDef> = Vec::new();
 
@@ -238,13 +244,8 @@ async fn stream_table_json<W: Write>(
         .write_all(b"[\n")
         .map_err(|e| AppError::Database(format!("JSON write error: {}", e)))?;
 
-    loop {
-        let response = driver.get_table_data(schema, table, page_size, offset).await?;
-        if response.rows.is_empty() {
-            break;
-        }
-
-        if offset == 0 {
+    while let Some(response) = scan.next_page().await? {
+        if total == 0 {
             columns = response.columns.clone();
         }
 
@@ -267,11 +268,6 @@ async fn stream_table_json<W: Write>(
         }
 
         total += response.rows.len() as u64;
-        let count = response.rows.len() as i64;
-        if count < page_size {
-            break;
-        }
-        offset += page_size;
     }
 
     writer
@@ -294,18 +290,12 @@ async fn stream_table_sql<W: Write>(
     let handle = pool_manager.get(connection_id).await?;
     let driver = handle.as_sql()?;
 
-    let page_size: i64 = 5000;
-    let mut offset: i64 = 0;
+    let mut scan = TableScan::new(driver, schema, table, PAGE_SIZE).await?;
     let mut total: u64 = 0;
     let mut columns: Vec<ColumnDef> = Vec::new();
 
-    loop {
-        let response = driver.get_table_data(schema, table, page_size, offset).await?;
-        if response.rows.is_empty() {
-            break;
-        }
-
-        if offset == 0 {
+    while let Some(response) = scan.next_page().await? {
+        if total == 0 {
             columns = response.columns.clone();
         }
 
@@ -332,11 +322,6 @@ async fn stream_table_sql<W: Write>(
         }
 
         total += response.rows.len() as u64;
-        let count = response.rows.len() as i64;
-        if count < page_size {
-            break;
-        }
-        offset += page_size;
     }
 
     writer
@@ -571,6 +556,7 @@ pub async fn import_csv(
     has_header: bool,
     delimiter: Option<String>,
     pool_manager: State<'_, PoolManager>,
+    audit: State<'_, AuditLog>,
 ) -> Result<ImportResult, AppError> {
     info!("Importing CSV from {} into '{}'.'{}'", file_path, schema, table);
 
@@ -641,6 +627,16 @@ pub async fn import_csv(
         "Import complete: {} imported, {} failed",
         rows_imported, rows_failed
     );
+    audit.record(
+        &connection_id,
+        "import_csv",
+        AuditDetails {
+            target: Some(format!("{}.{}", schema, table)),
+            statement: Some(format!("Import from {}", file_path)),
+            ..Default::default()
+        },
+        Ok(Some(rows_imported)),
+    );
     Ok(ImportResult {
         rows_imported,
         rows_failed,
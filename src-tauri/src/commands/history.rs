@@ -0,0 +1,39 @@
+use log::info;
+use tauri::State;
+
+use crate::db::history::QueryHistory;
+use crate::error::AppError;
+use crate::models::history::{HistoryEntry, HistoryFilter};
+
+#[tauri::command]
+pub async fn search_history(
+    filter: Option<HistoryFilter>,
+    history: State<'_, QueryHistory>,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    history.search(&filter.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn pin_history_entry(
+    id: i64,
+    pinned: bool,
+    history: State<'_, QueryHistory>,
+) -> Result<(), AppError> {
+    history.set_pinned(id, pinned).await
+}
+
+/// Delete history executed before `before` (Unix ms), or all of it when
+/// omitted. Pinned entries survive unless `include_pinned` is set.
+#[tauri::command]
+pub async fn purge_history(
+    before: Option<i64>,
+    connection_id: Option<String>,
+    include_pinned: Option<bool>,
+    history: State<'_, QueryHistory>,
+) -> Result<u64, AppError> {
+    let removed = history
+        .purge(before, connection_id.as_deref(), include_pinned.unwrap_or(false))
+        .await?;
+    info!("Purged {} query history entries", removed);
+    Ok(removed)
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use log::{debug, error, info};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::commands::query::{apply_result_limits, audit_if_write, run_with_cancel};
+use crate::db::audit::AuditLog;
+use crate::db::cancel::CancellationRegistry;
+use crate::db::guardrails::Guardrails;
+use crate::db::jobs::JobManager;
+use crate::db::pool::PoolManager;
+use crate::error::AppError;
+use crate::models::job::JobInfo;
+use crate::models::query::QueryResponse;
+
+/// Event carrying a `JobInfo` snapshot on every status change and while running.
+const JOB_EVENT: &str = "query-job";
+const JOB_PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+/// Background jobs are meant for queries that outlive `DEFAULT_QUERY_TIMEOUT`.
+const DEFAULT_JOB_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Start a query in the background and return its job immediately. Progress
+/// and the final status are emitted as `query-job` events; the result is kept
+/// until fetched with `get_job_result`.
+#[tauri::command]
+pub async fn submit_query_job(
+    app: AppHandle,
+    connection_id: String,
+    sql: String,
+    timeout_secs: Option<u64>,
+    max_rows: Option<usize>,
+    max_cell_size: Option<usize>,
+    confirm_token: Option<String>,
+    pool_manager: State<'_, PoolManager>,
+    job_manager: State<'_, JobManager>,
+    guardrails: State<'_, Guardrails>,
+) -> Result<JobInfo, AppError> {
+    let handle = pool_manager.get(&connection_id).await?;
+    guardrails
+        .check_query(&connection_id, handle.base(), &sql, confirm_token.as_deref())
+        .await?;
+    let duration = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_JOB_TIMEOUT);
+
+    let job = job_manager.start(&connection_id, &sql);
+    app.emit(JOB_EVENT, &job).ok();
+
+    let job_id = job.id.clone();
+    tauri::async_runtime::spawn(async move {
+        let job_manager = app.state::<JobManager>();
+        let cancel_registry = app.state::<CancellationRegistry>();
+
+        let run = run_with_cancel(
+            &handle,
+            &connection_id,
+            Some(&job_id),
+            duration,
+            &cancel_registry,
+            handle.base().execute_with_messages(&sql, Vec::new()),
+        );
+        tokio::pin!(run);
+
+        let mut ticker = tokio::time::interval(JOB_PROGRESS_INTERVAL);
+        ticker.tick().await;
+        let mut result = loop {
+            tokio::select! {
+                result = &mut run => break result,
+                _ = ticker.tick() => {
+                    if let Some(info) = job_manager.get(&job_id) {
+                        app.emit(JOB_EVENT, &info).ok();
+                    }
+                }
+            }
+        };
+        audit_if_write(
+            &app.state::<AuditLog>(),
+            &connection_id,
+            "submit_query_job",
+            handle.base().dialect_hint(),
+            &sql,
+            result.as_ref().map(|response| response.affected_rows),
+        );
+
+        match &mut result {
+            Ok(response) => {
+                apply_result_limits(&connection_id, response, max_rows, max_cell_size);
+                info!(
+                    "Job '{}' on '{}' completed in {}ms ({} rows)",
+                    job_id, connection_id, response.execution_time_ms, response.row_count
+                );
+            }
+            Err(e) => error!("Job '{}' failed on '{}': {}", job_id, connection_id, e),
+        }
+        if let Some(info) = job_manager.finish(&job_id, result) {
+            app.emit(JOB_EVENT, &info).ok();
+        }
+    });
+
+    Ok(job)
+}
+
+#[tauri::command]
+pub async fn list_jobs(job_manager: State<'_, JobManager>) -> Result<Vec<JobInfo>, AppError> {
+    Ok(job_manager.list())
+}
+
+/// Return a finished job's result (or its error) and forget the job.
+#[tauri::command]
+pub async fn get_job_result(
+    job_id: String,
+    job_manager: State<'_, JobManager>,
+) -> Result<QueryResponse, AppError> {
+    debug!("Fetching result of job '{}'", job_id);
+    job_manager.take_result(&job_id)
+}
+
+/// Cancel a running job, or discard a finished job's unfetched result.
+/// Returns true if a running query was cancelled.
+#[tauri::command]
+pub async fn cancel_job(
+    job_id: String,
+    job_manager: State<'_, JobManager>,
+    cancel_registry: State<'_, CancellationRegistry>,
+) -> Result<bool, AppError> {
+    if job_manager.is_running(&job_id) {
+        info!("Cancelling job '{}'", job_id);
+        return Ok(cancel_registry.cancel(&job_id));
+    }
+    job_manager.remove(&job_id)?;
+    debug!("Discarded job '{}'", job_id);
+    Ok(false)
+}
@@ -1,7 +1,18 @@
 use tauri::State;
 
+use crate::db::audit::{AuditDetails, AuditLog};
 use crate::db::pool::PoolManager;
 use crate::error::AppError;
+use crate::models::query::CellValue;
+
+/// Audit-log form of a `get_value` result; structured values keep their JSON.
+fn value_to_cell(value: serde_json::Value) -> CellValue {
+    match value {
+        serde_json::Value::Null => CellValue::Null,
+        serde_json::Value::String(s) => CellValue::Text(s),
+        other => CellValue::Json(other.to_string()),
+    }
+}
 
 #[tauri::command]
 pub async fn get_value(
@@ -21,10 +32,25 @@ pub async fn set_value(
     value: String,
     ttl: Option<u64>,
     pool_manager: State<'_, PoolManager>,
+    audit: State<'_, AuditLog>,
 ) -> Result<(), AppError> {
     let handle = pool_manager.get(&connection_id).await?;
     let driver = handle.as_keyvalue()?;
-    driver.set_value(&key, &value, ttl).await
+    // Missing keys read as null, which is also what the old value was.
+    let old_value = driver.get_value(&key).await.ok().map(value_to_cell);
+    let result = driver.set_value(&key, &value, ttl).await;
+    audit.record(
+        &connection_id,
+        "set_value",
+        AuditDetails {
+            target: Some(key),
+            statement: ttl.map(|ttl| format!("TTL {}", ttl)),
+            old_value,
+            new_value: Some(CellValue::Text(value)),
+        },
+        result.as_ref().map(|_| Some(1)),
+    );
+    result
 }
 
 #[tauri::command]
@@ -32,10 +58,22 @@ pub async fn delete_keys(
     connection_id: String,
     keys: Vec<String>,
     pool_manager: State<'_, PoolManager>,
+    audit: State<'_, AuditLog>,
 ) -> Result<u64, AppError> {
     let handle = pool_manager.get(&connection_id).await?;
     let driver = handle.as_keyvalue()?;
-    driver.delete_keys(keys).await
+    let target = keys.join(", ");
+    let result = driver.delete_keys(keys).await;
+    audit.record(
+        &connection_id,
+        "delete_keys",
+        AuditDetails {
+            target: Some(target),
+            ..Default::default()
+        },
+        result.as_ref().map(|n| Some(*n)),
+    );
+    result
 }
 
 #[tauri::command]
@@ -1,11 +1,16 @@
+pub mod audit;
 pub mod backup;
+pub mod benchmark;
 pub mod connection;
 pub mod document;
 pub mod dump;
 pub mod export;
 pub mod graph;
+pub mod history;
+pub mod jobs;
 pub mod keychain;
 pub mod keyvalue;
 pub mod query;
+pub mod result_sets;
 pub mod schema;
 pub mod transaction;
@@ -1,60 +1,180 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
+use futures::stream::{self, StreamExt};
 use log::{debug, error, info, warn};
+use tauri::ipc::Channel;
 use tauri::State;
 use tokio::time::timeout;
+use uuid::Uuid;
 
-use crate::commands::schema::{build_order_by, quote_ident};
-use crate::db::cancel::CancellationRegistry;
+use crate::commands::schema::build_order_by;
+use crate::db::audit::{AuditDetails, AuditLog};
+use crate::db::cancel::{with_query_id, CancellationRegistry};
+use crate::db::guardrails::Guardrails;
+use crate::db::handle::DriverHandle;
+use crate::db::history::QueryHistory;
+use crate::db::keyset::quote_ident;
+use crate::db::multi;
 use crate::db::pool::PoolManager;
+use crate::db::read_only::is_write;
+use crate::db::statement::{self, analyze, split_script, statement_end, StatementKind};
+use crate::db::variables;
 use crate::error::AppError;
-use crate::models::query::{CellValue, QueryResponse, SortColumn};
+use crate::models::plan::QueryPlan;
+use crate::models::query::{
+    CellValue, MultiQueryResponse, QueryBatch, QueryParameter, QueryResponse, QueryStreamEvent,
+    ScriptEvent, ScriptStatementResult, ScriptSummary, SortColumn, SourceSpan, StatementError,
+    StreamSummary,
+};
 
 const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(30);
 
 const DEFAULT_MAX_ROWS: usize = 10_000;
 
+const DEFAULT_STREAM_BATCH_SIZE: usize = 500;
+
+/// Connections queried at once by `execute_query_multi` unless overridden.
+const DEFAULT_MULTI_CONCURRENCY: usize = 8;
+
+/// How long to wait for a statement to unwind after a server-side cancel.
+const SERVER_CANCEL_GRACE: Duration = Duration::from_secs(5);
+
 // === Helpers ===
 
-/// Check if a SQL query is paginatable (SELECT, WITH, TABLE, VALUES).
-fn is_paginatable_query(sql: &str) -> bool {
-    let trimmed = sql.trim();
-    let upper = trimmed.to_uppercase();
-    upper.starts_with("SELECT")
-        || upper.starts_with("WITH")
-        || upper.starts_with("TABLE")
-        || upper.starts_with("VALUES")
+/// Row count stored in query history: rows returned, or rows affected for
+/// statements without a result set.
+fn history_row_count(response: &QueryResponse) -> Option<u64> {
+    if response.columns.is_empty() {
+        response.affected_rows
+    } else {
+        Some(response.row_count as u64)
+    }
+}
+
+/// Apply the grid's sort columns to a query. If the query already limits its
+/// rows the sort has to happen outside it; otherwise any top-level ORDER BY is
+/// replaced.
+fn apply_sort(sql: &str, order_clause: &str, dialect: &str) -> String {
+    let body = &sql[..statement_end(sql, dialect)];
+    let info = analyze(body, dialect);
+    if info.has_limit {
+        format!("SELECT * FROM ({}) AS _df_sorted{}", body, order_clause)
+    } else if let Some(pos) = info.order_by_start {
+        format!("{}{}", body[..pos].trim_end(), order_clause)
+    } else {
+        format!("{}{}", body, order_clause)
+    }
 }
 
 /// Wrap a user SQL query with LIMIT/OFFSET for pagination.
 /// MSSQL uses OFFSET...FETCH NEXT syntax; others use LIMIT...OFFSET.
 fn wrap_paginated(sql: &str, limit: i64, offset: i64, dialect: &str) -> String {
-    let trimmed = sql.trim().trim_end_matches(';');
+    let body = &sql[..statement_end(sql, dialect)];
     if dialect == "mssql" {
-        // MSSQL requires ORDER BY for OFFSET...FETCH. If none present, add ORDER BY (SELECT NULL).
-        let upper = trimmed.to_uppercase();
-        if upper.contains("ORDER BY") {
+        // OFFSET...FETCH needs a top-level ORDER BY and cannot be combined with
+        // TOP or an existing OFFSET, so limited queries are paged as a subquery.
+        // A CTE cannot be nested in a subquery, so only the statement after
+        // the WITH clause is wrapped.
+        let info = analyze(body, dialect);
+        if info.has_limit && info.has_cte {
+            format!(
+                "{} SELECT * FROM ({}) AS _df_page ORDER BY (SELECT NULL) OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+                body[..info.main_start].trim_end(),
+                &body[info.main_start..],
+                offset,
+                limit
+            )
+        } else if info.has_limit {
+            format!(
+                "SELECT * FROM ({}) AS _df_page ORDER BY (SELECT NULL) OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+                body, offset, limit
+            )
+        } else if info.has_order_by {
             format!(
                 "{} OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
-                trimmed, offset, limit
+                body, offset, limit
             )
         } else {
             format!(
                 "{} ORDER BY (SELECT NULL) OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
-                trimmed, offset, limit
+                body, offset, limit
             )
         }
     } else {
         format!(
             "SELECT * FROM ({}) AS _df_page LIMIT {} OFFSET {}",
-            trimmed, limit, offset
+            body, limit, offset
         )
     }
 }
 
-/// Truncate large cell values in a response to save bandwidth.
-fn truncate_large_values(response: &mut QueryResponse, max_cell_size: usize) {
-    for row in response.rows.iter_mut() {
+/// Await a driver call under the query timeout while honouring `cancel_query`.
+/// On cancel or timeout the driver is asked to abort the statement server-side
+/// and given a short grace period to wind it down before the error is returned.
+pub(crate) async fn run_with_cancel<T>(
+    handle: &DriverHandle,
+    connection_id: &str,
+    query_id: Option<&str>,
+    duration: Duration,
+    cancel_registry: &CancellationRegistry,
+    fut: impl Future<Output = Result<T, AppError>>,
+) -> Result<T, AppError> {
+    // Statements are always tracked so a timeout can cancel them server-side,
+    // even when the frontend did not supply a query id.
+    let tracking_id = query_id
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let query = with_query_id(tracking_id.clone(), fut);
+    tokio::pin!(query);
+
+    let cancel_rx = query_id.map(|qid| cancel_registry.register(qid.to_string()));
+    let cancelled = async move {
+        if let Some(rx) = cancel_rx {
+            if rx.await.is_ok() {
+                return;
+            }
+        }
+        std::future::pending::<()>().await
+    };
+
+    let err = tokio::select! {
+        res = &mut query => {
+            if let Some(qid) = query_id {
+                cancel_registry.remove(qid);
+            }
+            return res;
+        }
+        _ = tokio::time::sleep(duration) => {
+            error!("Query timed out after {}s on '{}'", duration.as_secs(), connection_id);
+            AppError::QueryTimeout(duration.as_secs())
+        }
+        _ = cancelled => {
+            warn!("Query '{}' cancelled on '{}'", tracking_id, connection_id);
+            AppError::QueryCancelled
+        }
+    };
+
+    if let Some(qid) = query_id {
+        cancel_registry.remove(qid);
+    }
+    match handle.base().cancel(&tracking_id).await {
+        Ok(true) => {
+            debug!("Server-side cancel sent for '{}' on '{}'", tracking_id, connection_id);
+            let _ = timeout(SERVER_CANCEL_GRACE, &mut query).await;
+        }
+        Ok(false) => {}
+        Err(e) => warn!("Server-side cancel failed on '{}': {}", connection_id, e),
+    }
+    Err(err)
+}
+
+/// Truncate large cell values in a set of rows to save bandwidth.
+fn truncate_large_values(rows: &mut [Vec<CellValue>], max_cell_size: usize) {
+    for row in rows.iter_mut() {
         for cell in row.iter_mut() {
             match cell {
                 CellValue::Text(ref v) if v.len() > max_cell_size => {
@@ -87,6 +207,63 @@ fn truncate_large_values(response: &mut QueryResponse, max_cell_size: usize) {
     }
 }
 
+/// Cap the row count at `max_rows` (default `DEFAULT_MAX_ROWS`) and truncate
+/// oversized cell values.
+pub(crate) fn apply_result_limits(
+    connection_id: &str,
+    response: &mut QueryResponse,
+    max_rows: Option<usize>,
+    max_cell_size: Option<usize>,
+) {
+    let limit = max_rows.unwrap_or(DEFAULT_MAX_ROWS);
+    if response.rows.len() > limit {
+        info!(
+            "Query on '{}' returned {} rows, truncating to {} (limit)",
+            connection_id, response.rows.len(), limit
+        );
+        response.rows.truncate(limit);
+        response.truncated = true;
+        response.max_rows_limit = Some(limit);
+        response.row_count = limit;
+    }
+    if let Some(mcs) = max_cell_size {
+        truncate_large_values(&mut response.rows, mcs);
+    }
+}
+
+/// Record `statement` in the audit log if it writes. `outcome` is the affected
+/// row count or the error the statement failed with.
+pub(crate) fn audit_if_write(
+    audit: &AuditLog,
+    connection_id: &str,
+    operation: &str,
+    dialect: &str,
+    statement: &str,
+    outcome: Result<Option<u64>, &AppError>,
+) {
+    if is_write(statement, dialect) {
+        audit.record(
+            connection_id,
+            operation,
+            AuditDetails {
+                statement: Some(statement.to_string()),
+                ..Default::default()
+            },
+            outcome,
+        );
+    }
+}
+
+/// Map a byte range of `sql` to the span reported for a script statement.
+fn source_span(sql: &str, range: &Range<usize>) -> SourceSpan {
+    let start = sql[..range.start].encode_utf16().count();
+    SourceSpan {
+        start,
+        end: start + sql[range.clone()].encode_utf16().count(),
+        line: sql[..range.start].matches('\n').count() + 1,
+    }
+}
+
 // === Commands ===
 
 #[tauri::command]
@@ -97,10 +274,17 @@ pub async fn execute_query(
     query_id: Option<String>,
     max_rows: Option<usize>,
     max_cell_size: Option<usize>,
+    confirm_token: Option<String>,
     pool_manager: State<'_, PoolManager>,
     cancel_registry: State<'_, CancellationRegistry>,
+    guardrails: State<'_, Guardrails>,
+    history: State<'_, QueryHistory>,
+    audit: State<'_, AuditLog>,
 ) -> Result<QueryResponse, AppError> {
     let handle = pool_manager.get(&connection_id).await?;
+    guardrails
+        .check_query(&connection_id, handle.base(), &sql, confirm_token.as_deref())
+        .await?;
     let duration = timeout_secs
         .map(Duration::from_secs)
         .unwrap_or(DEFAULT_QUERY_TIMEOUT);
@@ -112,63 +296,603 @@ pub async fn execute_query(
     };
     debug!("Executing query on '{}': {}", connection_id, log_sql);
 
-    let query_future = timeout(duration, handle.base().execute_raw(&sql));
+    let started = Instant::now();
+    let result = run_with_cancel(
+        &handle,
+        &connection_id,
+        query_id.as_deref(),
+        duration,
+        &cancel_registry,
+        handle.base().execute_with_messages(&sql, Vec::new()),
+    )
+    .await;
+    history.record(
+        &connection_id,
+        &sql,
+        started.elapsed(),
+        result.as_ref().map(history_row_count),
+    )
+    .await;
+    audit_if_write(
+        &audit,
+        &connection_id,
+        "execute_query",
+        handle.base().dialect_hint(),
+        &sql,
+        result.as_ref().map(|response| response.affected_rows),
+    );
 
-    let result = if let Some(ref qid) = query_id {
-        let cancel_rx = cancel_registry.register(qid.clone());
+    match result {
+        Ok(mut response) => {
+            apply_result_limits(&connection_id, &mut response, max_rows, max_cell_size);
+            info!(
+                "Query on '{}' completed in {}ms ({} rows)",
+                connection_id, response.execution_time_ms, response.row_count
+            );
+            Ok(response)
+        }
+        Err(e) => {
+            error!("Query failed on '{}': {}", connection_id, e);
+            Err(e)
+        }
+    }
+}
 
-        let outcome = tokio::select! {
-            res = query_future => {
-                cancel_registry.remove(qid);
-                res.map_err(|_| {
-                    error!("Query timed out after {}s on '{}'", duration.as_secs(), connection_id);
-                    AppError::QueryTimeout(duration.as_secs())
-                })?
+/// Run one query on several connections concurrently, at most
+/// `max_concurrency` at a time. A failing connection is reported in its own
+/// result and never stops the others. With `merge`, rows of every successful
+/// connection are combined behind a `_connection` column. The timeout and
+/// `max_rows` apply per connection.
+#[tauri::command]
+pub async fn execute_query_multi(
+    connection_ids: Vec<String>,
+    sql: String,
+    merge: Option<bool>,
+    max_concurrency: Option<usize>,
+    timeout_secs: Option<u64>,
+    max_rows: Option<usize>,
+    max_cell_size: Option<usize>,
+    confirm_tokens: Option<HashMap<String, String>>,
+    pool_manager: State<'_, PoolManager>,
+    cancel_registry: State<'_, CancellationRegistry>,
+    guardrails: State<'_, Guardrails>,
+    audit: State<'_, AuditLog>,
+) -> Result<MultiQueryResponse, AppError> {
+    let duration = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_QUERY_TIMEOUT);
+    let concurrency = max_concurrency.unwrap_or(DEFAULT_MULTI_CONCURRENCY).max(1);
+    let confirm_tokens = confirm_tokens.unwrap_or_default();
+    info!(
+        "Executing query on {} connections (concurrency {})",
+        connection_ids.len(),
+        concurrency
+    );
+
+    let (sql, confirm_tokens) = (&sql, &confirm_tokens);
+    let (pool_manager, cancel_registry, guardrails, audit) =
+        (&*pool_manager, &*cancel_registry, &*guardrails, &*audit);
+    let started = Instant::now();
+    let mut results: Vec<_> = stream::iter(connection_ids)
+        .map(|connection_id| async move {
+            let started = Instant::now();
+            let outcome = async {
+                let handle = pool_manager.get(&connection_id).await?;
+                let token = confirm_tokens.get(&connection_id).map(String::as_str);
+                guardrails
+                    .check_query(&connection_id, handle.base(), sql, token)
+                    .await?;
+                let result = run_with_cancel(
+                    &handle,
+                    &connection_id,
+                    None,
+                    duration,
+                    cancel_registry,
+                    handle.base().execute_with_messages(sql, Vec::new()),
+                )
+                .await;
+                audit_if_write(
+                    audit,
+                    &connection_id,
+                    "execute_query_multi",
+                    handle.base().dialect_hint(),
+                    sql,
+                    result.as_ref().map(|response| response.affected_rows),
+                );
+                let mut response = result?;
+                apply_result_limits(&connection_id, &mut response, max_rows, max_cell_size);
+                Ok(response)
             }
-            _ = cancel_rx => {
-                warn!("Query '{}' cancelled on '{}'", qid, connection_id);
-                return Err(AppError::QueryCancelled);
+            .await;
+            if let Err(e) = &outcome {
+                warn!("Query failed on '{}': {}", connection_id, e);
             }
-        };
-        outcome
-    } else {
-        query_future
-            .await
-            .map_err(|_| {
-                error!("Query timed out after {}s on '{}'", duration.as_secs(), connection_id);
-                AppError::QueryTimeout(duration.as_secs())
-            })?
+            multi::connection_result(connection_id, outcome, started.elapsed())
+        })
+        .buffered(concurrency)
+        .collect()
+        .await;
+
+    let merged = merge.unwrap_or(false).then(|| multi::merge(&mut results));
+    // Counted after merging, which fails results with mismatched columns.
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    info!(
+        "Multi-connection query finished: {} succeeded, {} failed",
+        results.len() - failed,
+        failed
+    );
+    Ok(MultiQueryResponse {
+        merged,
+        succeeded: results.len() - failed,
+        failed,
+        results,
+        execution_time_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Execute a query and push its rows to the frontend in batches over `on_event`
+/// instead of buffering the whole result set. Fetching stops at `max_rows`.
+#[tauri::command]
+pub async fn execute_query_stream(
+    connection_id: String,
+    sql: String,
+    timeout_secs: Option<u64>,
+    query_id: Option<String>,
+    max_rows: Option<usize>,
+    batch_size: Option<usize>,
+    max_cell_size: Option<usize>,
+    confirm_token: Option<String>,
+    on_event: Channel<QueryStreamEvent>,
+    pool_manager: State<'_, PoolManager>,
+    cancel_registry: State<'_, CancellationRegistry>,
+    guardrails: State<'_, Guardrails>,
+    audit: State<'_, AuditLog>,
+) -> Result<StreamSummary, AppError> {
+    let handle = pool_manager.get(&connection_id).await?;
+    guardrails
+        .check_query(&connection_id, handle.base(), &sql, confirm_token.as_deref())
+        .await?;
+    let duration = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_QUERY_TIMEOUT);
+    let limit = max_rows.unwrap_or(DEFAULT_MAX_ROWS);
+    let batch_size = batch_size.unwrap_or(DEFAULT_STREAM_BATCH_SIZE);
+
+    debug!(
+        "Streaming query on '{}' (batch size {}, limit {})",
+        connection_id, batch_size, limit
+    );
+
+    let sink = |mut batch: QueryBatch| {
+        if let Some(mcs) = max_cell_size {
+            truncate_large_values(&mut batch.rows, mcs);
+        }
+        on_event
+            .send(QueryStreamEvent::Batch(batch))
+            .map_err(|e| AppError::Serialization(e.to_string()))
     };
 
+    let result = run_with_cancel(
+        &handle,
+        &connection_id,
+        query_id.as_deref(),
+        duration,
+        &cancel_registry,
+        handle.base().execute_stream(&sql, batch_size, limit, &sink),
+    )
+    .await;
+    audit_if_write(
+        &audit,
+        &connection_id,
+        "execute_query_stream",
+        handle.base().dialect_hint(),
+        &sql,
+        result.as_ref().map(|summary| summary.affected_rows),
+    );
+
     match result {
-        Ok(mut response) => {
-            let limit = max_rows.unwrap_or(DEFAULT_MAX_ROWS);
-            if response.rows.len() > limit {
+        Ok(summary) => {
+            if summary.truncated {
                 info!(
-                    "Query on '{}' returned {} rows, truncating to {} (limit)",
-                    connection_id, response.rows.len(), limit
+                    "Streamed query on '{}' stopped at {} rows (limit)",
+                    connection_id, limit
                 );
-                response.rows.truncate(limit);
-                response.truncated = true;
-                response.max_rows_limit = Some(limit);
-                response.row_count = limit;
             }
-            if let Some(mcs) = max_cell_size {
-                truncate_large_values(&mut response, mcs);
+            info!(
+                "Streamed query on '{}' completed in {}ms ({} rows)",
+                connection_id, summary.execution_time_ms, summary.row_count
+            );
+            on_event
+                .send(QueryStreamEvent::Finished(summary.clone()))
+                .map_err(|e| AppError::Serialization(e.to_string()))?;
+            Ok(summary)
+        }
+        Err(e) => {
+            error!("Streamed query failed on '{}': {}", connection_id, e);
+            Err(e)
+        }
+    }
+}
+
+/// Execute a query and return every result set it produces, for stored
+/// procedure calls (`EXEC`, `CALL`) and batches that select more than once.
+/// Row and cell limits apply to each result set separately.
+#[tauri::command]
+pub async fn execute_query_result_sets(
+    connection_id: String,
+    sql: String,
+    timeout_secs: Option<u64>,
+    query_id: Option<String>,
+    max_rows: Option<usize>,
+    max_cell_size: Option<usize>,
+    confirm_token: Option<String>,
+    pool_manager: State<'_, PoolManager>,
+    cancel_registry: State<'_, CancellationRegistry>,
+    guardrails: State<'_, Guardrails>,
+    audit: State<'_, AuditLog>,
+) -> Result<Vec<QueryResponse>, AppError> {
+    let handle = pool_manager.get(&connection_id).await?;
+    guardrails
+        .check_query(&connection_id, handle.base(), &sql, confirm_token.as_deref())
+        .await?;
+    let duration = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_QUERY_TIMEOUT);
+
+    debug!("Executing multi-result query on '{}'", connection_id);
+
+    let result = run_with_cancel(
+        &handle,
+        &connection_id,
+        query_id.as_deref(),
+        duration,
+        &cancel_registry,
+        handle.base().execute_result_sets(&sql),
+    )
+    .await;
+    audit_if_write(
+        &audit,
+        &connection_id,
+        "execute_query_result_sets",
+        handle.base().dialect_hint(),
+        &sql,
+        result.as_ref().map(|responses| {
+            responses
+                .iter()
+                .filter_map(|response| response.affected_rows)
+                .reduce(|a, b| a + b)
+        }),
+    );
+
+    match result {
+        Ok(mut responses) => {
+            for response in responses.iter_mut() {
+                apply_result_limits(&connection_id, response, max_rows, max_cell_size);
             }
             info!(
-                "Query on '{}' completed in {}ms ({} rows)",
+                "Multi-result query on '{}' returned {} result set(s)",
+                connection_id,
+                responses.len()
+            );
+            Ok(responses)
+        }
+        Err(e) => {
+            error!("Multi-result query failed on '{}': {}", connection_id, e);
+            Err(e)
+        }
+    }
+}
+
+/// Explain a query with the engine's own EXPLAIN and return the parsed plan
+/// tree. With `analyze` the statement is executed for actual row counts and
+/// timings where the engine supports it; its changes are rolled back. Since
+/// it really runs, it goes through the guardrails, and transaction control
+/// that could commit it before the rollback is refused.
+#[tauri::command]
+pub async fn explain_query(
+    connection_id: String,
+    sql: String,
+    analyze: Option<bool>,
+    timeout_secs: Option<u64>,
+    query_id: Option<String>,
+    confirm_token: Option<String>,
+    pool_manager: State<'_, PoolManager>,
+    cancel_registry: State<'_, CancellationRegistry>,
+    guardrails: State<'_, Guardrails>,
+) -> Result<QueryPlan, AppError> {
+    let handle = pool_manager.get(&connection_id).await?;
+    let analyze = analyze.unwrap_or(false);
+    if analyze {
+        let dialect = handle.base().dialect_hint();
+        // `analyze` is shadowed by the flag here.
+        let controls_transaction = split_script(&sql, dialect)
+            .into_iter()
+            .any(|range| statement::analyze(&sql[range], dialect).kind == StatementKind::Transaction);
+        if controls_transaction {
+            return Err(AppError::InvalidConfig(
+                "EXPLAIN ANALYZE cannot run transaction control statements".to_string(),
+            ));
+        }
+        guardrails
+            .check_query(&connection_id, handle.base(), &sql, confirm_token.as_deref())
+            .await?;
+    }
+    let duration = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_QUERY_TIMEOUT);
+
+    debug!("Explaining query on '{}' (analyze: {})", connection_id, analyze);
+
+    let result = run_with_cancel(
+        &handle,
+        &connection_id,
+        query_id.as_deref(),
+        duration,
+        &cancel_registry,
+        handle.base().explain(&sql, analyze),
+    )
+    .await;
+
+    if let Err(e) = &result {
+        error!("Explain failed on '{}': {}", connection_id, e);
+    }
+    result
+}
+
+/// Execute a query with bind parameters. Placeholders use the driver's native
+/// syntax (`$1` on PostgreSQL, `@P1` on SQL Server, `?` elsewhere).
+#[tauri::command]
+pub async fn execute_query_params(
+    connection_id: String,
+    sql: String,
+    params: Vec<CellValue>,
+    timeout_secs: Option<u64>,
+    query_id: Option<String>,
+    max_rows: Option<usize>,
+    max_cell_size: Option<usize>,
+    confirm_token: Option<String>,
+    pool_manager: State<'_, PoolManager>,
+    cancel_registry: State<'_, CancellationRegistry>,
+    guardrails: State<'_, Guardrails>,
+    audit: State<'_, AuditLog>,
+) -> Result<QueryResponse, AppError> {
+    let handle = pool_manager.get(&connection_id).await?;
+    guardrails
+        .check_query(&connection_id, handle.base(), &sql, confirm_token.as_deref())
+        .await?;
+    let duration = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_QUERY_TIMEOUT);
+
+    debug!(
+        "Executing parameterized query on '{}' ({} params)",
+        connection_id,
+        params.len()
+    );
+
+    let result = run_with_cancel(
+        &handle,
+        &connection_id,
+        query_id.as_deref(),
+        duration,
+        &cancel_registry,
+        handle.base().execute_with_messages(&sql, params),
+    )
+    .await;
+    audit_if_write(
+        &audit,
+        &connection_id,
+        "execute_query_params",
+        handle.base().dialect_hint(),
+        &sql,
+        result.as_ref().map(|response| response.affected_rows),
+    );
+
+    match result {
+        Ok(mut response) => {
+            apply_result_limits(&connection_id, &mut response, max_rows, max_cell_size);
+            info!(
+                "Parameterized query on '{}' completed in {}ms ({} rows)",
                 connection_id, response.execution_time_ms, response.row_count
             );
             Ok(response)
         }
         Err(e) => {
-            error!("Query failed on '{}': {}", connection_id, e);
+            error!("Parameterized query failed on '{}': {}", connection_id, e);
             Err(e)
         }
     }
 }
 
+/// List the `:name` parameters and `${name}` variables in a query, using the
+/// lexical rules of the connection's dialect.
+#[tauri::command]
+pub async fn describe_parameters(
+    connection_id: String,
+    sql: String,
+    pool_manager: State<'_, PoolManager>,
+) -> Result<Vec<QueryParameter>, AppError> {
+    let handle = pool_manager.get(&connection_id).await?;
+    Ok(variables::describe(&sql, handle.base().dialect_hint()))
+}
+
+/// Execute a query with named placeholders (see `db::variables`). `:name`
+/// values are bound natively; `${name}` variables are substituted as quoted
+/// identifiers.
+#[tauri::command]
+pub async fn execute_query_named(
+    connection_id: String,
+    sql: String,
+    values: HashMap<String, CellValue>,
+    timeout_secs: Option<u64>,
+    query_id: Option<String>,
+    max_rows: Option<usize>,
+    max_cell_size: Option<usize>,
+    confirm_token: Option<String>,
+    pool_manager: State<'_, PoolManager>,
+    cancel_registry: State<'_, CancellationRegistry>,
+    guardrails: State<'_, Guardrails>,
+    audit: State<'_, AuditLog>,
+) -> Result<QueryResponse, AppError> {
+    let handle = pool_manager.get(&connection_id).await?;
+    let (sql, params) = variables::bind(&sql, handle.base().dialect_hint(), &values)?;
+    guardrails
+        .check_query(&connection_id, handle.base(), &sql, confirm_token.as_deref())
+        .await?;
+    let duration = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_QUERY_TIMEOUT);
+
+    debug!(
+        "Executing named-parameter query on '{}' ({} params)",
+        connection_id,
+        params.len()
+    );
+
+    let result = run_with_cancel(
+        &handle,
+        &connection_id,
+        query_id.as_deref(),
+        duration,
+        &cancel_registry,
+        handle.base().execute_with_messages(&sql, params),
+    )
+    .await;
+    audit_if_write(
+        &audit,
+        &connection_id,
+        "execute_query_named",
+        handle.base().dialect_hint(),
+        &sql,
+        result.as_ref().map(|response| response.affected_rows),
+    );
+
+    match result {
+        Ok(mut response) => {
+            apply_result_limits(&connection_id, &mut response, max_rows, max_cell_size);
+            info!(
+                "Named-parameter query on '{}' completed in {}ms ({} rows)",
+                connection_id, response.execution_time_ms, response.row_count
+            );
+            Ok(response)
+        }
+        Err(e) => {
+            error!("Named-parameter query failed on '{}': {}", connection_id, e);
+            Err(e)
+        }
+    }
+}
+
+/// Split a script into statements (see `db::statement::split_script`) and run
+/// them in order on one session, sending each statement's result over
+/// `on_event` as it completes. Unless `continue_on_error` is set the script
+/// stops at the first failing statement. The timeout covers the whole script.
+#[tauri::command]
+pub async fn execute_script(
+    connection_id: String,
+    sql: String,
+    continue_on_error: Option<bool>,
+    timeout_secs: Option<u64>,
+    query_id: Option<String>,
+    max_rows: Option<usize>,
+    max_cell_size: Option<usize>,
+    confirm_token: Option<String>,
+    on_event: Channel<ScriptEvent>,
+    pool_manager: State<'_, PoolManager>,
+    cancel_registry: State<'_, CancellationRegistry>,
+    guardrails: State<'_, Guardrails>,
+    audit: State<'_, AuditLog>,
+) -> Result<ScriptSummary, AppError> {
+    let handle = pool_manager.get(&connection_id).await?;
+    guardrails
+        .check_query(&connection_id, handle.base(), &sql, confirm_token.as_deref())
+        .await?;
+    let duration = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_QUERY_TIMEOUT);
+    let continue_on_error = continue_on_error.unwrap_or(false);
+
+    let dialect = handle.base().dialect_hint();
+    let ranges = split_script(&sql, dialect);
+    let statements: Vec<String> = ranges.iter().map(|r| sql[r.clone()].to_string()).collect();
+    debug!(
+        "Executing script on '{}' ({} statements)",
+        connection_id,
+        statements.len()
+    );
+
+    let start = Instant::now();
+    let succeeded = AtomicUsize::new(0);
+    let failed = AtomicUsize::new(0);
+    let sink = |index: usize, result: Result<QueryResponse, AppError>| {
+        // Each statement is audited on its own, as it completes.
+        audit_if_write(
+            &audit,
+            &connection_id,
+            "execute_script",
+            dialect,
+            &statements[index],
+            result.as_ref().map(|response| response.affected_rows),
+        );
+        let (response, error) = match result {
+            Ok(mut response) => {
+                apply_result_limits(&connection_id, &mut response, max_rows, max_cell_size);
+                succeeded.fetch_add(1, Ordering::Relaxed);
+                (Some(response), None)
+            }
+            Err(e) => {
+                warn!("Script statement {} failed on '{}': {}", index + 1, connection_id, e);
+                failed.fetch_add(1, Ordering::Relaxed);
+                let error = StatementError {
+                    code: e.error_code().to_string(),
+                    message: e.to_string(),
+                };
+                (None, Some(error))
+            }
+        };
+        let stop = error.is_some() && !continue_on_error;
+        let event = ScriptEvent::Statement(ScriptStatementResult {
+            index,
+            span: source_span(&sql, &ranges[index]),
+            response,
+            error,
+        });
+        on_event.send(event).is_ok() && !stop
+    };
+
+    run_with_cancel(
+        &handle,
+        &connection_id,
+        query_id.as_deref(),
+        duration,
+        &cancel_registry,
+        handle.base().execute_script(&statements, &sink),
+    )
+    .await
+    .map_err(|e| {
+        error!("Script failed on '{}': {}", connection_id, e);
+        e
+    })?;
+
+    let succeeded = succeeded.into_inner();
+    let failed = failed.into_inner();
+    let summary = ScriptSummary {
+        statement_count: statements.len(),
+        succeeded,
+        failed,
+        skipped: statements.len() - succeeded - failed,
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    };
+    info!(
+        "Script on '{}' completed in {}ms ({} ok, {} failed, {} skipped)",
+        connection_id, summary.execution_time_ms, summary.succeeded, summary.failed, summary.skipped
+    );
+    on_event
+        .send(ScriptEvent::Finished(summary.clone()))
+        .map_err(|e| AppError::Serialization(e.to_string()))?;
+    Ok(summary)
+}
+
 #[tauri::command]
 pub async fn execute_query_page(
     connection_id: String,
@@ -179,31 +903,35 @@ pub async fn execute_query_page(
     query_id: Option<String>,
     max_cell_size: Option<usize>,
     sort_columns: Option<Vec<SortColumn>>,
+    confirm_token: Option<String>,
     pool_manager: State<'_, PoolManager>,
     cancel_registry: State<'_, CancellationRegistry>,
+    guardrails: State<'_, Guardrails>,
+    history: State<'_, QueryHistory>,
+    audit: State<'_, AuditLog>,
 ) -> Result<QueryResponse, AppError> {
     let handle = pool_manager.get(&connection_id).await?;
+    // Statements that cannot be paged run as written, so they are checked like
+    // any other query.
+    guardrails
+        .check_query(&connection_id, handle.base(), &sql, confirm_token.as_deref())
+        .await?;
     let duration = timeout_secs
         .map(Duration::from_secs)
         .unwrap_or(DEFAULT_QUERY_TIMEOUT);
 
     let dialect = handle.base().dialect_hint();
-    let category = handle.base().category();
 
     // Build the paginated SQL
-    let paginated_sql = if is_paginatable_query(&sql) {
-        // If sort columns are provided, append ORDER BY before wrapping
-        if let Some(ref sorts) = sort_columns {
-            if !sorts.is_empty() {
-                let order_clause = build_order_by(sorts, &category);
-                let trimmed = sql.trim().trim_end_matches(';');
-                let with_order = format!("{}{}", trimmed, order_clause);
-                wrap_paginated(&with_order, limit, offset, dialect)
-            } else {
-                wrap_paginated(&sql, limit, offset, dialect)
+    let paginated_sql = if analyze(&sql, dialect).is_query() {
+        // If sort columns are provided, apply them before wrapping
+        match sort_columns {
+            Some(ref sorts) if !sorts.is_empty() => {
+                let order_clause = build_order_by(sorts, dialect);
+                let sorted = apply_sort(&sql, &order_clause, dialect);
+                wrap_paginated(&sorted, limit, offset, dialect)
             }
-        } else {
-            wrap_paginated(&sql, limit, offset, dialect)
+            _ => wrap_paginated(&sql, limit, offset, dialect),
         }
     } else {
         // Not paginatable — just execute as-is
@@ -215,38 +943,41 @@ pub async fn execute_query_page(
         connection_id, limit, offset
     );
 
-    let query_future = timeout(duration, handle.base().execute_raw(&paginated_sql));
-
-    let result = if let Some(ref qid) = query_id {
-        let cancel_rx = cancel_registry.register(qid.clone());
-
-        let outcome = tokio::select! {
-            res = query_future => {
-                cancel_registry.remove(qid);
-                res.map_err(|_| {
-                    error!("Paginated query timed out after {}s on '{}'", duration.as_secs(), connection_id);
-                    AppError::QueryTimeout(duration.as_secs())
-                })?
-            }
-            _ = cancel_rx => {
-                warn!("Query '{}' cancelled on '{}'", qid, connection_id);
-                return Err(AppError::QueryCancelled);
-            }
-        };
-        outcome
-    } else {
-        query_future
-            .await
-            .map_err(|_| {
-                error!("Paginated query timed out after {}s on '{}'", duration.as_secs(), connection_id);
-                AppError::QueryTimeout(duration.as_secs())
-            })?
-    };
+    let started = Instant::now();
+    let result = run_with_cancel(
+        &handle,
+        &connection_id,
+        query_id.as_deref(),
+        duration,
+        &cancel_registry,
+        handle.base().execute_with_messages(&paginated_sql, Vec::new()),
+    )
+    .await;
+    // Only statements that could not be paged can write; they ran as written.
+    audit_if_write(
+        &audit,
+        &connection_id,
+        "execute_query_page",
+        dialect,
+        &sql,
+        result.as_ref().map(|response| response.affected_rows),
+    );
+    // Later pages are the grid scrolling through the same statement; only the
+    // first fetch counts as running it.
+    if offset == 0 {
+        history.record(
+            &connection_id,
+            &sql,
+            started.elapsed(),
+            result.as_ref().map(history_row_count),
+        )
+        .await;
+    }
 
     match result {
         Ok(mut response) => {
             if let Some(mcs) = max_cell_size {
-                truncate_large_values(&mut response, mcs);
+                truncate_large_values(&mut response.rows, mcs);
             }
             info!(
                 "Paginated query on '{}' completed in {}ms ({} rows)",
@@ -268,15 +999,16 @@ pub async fn count_query_rows(
     pool_manager: State<'_, PoolManager>,
 ) -> Result<i64, AppError> {
     let handle = pool_manager.get(&connection_id).await?;
+    let dialect = handle.base().dialect_hint();
 
-    if !is_paginatable_query(&sql) {
+    if !analyze(&sql, dialect).is_query() {
         return Err(AppError::InvalidConfig(
             "Cannot count rows for non-SELECT queries".to_string(),
         ));
     }
 
-    let trimmed = sql.trim().trim_end_matches(';');
-    let count_sql = format!("SELECT COUNT(*) AS _df_count FROM ({}) AS _df_cnt", trimmed);
+    let body = &sql[..statement_end(&sql, dialect)];
+    let count_sql = format!("SELECT COUNT(*) AS _df_count FROM ({}) AS _df_cnt", body);
 
     debug!("Counting query rows on '{}'", connection_id);
 
@@ -313,10 +1045,9 @@ pub async fn fetch_full_cell(
 ) -> Result<CellValue, AppError> {
     let handle = pool_manager.get(&connection_id).await?;
     let dialect = handle.base().dialect_hint();
-    let category = handle.base().category();
 
-    let col_ident = quote_ident(&column, &category);
-    let trimmed = sql.trim().trim_end_matches(';');
+    let col_ident = quote_ident(dialect, &column);
+    let trimmed = &sql[..statement_end(&sql, dialect)];
 
     let fetch_sql = if dialect == "mssql" {
         format!(
@@ -352,6 +1083,9 @@ pub async fn fetch_full_cell(
     Ok(CellValue::Null)
 }
 
+/// Cancel the query running under `query_id` and abort it on the server. On
+/// SQL Server this kills the statement's session, which also rolls back any
+/// transaction open on it.
 #[tauri::command]
 pub async fn cancel_query(
     query_id: String,
@@ -360,3 +1094,38 @@ pub async fn cancel_query(
     info!("Cancelling query '{}'", query_id);
     Ok(cancel_registry.cancel(&query_id))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_paginated_mssql_appends_offset_fetch() {
+        assert_eq!(
+            wrap_paginated("SELECT * FROM t ORDER BY id", 50, 100, "mssql"),
+            "SELECT * FROM t ORDER BY id OFFSET 100 ROWS FETCH NEXT 50 ROWS ONLY"
+        );
+        assert_eq!(
+            wrap_paginated("SELECT TOP 10 * FROM t", 50, 0, "mssql"),
+            "SELECT * FROM (SELECT TOP 10 * FROM t) AS _df_page ORDER BY (SELECT NULL) OFFSET 0 ROWS FETCH NEXT 50 ROWS ONLY"
+        );
+    }
+
+    #[test]
+    fn test_wrap_paginated_mssql_pages_limited_cte() {
+        let sql = "WITH recent AS (SELECT TOP 5 * FROM orders ORDER BY placed_at DESC) SELECT TOP 3 * FROM recent";
+        assert_eq!(
+            wrap_paginated(sql, 2, 2, "mssql"),
+            "WITH recent AS (SELECT TOP 5 * FROM orders ORDER BY placed_at DESC) \
+             SELECT * FROM (SELECT TOP 3 * FROM recent) AS _df_page \
+             ORDER BY (SELECT NULL) OFFSET 2 ROWS FETCH NEXT 2 ROWS ONLY"
+        );
+
+        // TOP inside the CTE alone does not limit the outer select.
+        let sql = "WITH recent AS (SELECT TOP 5 * FROM orders ORDER BY placed_at DESC) SELECT * FROM recent";
+        assert_eq!(
+            wrap_paginated(sql, 50, 100, "mssql"),
+            format!("{} ORDER BY (SELECT NULL) OFFSET 100 ROWS FETCH NEXT 50 ROWS ONLY", sql)
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use log::info;
+use tauri::State;
+
+use crate::commands::query::apply_result_limits;
+use crate::db::result_sets::ResultSetStore;
+use crate::error::AppError;
+use crate::models::query::QueryResponse;
+
+/// Keep a fetched result as the local table `result_id` for
+/// `query_result_set`. Storing under an existing id replaces that result.
+#[tauri::command]
+pub async fn store_result_set(
+    result_id: String,
+    response: QueryResponse,
+    store: State<'_, ResultSetStore>,
+) -> Result<(), AppError> {
+    store.store(&result_id, &response).await
+}
+
+/// Run a SELECT over the stored results, which are tables named by their
+/// result ids, e.g. `SELECT status, count(*) FROM "orders" GROUP BY status`.
+/// Runs locally in SQLite; nothing is sent to a server.
+#[tauri::command]
+pub async fn query_result_set(
+    sql: String,
+    max_rows: Option<usize>,
+    max_cell_size: Option<usize>,
+    store: State<'_, ResultSetStore>,
+) -> Result<QueryResponse, AppError> {
+    let mut response = store.query(&sql).await?;
+    apply_result_limits("result sets", &mut response, max_rows, max_cell_size);
+    info!(
+        "Result set query returned {} rows in {}ms",
+        response.row_count, response.execution_time_ms
+    );
+    Ok(response)
+}
+
+/// Drop a stored result, e.g. when its tab is closed.
+#[tauri::command]
+pub async fn drop_result_set(
+    result_id: String,
+    store: State<'_, ResultSetStore>,
+) -> Result<(), AppError> {
+    store.remove(&result_id).await
+}
@@ -1,47 +1,38 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use tauri::State;
 use tokio::time::timeout;
 
-use crate::db::escape::escape_sql_literal;
+use crate::db::audit::{AuditDetails, AuditLog};
+use crate::db::handle::DriverHandle;
+use crate::db::history::QueryHistory;
+use crate::db::keyset::{named_key, primary_key, quote_ident, select_page, supports_keyset};
+use crate::db::params::{param_literal, ParamBuilder};
 use crate::db::pool::PoolManager;
+use crate::db::traits::SqlDriver;
 use crate::error::AppError;
 use crate::models::connection::DatabaseCategory;
-use crate::models::query::{FilterCondition, QueryResponse, SortColumn};
+use crate::models::query::{CellValue, FilterCondition, QueryResponse, SortColumn, TablePage};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, EnumInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo,
-    RoutineInfo, SchemaInfo, SequenceInfo, TableInfo, TableStats,
+    KeyColumn, RoutineInfo, SchemaInfo, SequenceInfo, TableInfo, TableStats,
 };
 
 const DEFAULT_DATA_TIMEOUT: Duration = Duration::from_secs(30);
 
 // === Helpers ===
 
-/// Quote an identifier based on database category.
-/// MSSQL uses [name], MySQL uses `name`, everything else uses "name".
-pub(crate) fn quote_ident(name: &str, category: &DatabaseCategory) -> String {
-    match category {
-        DatabaseCategory::Relational => {
-            // We can't distinguish MSSQL from PG here by category alone,
-            // so we use double quotes which work for PG, SQLite, CockroachDB, Redshift.
-            // MSSQL also supports double-quoted identifiers when QUOTED_IDENTIFIER is ON (default).
-            format!("\"{}\"", name.replace('"', "\"\""))
-        }
-        DatabaseCategory::Analytics => {
-            // ClickHouse uses double quotes
-            format!("\"{}\"", name.replace('"', "\"\""))
-        }
-        DatabaseCategory::WideColumn => {
-            // Cassandra/ScyllaDB use double quotes
-            format!("\"{}\"", name.replace('"', "\"\""))
-        }
-        _ => format!("\"{}\"", name.replace('"', "\"\"")),
-    }
-}
-
-/// Build a WHERE clause from filter conditions.
-fn build_where_clause(filters: &[FilterCondition], category: &DatabaseCategory) -> String {
+/// Build a WHERE clause from filter conditions. Filter values are added to
+/// `params` as bind parameters; `column_types` (only populated for PostgreSQL)
+/// supplies the casts needed to compare text binds against typed columns.
+fn build_where_clause(
+    filters: &[FilterCondition],
+    dialect: &str,
+    params: &mut ParamBuilder,
+    column_types: &HashMap<String, String>,
+) -> String {
     if filters.is_empty() {
         return String::new();
     }
@@ -49,23 +40,25 @@ fn build_where_clause(filters: &[FilterCondition], category: &DatabaseCategory)
     let conditions: Vec<String> = filters
         .iter()
         .filter_map(|f| {
-            let col = quote_ident(&f.column, category);
+            let col = quote_ident(dialect, &f.column);
+            let ty = column_types.get(&f.column).map(String::as_str);
+            let value = || CellValue::Text(f.value.clone());
             match f.operator.as_str() {
-                "eq" => Some(format!("{} = '{}'", col, escape_sql_literal(&f.value))),
-                "neq" => Some(format!("{} != '{}'", col, escape_sql_literal(&f.value))),
-                "gt" => Some(format!("{} > '{}'", col, escape_sql_literal(&f.value))),
-                "gte" => Some(format!("{} >= '{}'", col, escape_sql_literal(&f.value))),
-                "lt" => Some(format!("{} < '{}'", col, escape_sql_literal(&f.value))),
-                "lte" => Some(format!("{} <= '{}'", col, escape_sql_literal(&f.value))),
+                "eq" => Some(format!("{} = {}", col, params.push_typed(value(), ty))),
+                "neq" => Some(format!("{} != {}", col, params.push_typed(value(), ty))),
+                "gt" => Some(format!("{} > {}", col, params.push_typed(value(), ty))),
+                "gte" => Some(format!("{} >= {}", col, params.push_typed(value(), ty))),
+                "lt" => Some(format!("{} < {}", col, params.push_typed(value(), ty))),
+                "lte" => Some(format!("{} <= {}", col, params.push_typed(value(), ty))),
                 "contains" => Some(format!(
-                    "{} LIKE '%{}%'",
+                    "{} LIKE {}",
                     col,
-                    escape_sql_literal(&f.value).replace('%', "\\%")
+                    params.push(CellValue::Text(format!("%{}%", f.value.replace('%', "\\%"))))
                 )),
                 "starts_with" => Some(format!(
-                    "{} LIKE '{}%'",
+                    "{} LIKE {}",
                     col,
-                    escape_sql_literal(&f.value).replace('%', "\\%")
+                    params.push(CellValue::Text(format!("{}%", f.value.replace('%', "\\%"))))
                 )),
                 "is_null" => Some(format!("{} IS NULL", col)),
                 "is_not_null" => Some(format!("{} IS NOT NULL", col)),
@@ -82,7 +75,7 @@ fn build_where_clause(filters: &[FilterCondition], category: &DatabaseCategory)
 }
 
 /// Build an ORDER BY clause from sort columns.
-pub(crate) fn build_order_by(sorts: &[SortColumn], category: &DatabaseCategory) -> String {
+pub(crate) fn build_order_by(sorts: &[SortColumn], dialect: &str) -> String {
     if sorts.is_empty() {
         return String::new();
     }
@@ -91,29 +84,122 @@ pub(crate) fn build_order_by(sorts: &[SortColumn], category: &DatabaseCategory)
         .iter()
         .map(|s| {
             let dir = if s.direction == "DESC" { "DESC" } else { "ASC" };
-            format!("{} {}", quote_ident(&s.column, category), dir)
+            format!("{} {}", quote_ident(dialect, &s.column), dir)
         })
         .collect();
 
     format!(" ORDER BY {}", clauses.join(", "))
 }
 
-/// Build a WHERE clause from pk_columns and pk_values.
-fn build_pk_where(pk_columns: &[String], pk_values: &[String], category: &DatabaseCategory) -> String {
+/// Build a WHERE clause from pk_columns and pk_values, binding the values.
+fn build_pk_where(
+    pk_columns: &[String],
+    pk_values: &[String],
+    dialect: &str,
+    params: &mut ParamBuilder,
+    column_types: &HashMap<String, String>,
+) -> String {
     pk_columns
         .iter()
         .zip(pk_values.iter())
         .map(|(col, val)| {
+            let ty = column_types.get(col).map(String::as_str);
             format!(
-                "{} = '{}'",
-                quote_ident(col, category),
-                escape_sql_literal(val)
+                "{} = {}",
+                quote_ident(dialect, col),
+                params.push_typed(CellValue::Text(val.clone()), ty)
             )
         })
         .collect::<Vec<_>>()
         .join(" AND ")
 }
 
+// Grid edits go through driver methods rather than SQL text, so history gets
+// an equivalent statement with the values inlined as literals.
+
+fn history_table(dialect: &str, schema: &str, table: &str) -> String {
+    format!("{}.{}", quote_ident(dialect, schema), quote_ident(dialect, table))
+}
+
+fn history_literal(dialect: &str, value: &str) -> String {
+    // Text values always render, so this never falls back.
+    param_literal(&CellValue::Text(value.to_string()), dialect).unwrap_or_default()
+}
+
+fn history_pk_match(dialect: &str, pk_columns: &[String], pk_values: &[String]) -> String {
+    pk_columns
+        .iter()
+        .zip(pk_values.iter())
+        .map(|(col, val)| format!("{} = {}", quote_ident(dialect, col), history_literal(dialect, val)))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// Current value of a cell about to be edited, for the audit log. A failed
+/// lookup is logged and leaves the old value out rather than blocking the edit.
+async fn current_cell_value(
+    handle: &DriverHandle,
+    schema: &str,
+    table: &str,
+    column: &str,
+    pk_columns: &[String],
+    pk_values: &[String],
+) -> Option<CellValue> {
+    let lookup = async {
+        let dialect = handle.base().dialect_hint();
+        let types = column_types(handle.as_sql()?, dialect, schema, table).await?;
+        let mut params = ParamBuilder::new(dialect);
+        let where_clause = build_pk_where(pk_columns, pk_values, dialect, &mut params, &types);
+        let sql = format!(
+            "SELECT {} FROM {}.{} WHERE {}",
+            quote_ident(dialect, column),
+            quote_ident(dialect, schema),
+            quote_ident(dialect, table),
+            where_clause
+        );
+        handle.base().execute_params(&sql, params.into_params()).await
+    };
+    match lookup.await {
+        Ok(response) => response.rows.into_iter().next()?.into_iter().next(),
+        Err(e) => {
+            warn!("Failed to read old value of '{}'.'{}'.'{}': {}", schema, table, column, e);
+            None
+        }
+    }
+}
+
+/// Column name -> data type, used to cast bind parameters. Only PostgreSQL
+/// needs this, so other dialects skip the extra metadata query.
+async fn column_types(
+    driver: &dyn SqlDriver,
+    dialect: &str,
+    schema: &str,
+    table: &str,
+) -> Result<HashMap<String, String>, AppError> {
+    if dialect != "postgres" {
+        return Ok(HashMap::new());
+    }
+    Ok(driver
+        .get_columns(schema, table)
+        .await?
+        .into_iter()
+        .map(|c| (c.name, c.data_type))
+        .collect())
+}
+
+/// Await a table data read under `DEFAULT_DATA_TIMEOUT`.
+async fn with_data_timeout(
+    connection_id: &str,
+    schema: &str,
+    table: &str,
+    fut: impl std::future::Future<Output = Result<QueryResponse, AppError>>,
+) -> Result<QueryResponse, AppError> {
+    timeout(DEFAULT_DATA_TIMEOUT, fut).await.map_err(|_| {
+        error!("get_table_data timed out for '{}'.'{}'.'{}'", connection_id, schema, table);
+        AppError::QueryTimeout(DEFAULT_DATA_TIMEOUT.as_secs())
+    })?
+}
+
 // === Generic commands (all database types) ===
 
 #[tauri::command]
@@ -265,31 +351,97 @@ pub async fn get_table_data(
     offset: i64,
     sort_columns: Option<Vec<SortColumn>>,
     filters: Option<Vec<FilterCondition>>,
+    after: Option<HashMap<String, CellValue>>,
+    key: Option<Vec<KeyColumn>>,
+    select_list: Option<String>,
     pool_manager: State<'_, PoolManager>,
-) -> Result<QueryResponse, AppError> {
+) -> Result<TablePage, AppError> {
     debug!("Loading table data for '{}'.'{}'.'{}'", connection_id, schema, table);
     let handle = pool_manager.get(&connection_id).await?;
     let driver = handle.as_sql()?;
 
     let has_sorts = sort_columns.as_ref().map_or(false, |s| !s.is_empty());
     let has_filters = filters.as_ref().map_or(false, |f| !f.is_empty());
+    let dialect = handle.base().dialect_hint();
+
+    // Without an explicit sort, tables with a primary key are read in key
+    // order. `after` is the last row of the previous page; when the frontend
+    // pages forward the next page seeks past its key instead of using OFFSET.
+    // The key and select list are looked up on the first page only; the
+    // frontend passes them back as `key` and `select_list` after that.
+    let mut page_key = None;
+    if !has_sorts && supports_keyset(dialect) {
+        let (key, columns) = match key {
+            Some(key) => (key, None),
+            None => {
+                let columns = driver.get_columns(&schema, &table).await?;
+                (primary_key(&columns), Some(columns))
+            }
+        };
+        if !key.is_empty() {
+            let select_list = match select_list {
+                Some(select_list) => select_list,
+                None => driver.browse_columns(&schema, &table).await?,
+            };
+            let mut params = ParamBuilder::new(dialect);
+            let where_clause = if has_filters {
+                let types = match columns {
+                    Some(columns) if dialect == "postgres" => {
+                        columns.into_iter().map(|c| (c.name, c.data_type)).collect()
+                    }
+                    Some(_) => HashMap::new(),
+                    None => column_types(driver, dialect, &schema, &table).await?,
+                };
+                build_where_clause(filters.as_ref().unwrap(), dialect, &mut params, &types)
+            } else {
+                String::new()
+            };
+            let after_key = after.as_ref().and_then(|row| named_key(row, &key));
+            let sql = select_page(
+                dialect,
+                &select_list,
+                &schema,
+                &table,
+                &where_clause,
+                &key,
+                after_key.as_deref(),
+                limit,
+                offset,
+                &mut params,
+            );
+            let response = with_data_timeout(
+                &connection_id,
+                &schema,
+                &table,
+                handle.base().execute_params(&sql, params.into_params()),
+            )
+            .await?;
+            return Ok(TablePage {
+                response,
+                key: Some(key),
+                select_list: Some(select_list),
+            });
+        }
+        page_key = Some(key);
+    }
 
     if has_sorts || has_filters {
-        let category = handle.base().category();
         let qualified_table = format!(
             "{}.{}",
-            quote_ident(&schema, &category),
-            quote_ident(&table, &category)
+            quote_ident(dialect, &schema),
+            quote_ident(dialect, &table)
         );
 
+        let mut params = ParamBuilder::new(dialect);
         let where_clause = if has_filters {
-            build_where_clause(filters.as_ref().unwrap(), &category)
+            let types = column_types(driver, dialect, &schema, &table).await?;
+            build_where_clause(filters.as_ref().unwrap(), dialect, &mut params, &types)
         } else {
             String::new()
         };
 
         let order_clause = if has_sorts {
-            build_order_by(sort_columns.as_ref().unwrap(), &category)
+            build_order_by(sort_columns.as_ref().unwrap(), dialect)
         } else {
             String::new()
         };
@@ -299,24 +451,33 @@ pub async fn get_table_data(
             qualified_table, where_clause, order_clause, limit, offset
         );
 
-        return timeout(DEFAULT_DATA_TIMEOUT, handle.base().execute_raw(&sql))
-            .await
-            .map_err(|_| {
-                error!("get_table_data timed out for '{}'.'{}'.'{}'", connection_id, schema, table);
-                AppError::QueryTimeout(DEFAULT_DATA_TIMEOUT.as_secs())
-            })?;
+        let response = with_data_timeout(
+            &connection_id,
+            &schema,
+            &table,
+            handle.base().execute_params(&sql, params.into_params()),
+        )
+        .await?;
+        return Ok(TablePage {
+            response,
+            key: page_key,
+            select_list: None,
+        });
     }
 
     // Fallback to driver method (no sort/filter)
-    timeout(
-        DEFAULT_DATA_TIMEOUT,
+    let response = with_data_timeout(
+        &connection_id,
+        &schema,
+        &table,
         driver.get_table_data(&schema, &table, limit, offset),
     )
-    .await
-    .map_err(|_| {
-        error!("get_table_data timed out for '{}'.'{}'.'{}'", connection_id, schema, table);
-        AppError::QueryTimeout(DEFAULT_DATA_TIMEOUT.as_secs())
-    })?
+    .await?;
+    Ok(TablePage {
+        response,
+        key: page_key,
+        select_list: None,
+    })
 }
 
 #[tauri::command]
@@ -333,17 +494,23 @@ pub async fn get_row_count(
     let has_filters = filters.as_ref().map_or(false, |f| !f.is_empty());
 
     if has_filters {
-        let category = handle.base().category();
+        let dialect = handle.base().dialect_hint();
         let qualified_table = format!(
             "{}.{}",
-            quote_ident(&schema, &category),
-            quote_ident(&table, &category)
+            quote_ident(dialect, &schema),
+            quote_ident(dialect, &table)
         );
 
-        let where_clause = build_where_clause(filters.as_ref().unwrap(), &category);
+        let types = column_types(driver, dialect, &schema, &table).await?;
+        let mut params = ParamBuilder::new(dialect);
+        let where_clause =
+            build_where_clause(filters.as_ref().unwrap(), dialect, &mut params, &types);
         let sql = format!("SELECT COUNT(*) as count FROM {}{}", qualified_table, where_clause);
 
-        let result = handle.base().execute_raw(&sql).await?;
+        let result = handle
+            .base()
+            .execute_params(&sql, params.into_params())
+            .await?;
         if let Some(first_row) = result.rows.first() {
             if let Some(cell) = first_row.first() {
                 return match cell {
@@ -372,28 +539,65 @@ pub async fn update_cell(
     pk_values: Vec<String>,
     is_null: Option<bool>,
     pool_manager: State<'_, PoolManager>,
+    history: State<'_, QueryHistory>,
+    audit: State<'_, AuditLog>,
 ) -> Result<(), AppError> {
     info!("Updating cell in '{}'.'{}'.'{}'.'{}'", connection_id, schema, table, column);
     let handle = pool_manager.get(&connection_id).await?;
+    let dialect = handle.base().dialect_hint();
+    let is_null = is_null.unwrap_or(false);
+    let history_sql = format!(
+        "UPDATE {} SET {} = {} WHERE {}",
+        history_table(dialect, &schema, &table),
+        quote_ident(dialect, &column),
+        if is_null { "NULL".to_string() } else { history_literal(dialect, &value) },
+        history_pk_match(dialect, &pk_columns, &pk_values)
+    );
+    let old_value =
+        current_cell_value(&handle, &schema, &table, &column, &pk_columns, &pk_values).await;
+
+    let started = Instant::now();
+    let result = async {
+        if is_null {
+            let types = column_types(handle.as_sql()?, dialect, &schema, &table).await?;
+            let mut params = ParamBuilder::new(dialect);
+            let where_clause =
+                build_pk_where(&pk_columns, &pk_values, dialect, &mut params, &types);
+            let sql = format!(
+                "UPDATE {}.{} SET {} = NULL WHERE {}",
+                quote_ident(dialect, &schema),
+                quote_ident(dialect, &table),
+                quote_ident(dialect, &column),
+                where_clause
+            );
+            handle
+                .base()
+                .execute_params(&sql, params.into_params())
+                .await?;
+            return Ok(());
+        }
 
-    if is_null.unwrap_or(false) {
-        let category = handle.base().category();
-        let where_clause = build_pk_where(&pk_columns, &pk_values, &category);
-        let sql = format!(
-            "UPDATE {}.{} SET {} = NULL WHERE {}",
-            quote_ident(&schema, &category),
-            quote_ident(&table, &category),
-            quote_ident(&column, &category),
-            where_clause
-        );
-        handle.base().execute_raw(&sql).await?;
-        return Ok(());
+        let driver = handle.as_sql()?;
+        driver
+            .update_cell(&schema, &table, &column, &value, pk_columns, pk_values)
+            .await
     }
-
-    let driver = handle.as_sql()?;
-    driver
-        .update_cell(&schema, &table, &column, &value, pk_columns, pk_values)
-        .await
+    .await;
+    history
+        .record(&connection_id, &history_sql, started.elapsed(), result.as_ref().map(|_| Some(1)))
+        .await;
+    audit.record(
+        &connection_id,
+        "update_cell",
+        AuditDetails {
+            target: Some(format!("{}.{}", schema, table)),
+            statement: Some(history_sql),
+            old_value,
+            new_value: Some(if is_null { CellValue::Null } else { CellValue::Text(value) }),
+        },
+        result.as_ref().map(|_| Some(1)),
+    );
+    result
 }
 
 #[tauri::command]
@@ -404,11 +608,36 @@ pub async fn insert_row(
     columns: Vec<String>,
     values: Vec<String>,
     pool_manager: State<'_, PoolManager>,
+    history: State<'_, QueryHistory>,
+    audit: State<'_, AuditLog>,
 ) -> Result<(), AppError> {
     info!("Inserting row into '{}'.'{}'.'{}'", connection_id, schema, table);
     let handle = pool_manager.get(&connection_id).await?;
+    let dialect = handle.base().dialect_hint();
+    let history_sql = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        history_table(dialect, &schema, &table),
+        columns.iter().map(|c| quote_ident(dialect, c)).collect::<Vec<_>>().join(", "),
+        values.iter().map(|v| history_literal(dialect, v)).collect::<Vec<_>>().join(", ")
+    );
+
     let driver = handle.as_sql()?;
-    driver.insert_row(&schema, &table, columns, values).await
+    let started = Instant::now();
+    let result = driver.insert_row(&schema, &table, columns, values).await;
+    history
+        .record(&connection_id, &history_sql, started.elapsed(), result.as_ref().map(|_| Some(1)))
+        .await;
+    audit.record(
+        &connection_id,
+        "insert_row",
+        AuditDetails {
+            target: Some(format!("{}.{}", schema, table)),
+            statement: Some(history_sql),
+            ..Default::default()
+        },
+        result.as_ref().map(|_| Some(1)),
+    );
+    result
 }
 
 #[tauri::command]
@@ -419,16 +648,44 @@ pub async fn delete_rows(
     pk_columns: Vec<String>,
     pk_values_list: Vec<Vec<String>>,
     pool_manager: State<'_, PoolManager>,
+    history: State<'_, QueryHistory>,
+    audit: State<'_, AuditLog>,
 ) -> Result<u64, AppError> {
     info!(
         "Deleting {} row(s) from '{}'.'{}'.'{}'",
         pk_values_list.len(), connection_id, schema, table
     );
     let handle = pool_manager.get(&connection_id).await?;
+    let dialect = handle.base().dialect_hint();
+    let history_sql = format!(
+        "DELETE FROM {} WHERE {}",
+        history_table(dialect, &schema, &table),
+        pk_values_list
+            .iter()
+            .map(|pk_values| format!("({})", history_pk_match(dialect, &pk_columns, pk_values)))
+            .collect::<Vec<_>>()
+            .join(" OR ")
+    );
+
     let driver = handle.as_sql()?;
-    driver
+    let started = Instant::now();
+    let result = driver
         .delete_rows(&schema, &table, pk_columns, pk_values_list)
-        .await
+        .await;
+    history
+        .record(&connection_id, &history_sql, started.elapsed(), result.as_ref().map(|n| Some(*n)))
+        .await;
+    audit.record(
+        &connection_id,
+        "delete_rows",
+        AuditDetails {
+            target: Some(format!("{}.{}", schema, table)),
+            statement: Some(history_sql),
+            ..Default::default()
+        },
+        result.as_ref().map(|n| Some(*n)),
+    );
+    result
 }
 
 // === Phase 5: Schema browser commands ===
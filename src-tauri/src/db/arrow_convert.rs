@@ -0,0 +1,514 @@
+use arrow::array::{Array, AsArray, RecordBatch, StructArray};
+use arrow::datatypes::{
+    DataType, Date32Type, Date64Type, Decimal128Type, Decimal256Type, Field, Float32Type,
+    Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, Time32MillisecondType,
+    Time32SecondType, Time64MicrosecondType, Time64NanosecondType, TimeUnit,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+
+use crate::db::types::{date_cell, naive_datetime, time_cell};
+use crate::models::query::{CellValue, ColumnDef};
+
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Convert Arrow record batches into columns and rows. Fields carrying
+/// Snowflake's `logicalType` metadata are read the way Snowflake encodes that
+/// type; other fields by their Arrow type alone.
+pub fn batches_to_response(batches: &[RecordBatch]) -> (Vec<ColumnDef>, Vec<Vec<CellValue>>) {
+    let Some(first) = batches.first() else {
+        return (Vec::new(), Vec::new());
+    };
+    let schema = first.schema();
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| ColumnDef {
+            name: field.name().clone(),
+            data_type: column_type(field),
+        })
+        .collect();
+
+    let mut rows = Vec::new();
+    for batch in batches {
+        for row in 0..batch.num_rows() {
+            rows.push(
+                schema
+                    .fields()
+                    .iter()
+                    .zip(batch.columns())
+                    .map(|(field, array)| field_value(field, array.as_ref(), row))
+                    .collect(),
+            );
+        }
+    }
+    (columns, rows)
+}
+
+/// Type shown for a column: the Snowflake type when the field says, else the
+/// Arrow type.
+fn column_type(field: &Field) -> String {
+    match logical_type(field) {
+        Some("FIXED") => match (metadata_number(field, "precision"), scale(field)) {
+            (Some(precision), scale) => format!("NUMBER({},{})", precision, scale),
+            (None, _) => "NUMBER".to_string(),
+        },
+        Some("REAL") => "FLOAT".to_string(),
+        Some(logical) => logical.to_string(),
+        None => field.data_type().to_string(),
+    }
+}
+
+fn logical_type(field: &Field) -> Option<&str> {
+    field.metadata().get("logicalType").map(String::as_str)
+}
+
+fn metadata_number(field: &Field, key: &str) -> Option<u32> {
+    field.metadata().get(key)?.parse().ok()
+}
+
+/// Fractional digits of Snowflake FIXED, TIME and TIMESTAMP values.
+fn scale(field: &Field) -> u32 {
+    metadata_number(field, "scale").unwrap_or(0)
+}
+
+/// Value `row` of `array`, which holds the values of `field`.
+pub fn field_value(field: &Field, array: &dyn Array, row: usize) -> CellValue {
+    if array.is_null(row) {
+        return CellValue::Null;
+    }
+    let snowflake = match logical_type(field) {
+        Some("FIXED") => fixed(array, row, scale(field)),
+        Some("TIME") => integer(array, row).and_then(|v| time(scaled_micros(v, scale(field)))),
+        Some("TIMESTAMP_NTZ") => timestamp(array, row, scale(field))
+            .map(|micros| CellValue::DateTime { tz: None, micros }),
+        // An instant, shown in UTC since the session time zone is not known here.
+        Some("TIMESTAMP_LTZ") => {
+            timestamp(array, row, scale(field)).map(|micros| CellValue::DateTime {
+                tz: Some("+00:00".to_string()),
+                micros,
+            })
+        }
+        Some("TIMESTAMP_TZ") => timestamp_tz(array, row, scale(field)),
+        Some("VARIANT" | "OBJECT" | "ARRAY") => string(array, row).map(CellValue::Json),
+        _ => None,
+    };
+    snowflake.unwrap_or_else(|| array_value(array, row))
+}
+
+/// Value `row` of `array` by its Arrow type alone.
+pub fn array_value(array: &dyn Array, row: usize) -> CellValue {
+    if array.is_null(row) {
+        return CellValue::Null;
+    }
+    let cell = match array.data_type() {
+        DataType::Boolean => Some(CellValue::Bool(array.as_boolean().value(row))),
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32 => integer(array, row).map(CellValue::Int),
+        DataType::UInt64 => {
+            let value = array.as_primitive::<UInt64Type>().value(row);
+            Some(i64::try_from(value).map_or_else(
+                |_| CellValue::Decimal {
+                    digits: value.to_string(),
+                    scale: 0,
+                },
+                CellValue::Int,
+            ))
+        }
+        DataType::Float32 => Some(CellValue::Float(
+            array.as_primitive::<Float32Type>().value(row) as f64,
+        )),
+        DataType::Float64 => Some(CellValue::Float(
+            array.as_primitive::<Float64Type>().value(row),
+        )),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            string(array, row).map(CellValue::Text)
+        }
+        DataType::Binary => Some(CellValue::Binary(
+            array.as_binary::<i32>().value(row).to_vec(),
+        )),
+        DataType::LargeBinary => Some(CellValue::Binary(
+            array.as_binary::<i64>().value(row).to_vec(),
+        )),
+        DataType::FixedSizeBinary(_) => Some(CellValue::Binary(
+            array.as_fixed_size_binary().value(row).to_vec(),
+        )),
+        DataType::Decimal128(_, scale) => Some(decimal(
+            array
+                .as_primitive::<Decimal128Type>()
+                .value(row)
+                .to_string(),
+            *scale,
+        )),
+        DataType::Decimal256(_, scale) => Some(decimal(
+            array
+                .as_primitive::<Decimal256Type>()
+                .value(row)
+                .to_string(),
+            *scale,
+        )),
+        DataType::Date32 => {
+            date(array.as_primitive::<Date32Type>().value(row) as i64 * MICROS_PER_DAY)
+        }
+        DataType::Date64 => date(array.as_primitive::<Date64Type>().value(row) * 1000),
+        DataType::Time32(TimeUnit::Second) => {
+            time(array.as_primitive::<Time32SecondType>().value(row) as i64 * 1_000_000)
+        }
+        DataType::Time32(_) => {
+            time(array.as_primitive::<Time32MillisecondType>().value(row) as i64 * 1000)
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            time(array.as_primitive::<Time64MicrosecondType>().value(row))
+        }
+        DataType::Time64(_) => time(array.as_primitive::<Time64NanosecondType>().value(row) / 1000),
+        DataType::Timestamp(unit, tz) => {
+            let micros = match unit {
+                TimeUnit::Second => {
+                    array.as_primitive::<TimestampSecondType>().value(row) * 1_000_000
+                }
+                TimeUnit::Millisecond => {
+                    array.as_primitive::<TimestampMillisecondType>().value(row) * 1000
+                }
+                TimeUnit::Microsecond => {
+                    array.as_primitive::<TimestampMicrosecondType>().value(row)
+                }
+                TimeUnit::Nanosecond => array
+                    .as_primitive::<TimestampNanosecondType>()
+                    .value(row)
+                    .div_euclid(1000),
+            };
+            // Zoned timestamps are UTC instants; named zones are shown in UTC.
+            let tz = tz
+                .as_ref()
+                .map(|tz| match tz.parse::<chrono::FixedOffset>() {
+                    Ok(offset) => offset.to_string(),
+                    Err(_) => "+00:00".to_string(),
+                });
+            Some(CellValue::DateTime { tz, micros })
+        }
+        DataType::List(_) => {
+            let items = array.as_list::<i32>().value(row);
+            Some(CellValue::Array(
+                (0..items.len())
+                    .map(|i| array_value(items.as_ref(), i))
+                    .collect(),
+            ))
+        }
+        DataType::LargeList(_) => {
+            let items = array.as_list::<i64>().value(row);
+            Some(CellValue::Array(
+                (0..items.len())
+                    .map(|i| array_value(items.as_ref(), i))
+                    .collect(),
+            ))
+        }
+        _ => None,
+    };
+    cell.unwrap_or_else(|| {
+        CellValue::Text(arrow::util::display::array_value_to_string(array, row).unwrap_or_default())
+    })
+}
+
+/// Snowflake NUMBER: integers holding the value times 10^scale, or Decimal128.
+fn fixed(array: &dyn Array, row: usize, scale: u32) -> Option<CellValue> {
+    match array.data_type() {
+        DataType::Decimal128(..) | DataType::Decimal256(..) => None,
+        _ if scale == 0 => integer(array, row).map(CellValue::Int),
+        _ => integer(array, row).map(|v| CellValue::Decimal {
+            digits: v.to_string(),
+            scale,
+        }),
+    }
+}
+
+/// Epoch microseconds of a TIMESTAMP_NTZ or TIMESTAMP_LTZ value: an integer in
+/// 10^-scale seconds, or a struct of epoch seconds and nanosecond fraction.
+fn timestamp(array: &dyn Array, row: usize, scale: u32) -> Option<i64> {
+    match array.data_type() {
+        DataType::Struct(_) => epoch_fraction(array.as_struct(), row),
+        _ => integer(array, row).map(|v| scaled_micros(v, scale)),
+    }
+}
+
+/// TIMESTAMP_TZ: a struct of the UTC epoch, either in 10^-scale seconds or as
+/// seconds plus nanosecond fraction, and the offset in minutes plus 1440.
+fn timestamp_tz(array: &dyn Array, row: usize, scale: u32) -> Option<CellValue> {
+    let parts = array.as_struct_opt()?;
+    let micros = match parts.num_columns() {
+        2 => scaled_micros(
+            integer(parts.column_by_name("epoch")?.as_ref(), row)?,
+            scale,
+        ),
+        _ => epoch_fraction(parts, row)?,
+    };
+    let offset = integer(parts.column_by_name("timezone")?.as_ref(), row)? - 1440;
+    let offset = chrono::FixedOffset::east_opt(offset as i32 * 60)?;
+    Some(CellValue::DateTime {
+        tz: Some(offset.to_string()),
+        micros,
+    })
+}
+
+/// Nanoseconds below a microsecond are dropped.
+fn epoch_fraction(parts: &StructArray, row: usize) -> Option<i64> {
+    let epoch = integer(parts.column_by_name("epoch")?.as_ref(), row)?;
+    let fraction = integer(parts.column_by_name("fraction")?.as_ref(), row)?;
+    Some(epoch * 1_000_000 + fraction / 1000)
+}
+
+/// Microseconds in `value` units of 10^-scale seconds.
+fn scaled_micros(value: i64, scale: u32) -> i64 {
+    if scale <= 6 {
+        value * 10i64.pow(6 - scale)
+    } else {
+        value.div_euclid(10i64.pow(scale - 6))
+    }
+}
+
+/// Any integer array value that fits an i64.
+fn integer(array: &dyn Array, row: usize) -> Option<i64> {
+    Some(match array.data_type() {
+        DataType::Int8 => array.as_primitive::<Int8Type>().value(row) as i64,
+        DataType::Int16 => array.as_primitive::<Int16Type>().value(row) as i64,
+        DataType::Int32 => array.as_primitive::<Int32Type>().value(row) as i64,
+        DataType::Int64 => array.as_primitive::<Int64Type>().value(row),
+        DataType::UInt8 => array.as_primitive::<UInt8Type>().value(row) as i64,
+        DataType::UInt16 => array.as_primitive::<UInt16Type>().value(row) as i64,
+        DataType::UInt32 => array.as_primitive::<UInt32Type>().value(row) as i64,
+        DataType::UInt64 => i64::try_from(array.as_primitive::<UInt64Type>().value(row)).ok()?,
+        _ => return None,
+    })
+}
+
+fn string(array: &dyn Array, row: usize) -> Option<String> {
+    Some(match array.data_type() {
+        DataType::Utf8 => array.as_string::<i32>().value(row).to_string(),
+        DataType::LargeUtf8 => array.as_string::<i64>().value(row).to_string(),
+        DataType::Utf8View => array.as_string_view().value(row).to_string(),
+        _ => return None,
+    })
+}
+
+/// A decimal of `digits` times 10^-scale. Negative scales are multiples of a
+/// power of ten.
+fn decimal(digits: String, scale: i8) -> CellValue {
+    if scale < 0 {
+        let digits = if digits == "0" {
+            digits
+        } else {
+            format!("{}{}", digits, "0".repeat(scale.unsigned_abs() as usize))
+        };
+        return CellValue::Decimal { digits, scale: 0 };
+    }
+    CellValue::Decimal {
+        digits,
+        scale: scale as u32,
+    }
+}
+
+fn date(micros: i64) -> Option<CellValue> {
+    naive_datetime(micros).map(|ts| date_cell(ts.date()))
+}
+
+fn time(micros: i64) -> Option<CellValue> {
+    let micros = micros.rem_euclid(MICROS_PER_DAY);
+    chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+        (micros / 1_000_000) as u32,
+        (micros % 1_000_000) as u32 * 1000,
+    )
+    .map(time_cell)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use arrow::array::{
+        ArrayRef, BinaryArray, Date32Array, Decimal128Array, Int32Array, Int64Array, ListArray,
+        StringArray,
+    };
+    use arrow::datatypes::{Fields, Schema};
+
+    use crate::db::types::cell_text;
+
+    fn snowflake_field(name: &str, data_type: DataType, logical: &str, scale: u32) -> Field {
+        Field::new(name, data_type, true).with_metadata(HashMap::from([
+            ("logicalType".to_string(), logical.to_string()),
+            ("scale".to_string(), scale.to_string()),
+            ("precision".to_string(), "38".to_string()),
+        ]))
+    }
+
+    fn texts(field: Field, array: ArrayRef) -> Vec<Option<String>> {
+        (0..array.len())
+            .map(|row| cell_text(&field_value(&field, array.as_ref(), row)))
+            .collect()
+    }
+
+    #[test]
+    fn test_fixed_numbers() {
+        let field = snowflake_field("n", DataType::Int64, "FIXED", 2);
+        let array: ArrayRef = Arc::new(Int64Array::from(vec![Some(12345), Some(-5), None]));
+        assert_eq!(
+            texts(field, array),
+            vec![Some("123.45".to_string()), Some("-0.05".to_string()), None]
+        );
+
+        let field = snowflake_field("n", DataType::Decimal128(38, 4), "FIXED", 4);
+        let array: ArrayRef = Arc::new(
+            Decimal128Array::from(vec![123_456_789_012_345_678_901_234_567_890_123i128])
+                .with_precision_and_scale(38, 4)
+                .unwrap(),
+        );
+        assert_eq!(
+            texts(field, array),
+            vec![Some("12345678901234567890123456789.0123".to_string())]
+        );
+
+        let field = snowflake_field("n", DataType::Int32, "FIXED", 0);
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![7]));
+        assert!(matches!(
+            field_value(&field, array.as_ref(), 0),
+            CellValue::Int(7)
+        ));
+        assert_eq!(column_type(&field), "NUMBER(38,0)");
+    }
+
+    #[test]
+    fn test_dates_and_times() {
+        let field = snowflake_field("d", DataType::Date32, "DATE", 0);
+        let array: ArrayRef = Arc::new(Date32Array::from(vec![19_782, -1]));
+        assert_eq!(
+            texts(field, array),
+            vec![
+                Some("2024-02-29".to_string()),
+                Some("1969-12-31".to_string())
+            ]
+        );
+
+        let field = snowflake_field("t", DataType::Int64, "TIME", 9);
+        let array: ArrayRef = Arc::new(Int64Array::from(vec![45_301_123_456_789]));
+        assert_eq!(
+            texts(field, array),
+            vec![Some("12:35:01.123456".to_string())]
+        );
+
+        let field = snowflake_field("ts", DataType::Int64, "TIMESTAMP_NTZ", 3);
+        let array: ArrayRef = Arc::new(Int64Array::from(vec![1_709_210_101_120]));
+        assert_eq!(
+            texts(field, array),
+            vec![Some("2024-02-29 12:35:01.120".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_timestamp_structs() {
+        let epoch = Field::new("epoch", DataType::Int64, true);
+        let fraction = Field::new("fraction", DataType::Int32, true);
+        let timezone = Field::new("timezone", DataType::Int32, true);
+
+        let parts = StructArray::new(
+            Fields::from(vec![epoch.clone(), fraction.clone()]),
+            vec![
+                Arc::new(Int64Array::from(vec![1_709_210_101])) as ArrayRef,
+                Arc::new(Int32Array::from(vec![123_456_789])),
+            ],
+            None,
+        );
+        let field = snowflake_field("ts", parts.data_type().clone(), "TIMESTAMP_LTZ", 9);
+        assert_eq!(
+            texts(field, Arc::new(parts)),
+            vec![Some("2024-02-29 12:35:01.123456+00:00".to_string())]
+        );
+
+        // 12:35:01 UTC at +05:30.
+        let parts = StructArray::new(
+            Fields::from(vec![epoch.clone(), fraction, timezone.clone()]),
+            vec![
+                Arc::new(Int64Array::from(vec![1_709_210_101])) as ArrayRef,
+                Arc::new(Int32Array::from(vec![0])),
+                Arc::new(Int32Array::from(vec![1440 + 330])),
+            ],
+            None,
+        );
+        let field = snowflake_field("ts", parts.data_type().clone(), "TIMESTAMP_TZ", 9);
+        assert_eq!(
+            texts(field, Arc::new(parts)),
+            vec![Some("2024-02-29 18:05:01+05:30".to_string())]
+        );
+
+        // With scale 3 and no fraction, the epoch is in milliseconds.
+        let parts = StructArray::new(
+            Fields::from(vec![epoch, timezone]),
+            vec![
+                Arc::new(Int64Array::from(vec![1_709_210_101_500])) as ArrayRef,
+                Arc::new(Int32Array::from(vec![1440 - 480])),
+            ],
+            None,
+        );
+        let field = snowflake_field("ts", parts.data_type().clone(), "TIMESTAMP_TZ", 3);
+        assert_eq!(
+            texts(field, Arc::new(parts)),
+            vec![Some("2024-02-29 04:35:01.500-08:00".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_semi_structured_and_binary() {
+        let field = snowflake_field("v", DataType::Utf8, "VARIANT", 0);
+        let array: ArrayRef = Arc::new(StringArray::from(vec!["{\n  \"a\": 1\n}"]));
+        assert!(matches!(
+            field_value(&field, array.as_ref(), 0),
+            CellValue::Json(j) if j == "{\n  \"a\": 1\n}"
+        ));
+
+        let field = snowflake_field("s", DataType::Utf8, "TEXT", 0);
+        let array: ArrayRef = Arc::new(StringArray::from(vec!["x"]));
+        assert!(matches!(field_value(&field, array.as_ref(), 0), CellValue::Text(t) if t == "x"));
+
+        let field = snowflake_field("b", DataType::Binary, "BINARY", 0);
+        let array: ArrayRef = Arc::new(BinaryArray::from(vec![&[0xde, 0xad][..]]));
+        assert!(matches!(
+            field_value(&field, array.as_ref(), 0),
+            CellValue::Binary(v) if v == [0xde, 0xad]
+        ));
+    }
+
+    #[test]
+    fn test_plain_arrow_types() {
+        let list: ArrayRef = Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), None]),
+        ]));
+        let CellValue::Array(items) = array_value(list.as_ref(), 0) else {
+            panic!("expected an array");
+        };
+        assert!(matches!(
+            items.as_slice(),
+            [CellValue::Int(1), CellValue::Null]
+        ));
+
+        let decimals: ArrayRef = Arc::new(
+            Decimal128Array::from(vec![12])
+                .with_precision_and_scale(10, -2)
+                .unwrap(),
+        );
+        assert_eq!(
+            cell_text(&array_value(decimals.as_ref(), 0)).as_deref(),
+            Some("1200")
+        );
+
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, true)]));
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2]))]).unwrap();
+        let (columns, rows) = batches_to_response(&[batch.clone(), batch]);
+        assert_eq!(columns[0].data_type, "Int32");
+        assert_eq!(rows.len(), 4);
+    }
+}
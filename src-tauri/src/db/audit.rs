@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::warn;
+
+use crate::error::AppError;
+use crate::models::audit::AuditEntry;
+use crate::models::query::CellValue;
+
+const CURRENT_FILE: &str = "audit.jsonl";
+const ROTATED_PREFIX: &str = "audit-";
+/// The current file is rotated once it reaches this size.
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+/// Rotated files kept on disk; the oldest are deleted beyond this.
+const MAX_ROTATED_FILES: usize = 20;
+
+/// What a write touched, beyond the connection and operation.
+#[derive(Debug, Default)]
+pub struct AuditDetails {
+    pub target: Option<String>,
+    pub statement: Option<String>,
+    pub old_value: Option<CellValue>,
+    pub new_value: Option<CellValue>,
+}
+
+/// Append-only JSONL log of every write made through the app, kept apart from
+/// query history. Lines are only ever appended to `audit.jsonl`; when it grows
+/// past `MAX_FILE_BYTES` it is renamed to `audit-<timestamp>.jsonl`.
+pub struct AuditLog {
+    dir: PathBuf,
+    max_file_bytes: u64,
+    max_rotated_files: usize,
+    /// Connection id -> display name, registered on connect.
+    names: Mutex<HashMap<String, String>>,
+    /// Serializes appends and rotation.
+    write_lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(dir: PathBuf) -> Self {
+        Self::with_limits(dir, MAX_FILE_BYTES, MAX_ROTATED_FILES)
+    }
+
+    fn with_limits(dir: PathBuf, max_file_bytes: u64, max_rotated_files: usize) -> Self {
+        Self {
+            dir,
+            max_file_bytes,
+            max_rotated_files,
+            names: Mutex::new(HashMap::new()),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn set_connection_name(&self, connection_id: &str, name: &str) {
+        self.names
+            .lock()
+            .unwrap()
+            .insert(connection_id.to_string(), name.to_string());
+    }
+
+    pub fn remove(&self, connection_id: &str) {
+        self.names.lock().unwrap().remove(connection_id);
+    }
+
+    /// Append an entry for a write. `outcome` carries the affected row count
+    /// or the error the write failed with. Failures to write the log are
+    /// logged rather than surfaced, so auditing never blocks the operation.
+    pub fn record(
+        &self,
+        connection_id: &str,
+        operation: &str,
+        details: AuditDetails,
+        outcome: Result<Option<u64>, &AppError>,
+    ) {
+        let connection_name = self
+            .names
+            .lock()
+            .unwrap()
+            .get(connection_id)
+            .cloned()
+            .unwrap_or_else(|| connection_id.to_string());
+        let (affected_rows, error) = match outcome {
+            Ok(rows) => (rows, None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let entry = AuditEntry {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            connection_id: connection_id.to_string(),
+            connection_name,
+            operation: operation.to_string(),
+            target: details.target,
+            statement: details.statement,
+            affected_rows,
+            old_value: details.old_value,
+            new_value: details.new_value,
+            error,
+        };
+        if let Err(e) = self.append(&entry) {
+            warn!("Failed to write audit log entry for '{}': {}", connection_id, e);
+        }
+    }
+
+    fn append(&self, entry: &AuditEntry) -> Result<(), AppError> {
+        let line = serde_json::to_string(entry).map_err(|e| AppError::Serialization(e.to_string()))?;
+        let _guard = self.write_lock.lock().unwrap();
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| AppError::Database(format!("Failed to create audit dir: {}", e)))?;
+        self.rotate_if_full()?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(CURRENT_FILE))
+            .map_err(|e| AppError::Database(format!("Failed to open audit log: {}", e)))?;
+        writeln!(file, "{}", line)
+            .map_err(|e| AppError::Database(format!("Failed to write audit log: {}", e)))
+    }
+
+    fn rotate_if_full(&self) -> Result<(), AppError> {
+        let current = self.dir.join(CURRENT_FILE);
+        let size = fs::metadata(&current).map(|m| m.len()).unwrap_or(0);
+        if size < self.max_file_bytes {
+            return Ok(());
+        }
+        let rotated = self.dir.join(format!(
+            "{}{}.jsonl",
+            ROTATED_PREFIX,
+            Utc::now().format("%Y%m%d_%H%M%S%3f")
+        ));
+        fs::rename(&current, &rotated)
+            .map_err(|e| AppError::Database(format!("Failed to rotate audit log: {}", e)))?;
+
+        let rotated = self.rotated_files()?;
+        let excess = rotated.len().saturating_sub(self.max_rotated_files);
+        for old in &rotated[..excess] {
+            if let Err(e) = fs::remove_file(old) {
+                warn!("Failed to remove old audit log {}: {}", old.display(), e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rotated files, oldest first (their names sort by rotation time).
+    fn rotated_files(&self) -> Result<Vec<PathBuf>, AppError> {
+        let mut files = Vec::new();
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+            Err(e) => return Err(AppError::Database(format!("Failed to read audit dir: {}", e))),
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(ROTATED_PREFIX) && name.ends_with(".jsonl") {
+                files.push(entry.path());
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Write every entry across rotated and current files to `dest` as JSONL,
+    /// oldest first, keeping those within `[since, until)` (Unix ms) for
+    /// `connection_id` when given. Returns the number of entries written.
+    pub fn export(
+        &self,
+        dest: &Path,
+        since: Option<i64>,
+        until: Option<i64>,
+        connection_id: Option<&str>,
+    ) -> Result<u64, AppError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut files = self.rotated_files()?;
+        let current = self.dir.join(CURRENT_FILE);
+        if current.exists() {
+            files.push(current);
+        }
+
+        let out = File::create(dest)
+            .map_err(|e| AppError::Database(format!("Failed to create export file: {}", e)))?;
+        let mut out = BufWriter::new(out);
+        let mut written = 0;
+        for path in files {
+            let file = File::open(&path)
+                .map_err(|e| AppError::Database(format!("Failed to read audit log: {}", e)))?;
+            for line in BufReader::new(file).lines() {
+                let line =
+                    line.map_err(|e| AppError::Database(format!("Failed to read audit log: {}", e)))?;
+                let Ok(entry) = serde_json::from_str::<AuditEntry>(&line) else {
+                    continue;
+                };
+                if !matches(&entry, since, until, connection_id) {
+                    continue;
+                }
+                writeln!(out, "{}", line)
+                    .map_err(|e| AppError::Database(format!("Failed to write export file: {}", e)))?;
+                written += 1;
+            }
+        }
+        out.flush()
+            .map_err(|e| AppError::Database(format!("Failed to write export file: {}", e)))?;
+        Ok(written)
+    }
+}
+
+fn matches(entry: &AuditEntry, since: Option<i64>, until: Option<i64>, connection_id: Option<&str>) -> bool {
+    if connection_id.is_some_and(|id| id != entry.connection_id) {
+        return false;
+    }
+    if since.is_none() && until.is_none() {
+        return true;
+    }
+    let Ok(at) = DateTime::parse_from_rfc3339(&entry.timestamp) else {
+        return false;
+    };
+    let at = at.timestamp_millis();
+    since.is_none_or(|since| at >= since) && until.is_none_or(|until| at < until)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("queryark-audit-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn details(target: &str) -> AuditDetails {
+        AuditDetails {
+            target: Some(target.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_records_and_exports_entries() {
+        let dir = temp_dir("export");
+        let log = AuditLog::new(dir.clone());
+        log.set_connection_name("c1", "Production");
+        log.record("c1", "insert_row", details("public.users"), Ok(Some(1)));
+        let err = AppError::Database("boom".to_string());
+        log.record("c2", "delete_keys", details("k"), Err(&err));
+
+        let dest = dir.join("export.jsonl");
+        assert_eq!(log.export(&dest, None, None, None).unwrap(), 2);
+        assert_eq!(log.export(&dest, None, None, Some("c1")).unwrap(), 1);
+        let line = fs::read_to_string(&dest).unwrap();
+        let entry: AuditEntry = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(entry.connection_name, "Production");
+        assert_eq!(entry.affected_rows, Some(1));
+        assert_eq!(log.export(&dest, Some(i64::MAX), None, None).unwrap(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotates_and_prunes_files() {
+        let dir = temp_dir("rotate");
+        let log = AuditLog::with_limits(dir.clone(), 1, 2);
+        for _ in 0..5 {
+            log.record("c1", "set_value", details("key"), Ok(None));
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert_eq!(log.rotated_files().unwrap().len(), 2);
+        let dest = dir.join("export.jsonl");
+        // Two rotated files plus the current one survive.
+        assert_eq!(log.export(&dest, None, None, None).unwrap(), 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
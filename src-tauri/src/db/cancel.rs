@@ -1,5 +1,7 @@
+use std::cell::Cell;
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::future::Future;
+use std::sync::{Mutex, RwLock};
 use tokio::sync::oneshot;
 
 /// Registry that tracks in-flight queries and allows cancellation via oneshot channels.
@@ -39,3 +41,80 @@ impl CancellationRegistry {
         map.remove(query_id);
     }
 }
+
+tokio::task_local! {
+    /// Id of the query being executed by the current task. Set by the query
+    /// commands so drivers can record server-side handles without threading
+    /// the id through every trait method.
+    static CURRENT_QUERY_ID: String;
+
+    /// Session id of the connection the current query last took from a pool,
+    /// reported by the pool's acquire hook.
+    static ACQUIRED_SESSION: Cell<Option<i64>>;
+}
+
+/// Run `fut` with `query_id` as the current query id.
+pub async fn with_query_id<F: Future>(query_id: String, fut: F) -> F::Output {
+    CURRENT_QUERY_ID
+        .scope(query_id, ACQUIRED_SESSION.scope(Cell::new(None), fut))
+        .await
+}
+
+/// The id of the query running on this task, if the caller set one.
+pub fn current_query_id() -> Option<String> {
+    CURRENT_QUERY_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Report the session id of a connection being handed to the current query.
+/// Does nothing outside `with_query_id`.
+pub fn report_session(session_id: i64) {
+    ACQUIRED_SESSION.try_with(|slot| slot.set(Some(session_id))).ok();
+}
+
+/// Take the session id reported for the connection the current query
+/// acquired last.
+pub fn take_session() -> Option<i64> {
+    ACQUIRED_SESSION.try_with(Cell::take).ok().flatten()
+}
+
+/// Per-driver map from query id to the server-side handle of the running
+/// statement (backend pid, session id, job id, ...), used by `DbDriver::cancel`.
+pub struct ServerHandles<T> {
+    handles: Mutex<HashMap<String, T>>,
+}
+
+impl<T: Clone> ServerHandles<T> {
+    pub fn new() -> Self {
+        Self {
+            handles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record `handle` under the current query id. Returns a guard that removes
+    /// the entry when the statement finishes, or `None` if no query id is set.
+    pub fn track(&self, handle: T) -> Option<TrackedQuery<'_, T>> {
+        let query_id = current_query_id()?;
+        self.handles.lock().unwrap().insert(query_id.clone(), handle);
+        Some(TrackedQuery {
+            handles: self,
+            query_id,
+        })
+    }
+
+    /// Look up the handle recorded for `query_id`.
+    pub fn get(&self, query_id: &str) -> Option<T> {
+        self.handles.lock().unwrap().get(query_id).cloned()
+    }
+}
+
+/// Removes a query's server handle from its `ServerHandles` when dropped.
+pub struct TrackedQuery<'a, T: Clone> {
+    handles: &'a ServerHandles<T>,
+    query_id: String,
+}
+
+impl<T: Clone> Drop for TrackedQuery<'_, T> {
+    fn drop(&mut self) {
+        self.handles.handles.lock().unwrap().remove(&self.query_id);
+    }
+}
@@ -0,0 +1,406 @@
+use serde_json::Value;
+
+use crate::db::types::{datetime_cell, decimal_cell};
+use crate::error::AppError;
+use crate::models::query::{CellValue, ColumnDef};
+
+/// Output format of `query_to_response`: a row of column names, a row of
+/// column types, then one JSON array per row.
+pub const FORMAT: &str = "JSONCompactEachRowWithNamesAndTypes";
+
+/// Settings that keep `FORMAT` output lossless and positional: 64-bit
+/// integers and decimals as strings, `nan`/`inf` as strings instead of
+/// `null`, and named tuples as arrays like unnamed ones.
+pub const FORMAT_SETTINGS: [(&str, &str); 4] = [
+    ("output_format_json_quote_64bit_integers", "1"),
+    ("output_format_json_quote_decimals", "1"),
+    ("output_format_json_quote_denormals", "1"),
+    ("output_format_json_named_tuples_as_objects", "0"),
+];
+
+/// A column type as far as it matters for decoding. `Nullable` and
+/// `LowCardinality` wrappers are dropped, since neither changes how a value
+/// is written.
+#[derive(Debug, Clone, PartialEq)]
+enum ChType {
+    /// Any integer width; 64-bit and wider ones arrive as strings.
+    Integer,
+    Float,
+    Bool,
+    Decimal(u32),
+    Uuid,
+    Date,
+    DateTime,
+    /// `DateTime64` with its precision in fractional digits.
+    DateTime64(u32),
+    Enum,
+    Json,
+    Array(Box<ChType>),
+    Map(Box<ChType>, Box<ChType>),
+    Tuple(Vec<ChType>),
+    /// Strings, IP addresses and anything else shown as ClickHouse writes it.
+    Other,
+}
+
+/// Columns and rows of a `FORMAT` response body.
+pub fn parse_response(body: &str) -> Result<(Vec<ColumnDef>, Vec<Vec<CellValue>>), AppError> {
+    let mut lines = body.lines().filter(|line| !line.trim().is_empty());
+    let (names, types) = match (lines.next(), lines.next()) {
+        (Some(names), Some(types)) => (
+            serde_json::from_str::<Vec<String>>(names)?,
+            serde_json::from_str::<Vec<String>>(types)?,
+        ),
+        _ => return Ok((Vec::new(), Vec::new())),
+    };
+    let decoders: Vec<ChType> = types.iter().map(|t| parse_type(t)).collect();
+
+    let mut rows = Vec::new();
+    for line in lines {
+        let values: Vec<Value> = serde_json::from_str(line).map_err(|e| {
+            AppError::Serialization(format!("Failed to parse ClickHouse row: {}", e))
+        })?;
+        rows.push(
+            decoders
+                .iter()
+                .zip(values.iter().chain(std::iter::repeat(&Value::Null)))
+                .map(|(ty, value)| value_to_cell(ty, value))
+                .collect(),
+        );
+    }
+
+    let columns = names
+        .into_iter()
+        .zip(types)
+        .map(|(name, data_type)| ColumnDef { name, data_type })
+        .collect();
+    Ok((columns, rows))
+}
+
+fn parse_type(text: &str) -> ChType {
+    let text = text.trim();
+    let (name, args) = match text.find('(') {
+        Some(open) if text.ends_with(')') => {
+            (&text[..open], split_args(&text[open + 1..text.len() - 1]))
+        }
+        _ => (text, Vec::new()),
+    };
+    match (name, args.as_slice()) {
+        ("Nullable" | "LowCardinality", [inner]) => parse_type(inner),
+        ("Int8" | "Int16" | "Int32" | "Int64" | "Int128" | "Int256", [])
+        | ("UInt8" | "UInt16" | "UInt32" | "UInt64" | "UInt128" | "UInt256", []) => ChType::Integer,
+        ("Float32" | "Float64" | "BFloat16", []) => ChType::Float,
+        ("Bool", []) => ChType::Bool,
+        ("Decimal", [_, scale]) => ChType::Decimal(scale.parse().unwrap_or(0)),
+        ("Decimal", [_]) => ChType::Decimal(0),
+        ("Decimal32" | "Decimal64" | "Decimal128" | "Decimal256", [scale]) => {
+            ChType::Decimal(scale.parse().unwrap_or(0))
+        }
+        ("UUID", []) => ChType::Uuid,
+        ("Date" | "Date32", []) => ChType::Date,
+        ("DateTime", _) => ChType::DateTime,
+        ("DateTime64", [precision, ..]) => ChType::DateTime64(precision.parse().unwrap_or(3)),
+        ("Enum8" | "Enum16", _) => ChType::Enum,
+        ("JSON" | "Object", _) => ChType::Json,
+        ("Array", [inner]) => ChType::Array(Box::new(parse_type(inner))),
+        ("Map", [key, value]) => {
+            ChType::Map(Box::new(parse_type(key)), Box::new(parse_type(value)))
+        }
+        ("Tuple", elements) if !elements.is_empty() => ChType::Tuple(
+            elements
+                .iter()
+                .map(|e| parse_type(element_type(e)))
+                .collect(),
+        ),
+        _ => ChType::Other,
+    }
+}
+
+/// Top-level comma-separated arguments of a type, e.g. the key and value of
+/// `Map(String, Array(UInt8))`. Commas in nested types and quoted strings
+/// (enum names, time zones) do not split.
+fn split_args(text: &str) -> Vec<&str> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '`' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                args.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(text[start..].trim());
+    args
+}
+
+/// The type of a tuple element, without its name in `Tuple(id UInt64, name String)`.
+fn element_type(element: &str) -> &str {
+    if let Some(rest) = element.strip_prefix('`') {
+        return rest.find('`').map_or(element, |end| rest[end + 1..].trim());
+    }
+    let name_end = element.find(|c: char| c.is_whitespace() || c == '(');
+    match name_end {
+        Some(end) if !element[end..].starts_with('(') => element[end..].trim(),
+        _ => element,
+    }
+}
+
+fn value_to_cell(ty: &ChType, value: &Value) -> CellValue {
+    if value.is_null() {
+        return CellValue::Null;
+    }
+    let text = value.as_str();
+    let cell = match ty {
+        ChType::Integer => match value {
+            Value::Number(n) => n.as_i64().map(CellValue::Int),
+            // UInt64 values above i64::MAX and wider integers keep every digit.
+            _ => text.and_then(|s| {
+                s.parse()
+                    .ok()
+                    .map(CellValue::Int)
+                    .or_else(|| decimal_cell(s))
+            }),
+        },
+        ChType::Float => match value {
+            Value::Number(n) => n.as_f64().map(CellValue::Float),
+            _ => text.and_then(|s| s.parse().ok()).map(CellValue::Float),
+        },
+        ChType::Bool => value.as_bool().map(CellValue::Bool),
+        ChType::Decimal(scale) => match value {
+            Value::String(s) => decimal(s, *scale),
+            Value::Number(n) => decimal(&n.to_string(), *scale),
+            _ => None,
+        },
+        ChType::Uuid => text.map(|s| CellValue::Uuid(s.to_string())),
+        ChType::Date => text.map(|s| CellValue::Date(s.to_string())),
+        // Wall-clock time in the column's time zone, as ClickHouse shows it.
+        ChType::DateTime | ChType::DateTime64(0..=6) => text
+            .and_then(|s| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f").ok())
+            .map(datetime_cell),
+        ChType::Enum => text.map(|s| CellValue::Enum(s.to_string())),
+        ChType::Json => Some(CellValue::Json(match value {
+            Value::String(s) => s.clone(),
+            v => v.to_string(),
+        })),
+        ChType::Array(element) => value.as_array().map(|items| {
+            CellValue::Array(
+                items
+                    .iter()
+                    .map(|item| value_to_cell(element, item))
+                    .collect(),
+            )
+        }),
+        ChType::Map(..) | ChType::Tuple(_) => Some(CellValue::Json(to_json(ty, value).to_string())),
+        // Sub-microsecond DateTime64 values stay text so no digits are lost.
+        ChType::DateTime64(_) | ChType::Other => None,
+    };
+    cell.unwrap_or_else(|| untyped_cell(value))
+}
+
+/// A value as its JSON type suggests, for types without a decoder.
+fn untyped_cell(value: &Value) -> CellValue {
+    match value {
+        Value::Null => CellValue::Null,
+        Value::Bool(b) => CellValue::Bool(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => CellValue::Int(i),
+            None => n
+                .as_f64()
+                .map_or_else(|| CellValue::Text(n.to_string()), CellValue::Float),
+        },
+        Value::String(s) => CellValue::Text(s.clone()),
+        v => CellValue::Json(v.to_string()),
+    }
+}
+
+/// A `Decimal(P, S)` value with all `S` fractional digits, which ClickHouse
+/// trims trailing zeros from.
+fn decimal(text: &str, scale: u32) -> Option<CellValue> {
+    match decimal_cell(text)? {
+        CellValue::Decimal { digits, scale: s } if s < scale && digits != "0" => {
+            Some(CellValue::Decimal {
+                digits: format!("{}{}", digits, "0".repeat((scale - s) as usize)),
+                scale,
+            })
+        }
+        CellValue::Decimal { digits, scale: s } => Some(CellValue::Decimal {
+            digits,
+            scale: s.max(scale),
+        }),
+        cell => Some(cell),
+    }
+}
+
+/// A map or tuple as JSON, with quoted 64-bit integers turned back into
+/// numbers where JSON can hold them.
+fn to_json(ty: &ChType, value: &Value) -> Value {
+    match (ty, value) {
+        (ChType::Integer, Value::String(s)) => s
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| s.parse::<u64>().map(Value::from))
+            .unwrap_or_else(|_| value.clone()),
+        (ChType::Array(element), Value::Array(items)) => {
+            Value::Array(items.iter().map(|item| to_json(element, item)).collect())
+        }
+        (ChType::Map(_, element), Value::Object(entries)) => Value::Object(
+            entries
+                .iter()
+                .map(|(key, item)| (key.clone(), to_json(element, item)))
+                .collect(),
+        ),
+        (ChType::Tuple(elements), Value::Array(items)) => Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| to_json(elements.get(i).unwrap_or(&ChType::Other), item))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::cell_text;
+
+    fn cell(ty: &str, value: &str) -> CellValue {
+        value_to_cell(&parse_type(ty), &serde_json::from_str(value).unwrap())
+    }
+
+    fn text(ty: &str, value: &str) -> Option<String> {
+        cell_text(&cell(ty, value))
+    }
+
+    #[test]
+    fn test_parse_type_unwraps_and_nests() {
+        assert_eq!(
+            parse_type("LowCardinality(Nullable(String))"),
+            ChType::Other
+        );
+        assert_eq!(parse_type("Nullable(Decimal(18, 4))"), ChType::Decimal(4));
+        assert_eq!(parse_type("Decimal128(10)"), ChType::Decimal(10));
+        assert_eq!(
+            parse_type("DateTime64(9, 'Asia/Kolkata')"),
+            ChType::DateTime64(9)
+        );
+        assert_eq!(parse_type("DateTime('Europe/Berlin')"), ChType::DateTime);
+        assert_eq!(parse_type("Enum8('a,b' = 1, 'c)' = 2)"), ChType::Enum);
+        assert_eq!(
+            parse_type("Map(LowCardinality(String), Array(Nullable(UInt64)))"),
+            ChType::Map(
+                Box::new(ChType::Other),
+                Box::new(ChType::Array(Box::new(ChType::Integer)))
+            )
+        );
+        assert_eq!(
+            parse_type("Tuple(id UInt64, `full name` String, Nullable(Float64))"),
+            ChType::Tuple(vec![ChType::Integer, ChType::Other, ChType::Float])
+        );
+    }
+
+    #[test]
+    fn test_quoted_integers() {
+        assert!(matches!(cell("UInt64", "\"42\""), CellValue::Int(42)));
+        assert!(matches!(cell("Int32", "-7"), CellValue::Int(-7)));
+        assert_eq!(
+            text("UInt64", "\"18446744073709551615\"").as_deref(),
+            Some("18446744073709551615")
+        );
+        assert_eq!(
+            text("Int128", "\"-170141183460469231731687303715884105728\"").as_deref(),
+            Some("-170141183460469231731687303715884105728")
+        );
+    }
+
+    #[test]
+    fn test_decimal_keeps_scale() {
+        assert_eq!(
+            text("Decimal(38, 4)", "\"12345678901234567890.5\"").as_deref(),
+            Some("12345678901234567890.5000")
+        );
+        assert_eq!(text("Decimal32(2)", "\"-0.05\"").as_deref(), Some("-0.05"));
+        assert_eq!(text("Decimal(10, 2)", "\"0\"").as_deref(), Some("0.00"));
+        assert_eq!(text("Decimal(10, 2)", "3.5").as_deref(), Some("3.50"));
+    }
+
+    #[test]
+    fn test_floats_and_denormals() {
+        assert!(matches!(cell("Float64", "1.5"), CellValue::Float(v) if v == 1.5));
+        assert!(matches!(cell("Float32", "\"inf\""), CellValue::Float(v) if v == f64::INFINITY));
+        assert!(matches!(cell("Float64", "\"nan\""), CellValue::Float(v) if v.is_nan()));
+    }
+
+    #[test]
+    fn test_dates_and_times() {
+        assert!(
+            matches!(cell("Date32", "\"2024-02-29\""), CellValue::Date(d) if d == "2024-02-29")
+        );
+        assert_eq!(
+            text("DateTime('UTC')", "\"2024-02-29 12:35:01\"").as_deref(),
+            Some("2024-02-29 12:35:01")
+        );
+        assert_eq!(
+            text("Nullable(DateTime64(3))", "\"2024-02-29 12:35:01.120\"").as_deref(),
+            Some("2024-02-29 12:35:01.120")
+        );
+        assert!(matches!(
+            cell("DateTime64(9)", "\"2024-02-29 12:35:01.123456789\""),
+            CellValue::Text(t) if t == "2024-02-29 12:35:01.123456789"
+        ));
+    }
+
+    #[test]
+    fn test_nested_values() {
+        let CellValue::Array(items) = cell("Array(Nullable(UInt64))", "[\"1\", null, \"3\"]")
+        else {
+            panic!("expected an array");
+        };
+        assert!(matches!(
+            items.as_slice(),
+            [CellValue::Int(1), CellValue::Null, CellValue::Int(3)]
+        ));
+        assert!(matches!(
+            cell("Map(String, UInt64)", "{\"a\": \"1\"}"),
+            CellValue::Json(j) if j == "{\"a\":1}"
+        ));
+        assert!(matches!(
+            cell("Tuple(a Int64, b Array(String))", "[\"-2\", [\"x\"]]"),
+            CellValue::Json(j) if j == "[-2,[\"x\"]]"
+        ));
+        assert!(matches!(cell("LowCardinality(String)", "\"x\""), CellValue::Text(t) if t == "x"));
+        assert!(matches!(cell("Enum8('x' = 1)", "\"x\""), CellValue::Enum(t) if t == "x"));
+        assert!(matches!(cell("Nullable(String)", "null"), CellValue::Null));
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let body = "[\"n\",\"d\"]\n[\"UInt64\",\"Nullable(Decimal(9, 2))\"]\n[\"1\",\"2.5\"]\n[\"2\",null]\n";
+        let (columns, rows) = parse_response(body).unwrap();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[1].name, "d");
+        assert_eq!(columns[1].data_type, "Nullable(Decimal(9, 2))");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(cell_text(&rows[0][1]).as_deref(), Some("2.50"));
+        assert!(matches!(rows[1][1], CellValue::Null));
+
+        // Empty results still carry their columns.
+        let (columns, rows) = parse_response("[\"n\"]\n[\"String\"]\n").unwrap();
+        assert_eq!(columns.len(), 1);
+        assert!(rows.is_empty());
+        assert!(parse_response("").unwrap().0.is_empty());
+    }
+}
@@ -1,16 +1,28 @@
 // BigQuery driver — REST-based via gcp-bigquery-client crate.
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use gcp_bigquery_client::model::field_type::FieldType;
+use gcp_bigquery_client::model::get_query_results_parameters::GetQueryResultsParameters;
+use gcp_bigquery_client::model::query_parameter::QueryParameter;
+use gcp_bigquery_client::model::query_parameter_type::QueryParameterType;
+use gcp_bigquery_client::model::query_parameter_value::QueryParameterValue;
 use gcp_bigquery_client::model::query_request::QueryRequest;
+use gcp_bigquery_client::model::query_response::{QueryResponse as BqQueryResponse, ResultSet};
 use gcp_bigquery_client::Client;
 
+use crate::db::cancel::{current_query_id, ServerHandles};
 use crate::db::escape::escape_sql_literal;
+use crate::db::plan::parse_bigquery;
+use crate::db::statement::analyze;
 use crate::db::traits::{DbDriver, SqlDriver};
+use crate::db::types::{
+    cell_text, date_cell, datetime_cell, decimal_cell, time_cell, unbindable_param,
+};
 use crate::error::AppError;
 use crate::models::connection::{CloudAuth, ConnectionConfig, DatabaseCategory};
+use crate::models::plan::QueryPlan;
 use crate::models::query::{CellValue, ColumnDef, QueryResponse};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo, SchemaInfo, TableInfo,
@@ -40,9 +52,104 @@ fn field_type_to_string(ft: &FieldType) -> String {
     }
 }
 
+/// Build a query request carrying `params` as positional query parameters.
+fn query_request(sql: &str, params: &[CellValue]) -> Result<QueryRequest, AppError> {
+    let mut req = QueryRequest::new(sql);
+    if !params.is_empty() {
+        let parameters = params
+            .iter()
+            .map(query_parameter)
+            .collect::<Result<Vec<_>, _>>()?;
+        req.parameter_mode = Some("POSITIONAL".to_string());
+        req.query_parameters = Some(parameters);
+    }
+    Ok(req)
+}
+
+fn query_parameter(cell: &CellValue) -> Result<QueryParameter, AppError> {
+    let (type_name, value) = match cell {
+        CellValue::Null => ("STRING", None),
+        CellValue::Bool(v) => ("BOOL", Some(v.to_string())),
+        CellValue::Int(v) => ("INT64", Some(v.to_string())),
+        CellValue::Float(v) => ("FLOAT64", Some(v.to_string())),
+        CellValue::Text(v) => ("STRING", Some(v.clone())),
+        CellValue::Timestamp(v) => ("TIMESTAMP", Some(v.clone())),
+        CellValue::Json(v) => ("JSON", Some(v.clone())),
+        // NUMERIC holds 38 digits with at most 9 after the point.
+        CellValue::Decimal { digits, scale } => {
+            let integer_digits = digits.trim_start_matches('-').len().saturating_sub(*scale as usize);
+            let type_name = if *scale <= 9 && integer_digits <= 29 { "NUMERIC" } else { "BIGNUMERIC" };
+            (type_name, cell_text(cell))
+        }
+        CellValue::Date(_) => ("DATE", cell_text(cell)),
+        CellValue::Time(_) => ("TIME", cell_text(cell)),
+        CellValue::DateTime { tz: None, .. } => ("DATETIME", cell_text(cell)),
+        CellValue::DateTime { tz: Some(_), .. } => ("TIMESTAMP", cell_text(cell)),
+        CellValue::Uuid(v) | CellValue::Enum(v) => ("STRING", Some(v.clone())),
+        CellValue::Binary(_) => {
+            return Err(AppError::UnsupportedOperation(
+                "Binary parameters are not supported for BigQuery".to_string(),
+            ));
+        }
+        CellValue::Interval { .. } | CellValue::Array(_) => {
+            return Err(AppError::UnsupportedOperation(
+                "Interval and array parameters are not supported for BigQuery".to_string(),
+            ));
+        }
+        CellValue::LargeText { .. } | CellValue::LargeJson { .. } | CellValue::LargeBinary { .. } => {
+            return Err(unbindable_param());
+        }
+    };
+    Ok(QueryParameter {
+        name: None,
+        parameter_type: Some(QueryParameterType {
+            array_type: None,
+            struct_types: None,
+            r#type: type_name.to_string(),
+        }),
+        parameter_value: Some(QueryParameterValue {
+            array_values: None,
+            struct_values: None,
+            value,
+        }),
+    })
+}
+
+/// TIMESTAMP values arrive as seconds since the epoch, possibly in E notation
+/// (`1.7000000001234E9`); they are converted without going through a float.
+fn timestamp_cell(value: &str) -> Option<CellValue> {
+    let CellValue::Decimal { digits, scale } = decimal_cell(value)? else {
+        return None;
+    };
+    let digits: i128 = digits.parse().ok()?;
+    let micros = match scale {
+        0..=6 => digits * 10i128.pow(6 - scale),
+        _ => digits / 10i128.checked_pow(scale - 6)?,
+    };
+    Some(CellValue::DateTime {
+        tz: Some("+00:00".to_string()),
+        micros: i64::try_from(micros).ok()?,
+    })
+}
+
+/// How long each jobs.query / getQueryResults call waits before returning,
+/// so a running job's id is known early enough to cancel it.
+const JOB_POLL_TIMEOUT_MS: i32 = 2_000;
+
+/// Reference to a running BigQuery job.
+#[derive(Clone)]
+struct JobHandle {
+    job_id: String,
+    location: Option<String>,
+}
+
 pub struct BigQueryDriver {
     client: Client,
     project_id: String,
+    /// Jobs still running after this long are cancelled.
+    statement_timeout: Option<Duration>,
+    /// Jobs started under a query id, for jobs.cancel.
+    running: ServerHandles<JobHandle>,
 }
 
 impl BigQueryDriver {
@@ -75,20 +182,82 @@ impl BigQueryDriver {
             .await
             .map_err(|e| AppError::Database(format!("Failed to connect to BigQuery: {}", e)))?;
 
-        Ok(Self { client, project_id })
+        Ok(Self {
+            client,
+            project_id,
+            statement_timeout: config.statement_timeout_ms.map(Duration::from_millis),
+            running: ServerHandles::new(),
+        })
     }
 
-    /// Execute a query and parse the result set.
-    async fn query_to_response(&self, sql: &str) -> Result<(Vec<ColumnDef>, Vec<Vec<CellValue>>), AppError> {
-        let req = QueryRequest::new(sql);
+    /// Run a query job to completion. When the job outlives the initial request
+    /// its id is recorded under the current query id and results are polled.
+    /// A job still running at the statement timeout is cancelled.
+    async fn run_job(&self, mut req: QueryRequest) -> Result<ResultSet, AppError> {
+        let deadline = self.statement_timeout.map(|t| Instant::now() + t);
+        if current_query_id().is_some() || deadline.is_some() {
+            req.timeout_ms = Some(JOB_POLL_TIMEOUT_MS);
+        }
 
-        let mut result = self
+        let result = self
             .client
             .job()
             .query(&self.project_id, req)
             .await
             .map_err(|e| AppError::Database(format!("BigQuery query error: {}", e)))?;
 
+        let response = result.query_response();
+        if response.job_complete != Some(false) {
+            return Ok(result);
+        }
+
+        let job_ref = response.job_reference.clone().ok_or_else(|| {
+            AppError::Database("BigQuery returned an incomplete job without a reference".to_string())
+        })?;
+        let job = JobHandle {
+            job_id: job_ref.job_id.unwrap_or_default(),
+            location: job_ref.location,
+        };
+        let _tracked = self.running.track(job.clone());
+
+        loop {
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                self.client
+                    .job()
+                    .cancel_job(&self.project_id, &job.job_id, job.location.as_deref())
+                    .await
+                    .map_err(|e| AppError::Database(format!("BigQuery cancel error: {}", e)))?;
+                let timeout = self.statement_timeout.unwrap_or_default();
+                return Err(AppError::QueryTimeout(timeout.as_millis().div_ceil(1000) as u64));
+            }
+            let params = GetQueryResultsParameters {
+                location: job.location.clone(),
+                timeout_ms: Some(JOB_POLL_TIMEOUT_MS),
+                ..Default::default()
+            };
+            let page = self
+                .client
+                .job()
+                .get_query_results(&self.project_id, &job.job_id, params)
+                .await
+                .map_err(|e| AppError::Database(format!("BigQuery query error: {}", e)))?;
+            if page.job_complete == Some(true) {
+                return Ok(ResultSet::new(BqQueryResponse::from(page)));
+            }
+        }
+    }
+
+    /// Execute a query and parse the result set.
+    async fn query_to_response(&self, sql: &str) -> Result<(Vec<ColumnDef>, Vec<Vec<CellValue>>), AppError> {
+        self.request_to_response(QueryRequest::new(sql)).await
+    }
+
+    async fn request_to_response(
+        &self,
+        req: QueryRequest,
+    ) -> Result<(Vec<ColumnDef>, Vec<Vec<CellValue>>), AppError> {
+        let mut result = self.run_job(req).await?;
+
         // Build column definitions from schema
         let schema_fields = result
             .query_response()
@@ -125,15 +294,30 @@ impl BigQueryDriver {
                             "INTEGER" | "INT64" => {
                                 value.parse::<i64>().map(CellValue::Int).unwrap_or(CellValue::Text(value))
                             }
-                            "FLOAT" | "FLOAT64" | "NUMERIC" | "BIGNUMERIC" => {
+                            "FLOAT" | "FLOAT64" => {
                                 value.parse::<f64>().map(CellValue::Float).unwrap_or(CellValue::Text(value))
                             }
+                            "NUMERIC" | "BIGNUMERIC" => {
+                                decimal_cell(&value).unwrap_or(CellValue::Text(value))
+                            }
                             "BOOLEAN" | "BOOL" => {
                                 CellValue::Bool(value.to_lowercase() == "true")
                             }
-                            "TIMESTAMP" | "DATETIME" | "DATE" | "TIME" => {
-                                CellValue::Timestamp(value)
+                            "TIMESTAMP" => timestamp_cell(&value).unwrap_or(CellValue::Timestamp(value)),
+                            "DATETIME" => {
+                                match chrono::NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S%.f") {
+                                    Ok(ts) => datetime_cell(ts),
+                                    Err(_) => CellValue::Timestamp(value),
+                                }
                             }
+                            "DATE" => match chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+                                Ok(date) => date_cell(date),
+                                Err(_) => CellValue::Timestamp(value),
+                            },
+                            "TIME" => match chrono::NaiveTime::parse_from_str(&value, "%H:%M:%S%.f") {
+                                Ok(time) => time_cell(time),
+                                Err(_) => CellValue::Timestamp(value),
+                            },
                             "RECORD" | "STRUCT" | "JSON" => {
                                 CellValue::Json(value)
                             }
@@ -163,18 +347,23 @@ impl DbDriver for BigQueryDriver {
     }
 
     async fn execute_raw(&self, sql: &str) -> Result<QueryResponse, AppError> {
+        self.execute_params(sql, Vec::new()).await
+    }
+
+    /// Parameters are sent as positional (`?`) BigQuery query parameters.
+    async fn execute_params(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
         let start = Instant::now();
         let trimmed = sql.trim();
-        let upper = trimmed.to_uppercase();
+        let returns_rows = analyze(trimmed, "bigquery").returns_rows;
 
-        let is_query = upper.starts_with("SELECT")
-            || upper.starts_with("WITH")
-            || upper.starts_with("SHOW")
-            || upper.starts_with("DESCRIBE")
-            || upper.starts_with("EXPLAIN");
-
-        if is_query {
-            let (columns, rows) = self.query_to_response(trimmed).await?;
+        if returns_rows {
+            let (columns, rows) = self
+                .request_to_response(query_request(trimmed, &params)?)
+                .await?;
             let elapsed = start.elapsed().as_millis() as u64;
             let row_count = rows.len();
 
@@ -186,16 +375,11 @@ impl DbDriver for BigQueryDriver {
                 affected_rows: None,
                 truncated: false,
                 max_rows_limit: None,
+                messages: Vec::new(),
             })
         } else {
             // DML / DDL
-            let req = QueryRequest::new(trimmed);
-
-            self.client
-                .job()
-                .query(&self.project_id, req)
-                .await
-                .map_err(|e| AppError::Database(format!("BigQuery execute error: {}", e)))?;
+            self.run_job(query_request(trimmed, &params)?).await?;
 
             let elapsed = start.elapsed().as_millis() as u64;
 
@@ -207,10 +391,61 @@ impl DbDriver for BigQueryDriver {
                 affected_rows: Some(0),
                 truncated: false,
                 max_rows_limit: None,
+                messages: Vec::new(),
             })
         }
     }
 
+    /// BigQuery only reports a plan for a job that has run, so the query is
+    /// executed and its stages are read from the job statistics. Writes cannot
+    /// be rolled back and are refused.
+    async fn explain(&self, sql: &str, run: bool) -> Result<QueryPlan, AppError> {
+        if !run {
+            return Err(AppError::UnsupportedOperation(
+                "BigQuery has no estimated plans; explain with analyze to run the query".to_string(),
+            ));
+        }
+        if !analyze(sql.trim(), "bigquery").read_only {
+            return Err(AppError::UnsupportedOperation(
+                "BigQuery cannot explain a statement that modifies data".to_string(),
+            ));
+        }
+
+        let result = self.run_job(QueryRequest::new(sql.trim())).await?;
+        let job_ref = result.query_response().job_reference.clone().ok_or_else(|| {
+            AppError::Database("BigQuery returned a result without a job reference".to_string())
+        })?;
+        let job = self
+            .client
+            .job()
+            .get_job(
+                &self.project_id,
+                &job_ref.job_id.unwrap_or_default(),
+                job_ref.location.as_deref(),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("BigQuery job error: {}", e)))?;
+        let stages = job
+            .statistics
+            .and_then(|statistics| statistics.query)
+            .and_then(|query| query.query_plan)
+            .unwrap_or_default();
+        parse_bigquery(&serde_json::to_value(stages)?)
+    }
+
+    async fn cancel(&self, query_id: &str) -> Result<bool, AppError> {
+        let job = match self.running.get(query_id) {
+            Some(job) => job,
+            None => return Ok(false),
+        };
+        self.client
+            .job()
+            .cancel_job(&self.project_id, &job.job_id, job.location.as_deref())
+            .await
+            .map_err(|e| AppError::Database(format!("BigQuery cancel error: {}", e)))?;
+        Ok(true)
+    }
+
     async fn health_check(&self) -> Result<(), AppError> {
         self.execute_raw("SELECT 1").await.map(|_| ())
     }
@@ -1,11 +1,17 @@
 use std::time::Instant;
 
 use async_trait::async_trait;
-use scylla::frame::response::result::CqlValue;
-use scylla::Session;
-use scylla::SessionBuilder;
+use chrono::Timelike;
+use scylla::frame::response::result::{ColumnType, CqlValue};
+use scylla::frame::value::{
+    CqlDate, CqlDecimal, CqlDuration, CqlTime, CqlTimestamp, CqlTimeuuid, CqlVarint,
+};
+use scylla::{QueryResult, Session, SessionBuilder};
 
 use crate::db::traits::{DbDriver, SqlDriver};
+use crate::db::types::{
+    cell_text, date_cell, digits_to_signed_be, signed_be_digits, time_cell, unbindable_param,
+};
 use crate::error::AppError;
 use crate::models::connection::{ConnectionConfig, DatabaseCategory};
 use crate::models::query::{CellValue, ColumnDef, QueryResponse};
@@ -35,52 +41,8 @@ impl CassandraDriver {
         Ok(Self { session })
     }
 
-    fn cql_value_to_cell(value: &CqlValue) -> CellValue {
-        match value {
-            CqlValue::Boolean(b) => CellValue::Bool(*b),
-            CqlValue::TinyInt(i) => CellValue::Int(*i as i64),
-            CqlValue::SmallInt(i) => CellValue::Int(*i as i64),
-            CqlValue::Int(i) => CellValue::Int(*i as i64),
-            CqlValue::BigInt(i) => CellValue::Int(*i),
-            CqlValue::Float(f) => CellValue::Float(*f as f64),
-            CqlValue::Double(f) => CellValue::Float(*f),
-            CqlValue::Text(s) | CqlValue::Ascii(s) => CellValue::Text(s.clone()),
-            CqlValue::Blob(b) => CellValue::Binary(b.clone()),
-            CqlValue::Uuid(u) => CellValue::Text(u.to_string()),
-            CqlValue::Timeuuid(u) => CellValue::Text(u.to_string()),
-            CqlValue::Timestamp(ts) => CellValue::Timestamp(format!("{:?}", ts)),
-            CqlValue::Date(d) => CellValue::Timestamp(format!("{:?}", d)),
-            CqlValue::Time(t) => CellValue::Timestamp(format!("{:?}", t)),
-            CqlValue::Inet(addr) => CellValue::Text(addr.to_string()),
-            CqlValue::Counter(c) => CellValue::Int(c.0),
-            CqlValue::Varint(v) => CellValue::Text(format!("{:?}", v)),
-            CqlValue::Decimal(d) => CellValue::Text(format!("{:?}", d)),
-            CqlValue::Empty => CellValue::Null,
-            _ => CellValue::Text(format!("{:?}", value)),
-        }
-    }
-}
-
-#[async_trait]
-impl DbDriver for CassandraDriver {
-    fn category(&self) -> DatabaseCategory {
-        DatabaseCategory::WideColumn
-    }
-
-    fn dialect_hint(&self) -> &'static str {
-        "cassandra"
-    }
-
-    async fn execute_raw(&self, sql: &str) -> Result<QueryResponse, AppError> {
-        let start = Instant::now();
-        let trimmed = sql.trim();
-
-        let result = self
-            .session
-            .query_unpaged(trimmed, &[])
-            .await
-            .map_err(|e| AppError::Database(format!("Cassandra query error: {}", e)))?;
-
+    /// Convert a CQL query result into a QueryResponse.
+    fn result_to_response(result: QueryResult, start: Instant) -> QueryResponse {
         let elapsed = start.elapsed().as_millis() as u64;
 
         // Extract column specs before consuming rows, since col_specs() borrows result
@@ -110,7 +72,7 @@ impl DbDriver for CassandraDriver {
             }
 
             let row_count = data_rows.len();
-            Ok(QueryResponse {
+            QueryResponse {
                 columns,
                 rows: data_rows,
                 row_count,
@@ -118,9 +80,10 @@ impl DbDriver for CassandraDriver {
                 affected_rows: None,
                 truncated: false,
                 max_rows_limit: None,
-            })
+                messages: Vec::new(),
+            }
         } else {
-            Ok(QueryResponse {
+            QueryResponse {
                 columns: Vec::new(),
                 rows: Vec::new(),
                 row_count: 0,
@@ -128,10 +91,232 @@ impl DbDriver for CassandraDriver {
                 affected_rows: Some(0),
                 truncated: false,
                 max_rows_limit: None,
-            })
+                messages: Vec::new(),
+            }
         }
     }
 
+    /// Convert a bind value into the CQL value expected for a column type.
+    fn cell_to_cql(cell: &CellValue, typ: &ColumnType) -> Result<Option<CqlValue>, AppError> {
+        let invalid = |v: &dyn std::fmt::Display| {
+            AppError::InvalidConfig(format!("Cannot convert '{}' to CQL {:?}", v, typ))
+        };
+        let value = match cell {
+            CellValue::Null => return Ok(None),
+            CellValue::Bool(v) => CqlValue::Boolean(*v),
+            CellValue::Int(v) => match typ {
+                ColumnType::Int => CqlValue::Int(i32::try_from(*v).map_err(|_| invalid(v))?),
+                ColumnType::SmallInt => CqlValue::SmallInt(i16::try_from(*v).map_err(|_| invalid(v))?),
+                ColumnType::TinyInt => CqlValue::TinyInt(i8::try_from(*v).map_err(|_| invalid(v))?),
+                ColumnType::Double => CqlValue::Double(*v as f64),
+                ColumnType::Float => CqlValue::Float(*v as f32),
+                _ => CqlValue::BigInt(*v),
+            },
+            CellValue::Float(v) => match typ {
+                ColumnType::Float => CqlValue::Float(*v as f32),
+                _ => CqlValue::Double(*v),
+            },
+            CellValue::Text(v) | CellValue::Timestamp(v) | CellValue::Json(v) => match typ {
+                ColumnType::Int => CqlValue::Int(v.parse().map_err(|_| invalid(v))?),
+                ColumnType::BigInt => CqlValue::BigInt(v.parse().map_err(|_| invalid(v))?),
+                ColumnType::SmallInt => CqlValue::SmallInt(v.parse().map_err(|_| invalid(v))?),
+                ColumnType::TinyInt => CqlValue::TinyInt(v.parse().map_err(|_| invalid(v))?),
+                ColumnType::Double => CqlValue::Double(v.parse().map_err(|_| invalid(v))?),
+                ColumnType::Float => CqlValue::Float(v.parse().map_err(|_| invalid(v))?),
+                ColumnType::Boolean => CqlValue::Boolean(v.parse().map_err(|_| invalid(v))?),
+                ColumnType::Uuid => CqlValue::Uuid(uuid::Uuid::parse_str(v).map_err(|_| invalid(v))?),
+                ColumnType::Ascii => CqlValue::Ascii(v.clone()),
+                _ => CqlValue::Text(v.clone()),
+            },
+            CellValue::Binary(v) => CqlValue::Blob(v.clone()),
+            CellValue::Uuid(v) => {
+                let uuid = uuid::Uuid::parse_str(v).map_err(|_| invalid(v))?;
+                match typ {
+                    ColumnType::Timeuuid => CqlValue::Timeuuid(CqlTimeuuid::from(uuid)),
+                    _ => CqlValue::Uuid(uuid),
+                }
+            }
+            CellValue::Decimal { digits, scale } if *typ == ColumnType::Decimal => {
+                let bytes = digits_to_signed_be(digits).ok_or_else(|| invalid(digits))?;
+                CqlValue::Decimal(CqlDecimal::from_signed_be_bytes_and_exponent(bytes, *scale as i32))
+            }
+            CellValue::Decimal { digits, scale: 0 } if *typ == ColumnType::Varint => {
+                let bytes = digits_to_signed_be(digits).ok_or_else(|| invalid(digits))?;
+                CqlValue::Varint(CqlVarint::from_signed_bytes_be(bytes))
+            }
+            CellValue::DateTime { micros, .. } if *typ == ColumnType::Timestamp => {
+                CqlValue::Timestamp(CqlTimestamp(micros.div_euclid(1000)))
+            }
+            CellValue::Date(v) if *typ == ColumnType::Date => {
+                let date = chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d").map_err(|_| invalid(v))?;
+                let days = (date - chrono::NaiveDate::default()).num_days();
+                CqlValue::Date(CqlDate((days + (1 << 31)) as u32))
+            }
+            CellValue::Time(v) if *typ == ColumnType::Time => {
+                let time = chrono::NaiveTime::parse_from_str(v, "%H:%M:%S%.f").map_err(|_| invalid(v))?;
+                let nanos = time.num_seconds_from_midnight() as i64 * 1_000_000_000 + time.nanosecond() as i64;
+                CqlValue::Time(CqlTime(nanos))
+            }
+            CellValue::Interval {
+                months,
+                days,
+                micros,
+            } => CqlValue::Duration(CqlDuration {
+                months: *months,
+                days: *days,
+                nanoseconds: micros * 1000,
+            }),
+            CellValue::Array(items) => match typ {
+                ColumnType::List(inner) | ColumnType::Set(inner) => {
+                    let values = items
+                        .iter()
+                        .map(|item| Self::cell_to_cql(item, inner)?.ok_or_else(|| invalid(&"NULL")))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    match typ {
+                        ColumnType::Set(_) => CqlValue::Set(values),
+                        _ => CqlValue::List(values),
+                    }
+                }
+                _ => return Err(invalid(&cell_text(cell).unwrap_or_default())),
+            },
+            // Anything else is converted from its text form.
+            CellValue::Decimal { .. }
+            | CellValue::DateTime { .. }
+            | CellValue::Date(_)
+            | CellValue::Time(_)
+            | CellValue::Enum(_) => {
+                let text = CellValue::Text(cell_text(cell).unwrap_or_default());
+                return Self::cell_to_cql(&text, typ);
+            }
+            CellValue::LargeText { .. } | CellValue::LargeJson { .. } | CellValue::LargeBinary { .. } => {
+                return Err(unbindable_param());
+            }
+        };
+        Ok(Some(value))
+    }
+
+    fn cql_value_to_cell(value: &CqlValue) -> CellValue {
+        match value {
+            CqlValue::Boolean(b) => CellValue::Bool(*b),
+            CqlValue::TinyInt(i) => CellValue::Int(*i as i64),
+            CqlValue::SmallInt(i) => CellValue::Int(*i as i64),
+            CqlValue::Int(i) => CellValue::Int(*i as i64),
+            CqlValue::BigInt(i) => CellValue::Int(*i),
+            CqlValue::Float(f) => CellValue::Float(*f as f64),
+            CqlValue::Double(f) => CellValue::Float(*f),
+            CqlValue::Text(s) | CqlValue::Ascii(s) => CellValue::Text(s.clone()),
+            CqlValue::Blob(b) => CellValue::Binary(b.clone()),
+            CqlValue::Uuid(u) => CellValue::Uuid(u.to_string()),
+            CqlValue::Timeuuid(u) => CellValue::Uuid(u.to_string()),
+            CqlValue::Timestamp(ts) => CellValue::DateTime {
+                tz: Some("+00:00".to_string()),
+                micros: ts.0.saturating_mul(1000),
+            },
+            // Days since 2^31 days before the Unix epoch.
+            CqlValue::Date(d) => chrono::NaiveDate::default()
+                .checked_add_signed(chrono::Duration::days(d.0 as i64 - (1 << 31)))
+                .map(date_cell)
+                .unwrap_or_else(|| CellValue::Text(format!("{:?}", d))),
+            CqlValue::Time(t) => chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+                (t.0 / 1_000_000_000) as u32,
+                (t.0 % 1_000_000_000) as u32,
+            )
+            .map(time_cell)
+            .unwrap_or_else(|| CellValue::Text(format!("{:?}", t))),
+            CqlValue::Duration(d) => CellValue::Interval {
+                months: d.months,
+                days: d.days,
+                micros: d.nanoseconds / 1000,
+            },
+            CqlValue::Inet(addr) => CellValue::Text(addr.to_string()),
+            CqlValue::Counter(c) => CellValue::Int(c.0),
+            CqlValue::Varint(v) => {
+                let digits = signed_be_digits(v.as_signed_bytes_be_slice());
+                match digits.parse() {
+                    Ok(n) => CellValue::Int(n),
+                    Err(_) => CellValue::Decimal { digits, scale: 0 },
+                }
+            }
+            CqlValue::Decimal(d) => {
+                let (bytes, scale) = d.as_signed_be_bytes_slice_and_exponent();
+                let mut digits = signed_be_digits(bytes);
+                if scale < 0 && digits != "0" {
+                    digits.push_str(&"0".repeat(scale.unsigned_abs() as usize));
+                }
+                CellValue::Decimal {
+                    digits,
+                    scale: scale.max(0) as u32,
+                }
+            }
+            CqlValue::List(items) | CqlValue::Set(items) => {
+                CellValue::Array(items.iter().map(Self::cql_value_to_cell).collect())
+            }
+            CqlValue::Empty => CellValue::Null,
+            _ => CellValue::Text(format!("{:?}", value)),
+        }
+    }
+}
+
+#[async_trait]
+impl DbDriver for CassandraDriver {
+    fn category(&self) -> DatabaseCategory {
+        DatabaseCategory::WideColumn
+    }
+
+    fn dialect_hint(&self) -> &'static str {
+        "cassandra"
+    }
+
+    async fn execute_raw(&self, sql: &str) -> Result<QueryResponse, AppError> {
+        let start = Instant::now();
+        let trimmed = sql.trim();
+
+        let result = self
+            .session
+            .query_unpaged(trimmed, &[])
+            .await
+            .map_err(|e| AppError::Database(format!("Cassandra query error: {}", e)))?;
+
+        Ok(Self::result_to_response(result, start))
+    }
+
+    /// Prepares the statement so each `?` value can be converted to the CQL
+    /// type the server expects for that position.
+    async fn execute_params(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        let start = Instant::now();
+        let prepared = self
+            .session
+            .prepare(sql.trim())
+            .await
+            .map_err(|e| AppError::Database(format!("Cassandra prepare error: {}", e)))?;
+
+        let specs = prepared.get_variable_col_specs();
+        if specs.len() != params.len() {
+            return Err(AppError::InvalidConfig(format!(
+                "Query has {} placeholder(s) but {} parameter(s) were supplied",
+                specs.len(),
+                params.len()
+            )));
+        }
+        let values = params
+            .iter()
+            .zip(specs.iter())
+            .map(|(cell, spec)| Self::cell_to_cql(cell, &spec.typ))
+            .collect::<Result<Vec<Option<CqlValue>>, AppError>>()?;
+
+        let result = self
+            .session
+            .execute_unpaged(&prepared, values)
+            .await
+            .map_err(|e| AppError::Database(format!("Cassandra query error: {}", e)))?;
+
+        Ok(Self::result_to_response(result, start))
+    }
+
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
         let schemas = self.get_schemas().await?;
         Ok(schemas
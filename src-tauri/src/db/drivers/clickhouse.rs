@@ -1,19 +1,54 @@
 use std::time::Instant;
 
 use async_trait::async_trait;
+use clickhouse::query::Query;
 use clickhouse::Client;
 
+use crate::db::cancel::{current_query_id, ServerHandles, TrackedQuery};
+use crate::db::clickhouse_types;
 use crate::db::escape::{escape_sql_literal, validate_identifier};
+use crate::db::plan::{explain_target, parse_clickhouse, plan_document};
+use crate::db::statement::{analyze, tokenize, TokenKind};
 use crate::db::traits::{DbDriver, SqlDriver};
+use crate::db::types::{cell_text, naive_datetime, unbindable_param};
 use crate::error::AppError;
 use crate::models::connection::{ConnectionConfig, DatabaseCategory};
+use crate::models::plan::QueryPlan;
 use crate::models::query::{CellValue, ColumnDef, QueryResponse};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo, SchemaInfo, TableInfo,
 };
 
+/// Name/value pairs of a `SET name = value[, ...]` statement, or `None` for
+/// anything else. Quoted values are unquoted.
+fn parse_settings(statement: &str) -> Option<Vec<(String, String)>> {
+    let tokens: Vec<_> = tokenize(statement, "clickhouse")
+        .into_iter()
+        .filter(|t| t.kind != TokenKind::Comment && !t.is_punct(';'))
+        .collect();
+    if !tokens.first()?.is_keyword("SET") {
+        return None;
+    }
+    let mut settings = Vec::new();
+    for assignment in tokens[1..].split(|t| t.is_punct(',')) {
+        match assignment {
+            [name, eq, value] if name.kind == TokenKind::Word && eq.is_punct('=') => {
+                let value = match value.kind {
+                    TokenKind::String => value.text[1..value.text.len() - 1].to_string(),
+                    _ => value.text.to_string(),
+                };
+                settings.push((name.text.to_string(), value));
+            }
+            _ => return None,
+        }
+    }
+    Some(settings)
+}
+
 pub struct ClickHouseDriver {
     client: Client,
+    /// ClickHouse query_ids of statements started under a query id, for `KILL QUERY`.
+    running: ServerHandles<String>,
 }
 
 impl ClickHouseDriver {
@@ -33,11 +68,37 @@ impl ClickHouseDriver {
             client = client.with_password(config.password_or_default());
         }
 
-        let database = config.database_or_default().to_string();
+        // ClickHouse schemas are databases.
+        let database = config
+            .default_schema
+            .as_deref()
+            .filter(|s| !s.is_empty())
+            .unwrap_or(config.database_or_default())
+            .to_string();
         if !database.is_empty() {
             client = client.with_database(&database);
         }
 
+        // Settings are sent with every request, so they hold for each statement.
+        if let Some(ms) = config.statement_timeout_ms {
+            client = client.with_option("max_execution_time", ms.div_ceil(1000).to_string());
+        }
+        if let Some(ms) = config.lock_timeout_ms {
+            client = client.with_option("lock_acquire_timeout", ms.div_ceil(1000).to_string());
+        }
+        // Without a persistent session the init script can only carry settings.
+        for statement in &config.init_sql {
+            let settings = parse_settings(statement).ok_or_else(|| {
+                AppError::InvalidConfig(format!(
+                    "ClickHouse init statements must be SET statements: {}",
+                    statement
+                ))
+            })?;
+            for (name, value) in settings {
+                client = client.with_option(name, value);
+            }
+        }
+
         // Test connection
         client
             .query("SELECT 1")
@@ -45,74 +106,87 @@ impl ClickHouseDriver {
             .await
             .map_err(|e| AppError::Database(format!("Failed to connect to ClickHouse: {}", e)))?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            running: ServerHandles::new(),
+        })
+    }
+
+    /// Client for the next statement. When a query id is set, the statement is
+    /// sent with it as its ClickHouse `query_id` so `cancel` can kill it.
+    fn statement_client(&self) -> (Client, Option<TrackedQuery<'_, String>>) {
+        match current_query_id() {
+            Some(query_id) => (
+                self.client.clone().with_option("query_id", query_id.clone()),
+                self.running.track(query_id),
+            ),
+            None => (self.client.clone(), None),
+        }
     }
 
     async fn query_to_response(&self, sql: &str) -> Result<(Vec<ColumnDef>, Vec<Vec<CellValue>>), AppError> {
-        // Use JSONEachRow format for easier parsing
-        let query_with_format = format!("{} FORMAT JSONEachRow", sql.trim().trim_end_matches(';'));
-
-        let raw = self
-            .client
-            .query(&query_with_format)
-            .fetch_all::<String>()
-            .await;
-
-        match raw {
-            Ok(rows_str) => {
-                if rows_str.is_empty() {
-                    return Ok((Vec::new(), Vec::new()));
-                }
+        self.query_to_response_params(sql, &[]).await
+    }
 
-                let mut columns = Vec::new();
-                let mut rows = Vec::new();
-
-                for (idx, row_str) in rows_str.iter().enumerate() {
-                    let obj: serde_json::Value = serde_json::from_str(row_str)
-                        .map_err(|e| AppError::Serialization(format!("Failed to parse ClickHouse row: {}", e)))?;
-
-                    if let serde_json::Value::Object(map) = obj {
-                        if idx == 0 {
-                            columns = map
-                                .keys()
-                                .map(|k| ColumnDef {
-                                    name: k.clone(),
-                                    data_type: "String".to_string(),
-                                })
-                                .collect();
-                        }
-
-                        let row: Vec<CellValue> = columns
-                            .iter()
-                            .map(|col| {
-                                match map.get(&col.name) {
-                                    Some(serde_json::Value::Null) => CellValue::Null,
-                                    Some(serde_json::Value::Bool(b)) => CellValue::Bool(*b),
-                                    Some(serde_json::Value::Number(n)) => {
-                                        if let Some(i) = n.as_i64() {
-                                            CellValue::Int(i)
-                                        } else if let Some(f) = n.as_f64() {
-                                            CellValue::Float(f)
-                                        } else {
-                                            CellValue::Text(n.to_string())
-                                        }
-                                    }
-                                    Some(serde_json::Value::String(s)) => CellValue::Text(s.clone()),
-                                    Some(v) => CellValue::Json(v.to_string()),
-                                    None => CellValue::Null,
-                                }
-                            })
-                            .collect();
-
-                        rows.push(row);
-                    }
-                }
+    async fn query_to_response_params(
+        &self,
+        sql: &str,
+        params: &[CellValue],
+    ) -> Result<(Vec<ColumnDef>, Vec<Vec<CellValue>>), AppError> {
+        let sql = sql.trim().trim_end_matches(';');
+
+        let (client, _tracked) = self.statement_client();
+        let mut query = bind_params(client.query(sql), params)?;
+        for (name, value) in clickhouse_types::FORMAT_SETTINGS {
+            query = query.with_option(name, value);
+        }
+        let body = query
+            .fetch_bytes(clickhouse_types::FORMAT)
+            .map_err(|e| AppError::Database(format!("ClickHouse query error: {}", e)))?
+            .collect()
+            .await
+            .map_err(|e| AppError::Database(format!("ClickHouse query error: {}", e)))?;
 
-                Ok((columns, rows))
+        clickhouse_types::parse_response(&String::from_utf8_lossy(&body))
+    }
+}
+
+/// Bind values to the clickhouse crate's `?` placeholders, which it serializes
+/// as properly escaped literals on the client side.
+fn bind_params(mut query: Query, params: &[CellValue]) -> Result<Query, AppError> {
+    for cell in params {
+        query = match cell {
+            CellValue::Null => query.bind(None::<i64>),
+            CellValue::Bool(v) => query.bind(*v),
+            CellValue::Int(v) => query.bind(*v),
+            CellValue::Float(v) => query.bind(*v),
+            CellValue::Text(v) | CellValue::Timestamp(v) | CellValue::Json(v) => query.bind(v.as_str()),
+            // Quoted literals are converted to the compared column's type.
+            CellValue::Decimal { .. }
+            | CellValue::Uuid(_)
+            | CellValue::Date(_)
+            | CellValue::Time(_)
+            | CellValue::Enum(_) => query.bind(cell_text(cell)),
+            CellValue::DateTime { micros, .. } => match naive_datetime(*micros) {
+                Some(ts) => query.bind(ts.format("%Y-%m-%d %H:%M:%S%.f").to_string()),
+                None => query.bind(*micros),
+            },
+            CellValue::Interval { .. } | CellValue::Array(_) => {
+                return Err(AppError::UnsupportedOperation(
+                    "Interval and array parameters are not supported for ClickHouse".to_string(),
+                ));
             }
-            Err(e) => Err(AppError::Database(format!("ClickHouse query error: {}", e))),
-        }
+            CellValue::Binary(_) => {
+                return Err(AppError::UnsupportedOperation(
+                    "Binary parameters are not supported for ClickHouse".to_string(),
+                ));
+            }
+            CellValue::LargeText { .. } | CellValue::LargeJson { .. } | CellValue::LargeBinary { .. } => {
+                return Err(unbindable_param());
+            }
+        };
     }
+    Ok(query)
 }
 
 #[async_trait]
@@ -126,18 +200,20 @@ impl DbDriver for ClickHouseDriver {
     }
 
     async fn execute_raw(&self, sql: &str) -> Result<QueryResponse, AppError> {
+        self.execute_params(sql, Vec::new()).await
+    }
+
+    async fn execute_params(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
         let start = Instant::now();
         let trimmed = sql.trim();
-        let upper = trimmed.to_uppercase();
+        let returns_rows = analyze(trimmed, "clickhouse").returns_rows;
 
-        let is_select = upper.starts_with("SELECT")
-            || upper.starts_with("WITH")
-            || upper.starts_with("SHOW")
-            || upper.starts_with("DESCRIBE")
-            || upper.starts_with("EXPLAIN");
-
-        if is_select {
-            let (columns, rows) = self.query_to_response(trimmed).await?;
+        if returns_rows {
+            let (columns, rows) = self.query_to_response_params(trimmed, &params).await?;
             let elapsed = start.elapsed().as_millis() as u64;
             let row_count = rows.len();
 
@@ -149,10 +225,11 @@ impl DbDriver for ClickHouseDriver {
                 affected_rows: None,
                 truncated: false,
                 max_rows_limit: None,
+                messages: Vec::new(),
             })
         } else {
-            self.client
-                .query(trimmed)
+            let (client, _tracked) = self.statement_client();
+            bind_params(client.query(trimmed), &params)?
                 .execute()
                 .await
                 .map_err(|e| AppError::Database(format!("ClickHouse execute error: {}", e)))?;
@@ -167,10 +244,33 @@ impl DbDriver for ClickHouseDriver {
                 affected_rows: Some(0),
                 truncated: false,
                 max_rows_limit: None,
+                messages: Vec::new(),
             })
         }
     }
 
+    /// ClickHouse reports no per-step runtime statistics, so `analyze` is ignored.
+    async fn explain(&self, sql: &str, _analyze: bool) -> Result<QueryPlan, AppError> {
+        let response = self
+            .execute_raw(&format!("EXPLAIN json = 1, description = 1 {}", explain_target(sql)))
+            .await?;
+        parse_clickhouse(&plan_document(&response)?)
+    }
+
+    async fn cancel(&self, query_id: &str) -> Result<bool, AppError> {
+        let ch_query_id = match self.running.get(query_id) {
+            Some(id) => id,
+            None => return Ok(false),
+        };
+        self.client
+            .query("KILL QUERY WHERE query_id = ?")
+            .bind(ch_query_id.as_str())
+            .execute()
+            .await
+            .map_err(|e| AppError::Database(format!("ClickHouse cancel error: {}", e)))?;
+        Ok(true)
+    }
+
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
         let schemas = self.get_schemas().await?;
         Ok(schemas.iter().map(ContainerInfo::from).collect())
@@ -226,6 +226,7 @@ impl DbDriver for DynamoDbDriver {
             affected_rows: None,
             truncated: false,
             max_rows_limit: None,
+            messages: Vec::new(),
         })
     }
 
@@ -398,6 +399,7 @@ impl DbDriver for DynamoDbDriver {
             affected_rows: None,
             truncated: false,
             max_rows_limit: None,
+            messages: Vec::new(),
         })
     }
 
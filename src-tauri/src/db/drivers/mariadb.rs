@@ -1,10 +1,12 @@
 use async_trait::async_trait;
 
 use crate::db::drivers::mysql::MySqlDriver;
+use crate::db::stream::{BatchSink, StatementSink};
 use crate::db::traits::{DbDriver, SqlDriver};
 use crate::error::AppError;
 use crate::models::connection::{ConnectionConfig, DatabaseCategory};
-use crate::models::query::QueryResponse;
+use crate::models::plan::QueryPlan;
+use crate::models::query::{CellValue, QueryResponse, StreamSummary};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo,
     RoutineInfo, SchemaInfo, TableInfo, TableStats,
@@ -36,6 +38,52 @@ impl DbDriver for MariaDbDriver {
         self.inner.execute_raw(sql).await
     }
 
+    async fn execute_params(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        self.inner.execute_params(sql, params).await
+    }
+
+    async fn execute_with_messages(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        self.inner.execute_with_messages(sql, params).await
+    }
+
+    async fn execute_result_sets(&self, sql: &str) -> Result<Vec<QueryResponse>, AppError> {
+        self.inner.execute_result_sets(sql).await
+    }
+
+    async fn explain(&self, sql: &str, analyze: bool) -> Result<QueryPlan, AppError> {
+        self.inner.explain(sql, analyze).await
+    }
+
+    async fn execute_stream(
+        &self,
+        sql: &str,
+        batch_size: usize,
+        max_rows: usize,
+        sink: BatchSink<'_>,
+    ) -> Result<StreamSummary, AppError> {
+        self.inner.execute_stream(sql, batch_size, max_rows, sink).await
+    }
+
+    async fn execute_script(
+        &self,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) -> Result<(), AppError> {
+        self.inner.execute_script(statements, sink).await
+    }
+
+    async fn cancel(&self, query_id: &str) -> Result<bool, AppError> {
+        self.inner.cancel(query_id).await
+    }
+
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
         self.inner.get_containers().await
     }
@@ -162,6 +162,7 @@ impl DbDriver for MongoDbDriver {
                     affected_rows: None,
                     truncated: false,
                     max_rows_limit: None,
+                    messages: Vec::new(),
                 })
             }
             _ => Err(AppError::UnsupportedOperation(format!(
@@ -171,6 +172,41 @@ impl DbDriver for MongoDbDriver {
         }
     }
 
+    /// Each parameter is a JSON object merged into the command's `filter`, so
+    /// user values never have to be spliced into the command text.
+    async fn execute_params(
+        &self,
+        query: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        let mut cmd: serde_json::Value = serde_json::from_str(query)
+            .map_err(|e| AppError::InvalidConfig(format!("Invalid JSON query: {}", e)))?;
+
+        if !cmd["filter"].is_object() {
+            cmd["filter"] = serde_json::json!({});
+        }
+        for param in params {
+            let value: serde_json::Value = match param {
+                CellValue::Json(s) | CellValue::Text(s) => serde_json::from_str(&s)
+                    .map_err(|e| AppError::InvalidConfig(format!("Invalid filter parameter: {}", e)))?,
+                _ => serde_json::Value::Null,
+            };
+            let fields = match value {
+                serde_json::Value::Object(fields) => fields,
+                _ => {
+                    return Err(AppError::InvalidConfig(
+                        "MongoDB parameters must be JSON objects".to_string(),
+                    ))
+                }
+            };
+            if let Some(filter) = cmd["filter"].as_object_mut() {
+                filter.extend(fields);
+            }
+        }
+
+        self.execute_raw(&cmd.to_string()).await
+    }
+
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
         let db_names = self
             .client
@@ -351,6 +387,7 @@ impl DbDriver for MongoDbDriver {
             affected_rows: None,
             truncated: false,
             max_rows_limit: None,
+            messages: Vec::new(),
         })
     }
 
@@ -1,12 +1,27 @@
+use std::ops::{Deref, DerefMut};
+use std::panic::AssertUnwindSafe;
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use bb8::Pool;
+use bb8::{CustomizeConnection, ManageConnection, Pool, PooledConnection};
+use bb8_tiberius::rt::Client;
 use bb8_tiberius::ConnectionManager;
-use tiberius::{AuthMethod, Config, EncryptionLevel};
+use futures::{FutureExt, TryStreamExt};
+use tiberius::numeric::Numeric;
+use tiberius::{AuthMethod, Config, EncryptionLevel, QueryItem, Row, ToSql};
+
+use crate::db::cancel::{ServerHandles, TrackedQuery};
+use crate::db::keyset::quote_ident;
+use crate::db::messages::capture;
+use crate::db::plan::{parse_mssql, plan_document};
+use crate::db::statement::analyze;
+use crate::db::stream::StatementSink;
 use crate::db::traits::{DbDriver, SqlDriver};
+use crate::db::mssql_types::row_to_cells;
+use crate::db::types::{cell_text, naive_datetime, unbindable_param, zoned_datetime};
 use crate::error::AppError;
 use crate::models::connection::{ConnectionConfig, DatabaseCategory};
+use crate::models::plan::QueryPlan;
 use crate::models::query::{CellValue, ColumnDef, QueryResponse};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo,
@@ -14,7 +29,109 @@ use crate::models::schema::{
 };
 
 pub struct MssqlDriver {
-    pool: Pool<ConnectionManager>,
+    pool: Pool<SessionManager>,
+    /// Session ids (@@SPID) of statements started under a query id, for server-side cancel.
+    running: ServerHandles<i16>,
+}
+
+/// Session settings applied to each pooled connection when it is opened: the
+/// lock timeout, then the connection's init script. SQL Server has no
+/// server-side statement timeout, and the default schema belongs to the login's
+/// database user, so neither can be set per session.
+#[derive(Debug)]
+struct SessionSetup {
+    statements: Vec<String>,
+}
+
+impl SessionSetup {
+    fn new(config: &ConnectionConfig) -> Option<Self> {
+        let mut statements = Vec::new();
+        if let Some(ms) = config.lock_timeout_ms {
+            statements.push(format!("SET LOCK_TIMEOUT {}", ms));
+        }
+        statements.extend(config.init_sql.iter().cloned());
+        if statements.is_empty() {
+            return None;
+        }
+        Some(Self { statements })
+    }
+}
+
+#[async_trait]
+impl CustomizeConnection<SessionClient, bb8_tiberius::Error> for SessionSetup {
+    async fn on_acquire(&self, conn: &mut SessionClient) -> Result<(), bb8_tiberius::Error> {
+        for statement in &self.statements {
+            conn.simple_query(statement.as_str()).await?.into_results().await?;
+        }
+        Ok(())
+    }
+}
+
+/// A pooled client and its session id, read once when the connection opens.
+struct SessionClient {
+    client: Client,
+    spid: Option<i16>,
+    /// Close the connection instead of returning it to the pool.
+    discard: bool,
+}
+
+impl Deref for SessionClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        &self.client
+    }
+}
+
+impl DerefMut for SessionClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        &mut self.client
+    }
+}
+
+/// `ConnectionManager` that records each connection's @@SPID, so tracking a
+/// statement for `cancel` does not cost a round trip.
+struct SessionManager(ConnectionManager);
+
+#[async_trait]
+impl ManageConnection for SessionManager {
+    type Connection = SessionClient;
+    type Error = bb8_tiberius::Error;
+
+    async fn connect(&self) -> Result<SessionClient, bb8_tiberius::Error> {
+        let mut client = self.0.connect().await?;
+        let spid = client
+            .simple_query("SELECT @@SPID")
+            .await?
+            .into_row()
+            .await?
+            .and_then(|row| row.get::<i16, _>(0));
+        Ok(SessionClient {
+            client,
+            spid,
+            discard: false,
+        })
+    }
+
+    async fn is_valid(&self, conn: &mut SessionClient) -> Result<(), bb8_tiberius::Error> {
+        self.0.is_valid(&mut conn.client).await
+    }
+
+    fn has_broken(&self, conn: &mut SessionClient) -> bool {
+        conn.discard || self.0.has_broken(&mut conn.client)
+    }
+}
+
+/// Error for a panic caught while reading a response. tiberius 0.12 panics
+/// (`todo!()`) on sql_variant and CLR column metadata and leaves the response
+/// half read, so the connection is closed instead of returned to the pool.
+fn decode_panic(conn: &mut PooledConnection<'_, SessionManager>) -> AppError {
+    conn.discard = true;
+    AppError::Database(
+        "MSSQL result has a column the driver cannot read (sql_variant or a CLR type \
+         such as hierarchyid or geography); CAST it or call ToString() in the query"
+            .to_string(),
+    )
 }
 
 impl MssqlDriver {
@@ -32,6 +149,9 @@ impl MssqlDriver {
         ));
         tib_config.encryption(EncryptionLevel::Required);
         tib_config.trust_cert();
+        // ApplicationIntent=ReadOnly; routes to a readable secondary in an
+        // availability group.
+        tib_config.readonly(config.read_only);
 
         let host = config.host_or_default();
         let port = config.port_or_default();
@@ -81,119 +201,84 @@ impl MssqlDriver {
         let mgr = ConnectionManager::build(tib_config)
             .map_err(|e| AppError::Database(format!("Failed to create MSSQL pool manager: {}", e)))?;
 
-        let pool = Pool::builder()
+        let mut builder = Pool::builder()
             .max_size(config.pool_max_connections)
             .idle_timeout(Some(Duration::from_secs(config.pool_idle_timeout_secs)))
-            .connection_timeout(Duration::from_secs(config.pool_acquire_timeout_secs.max(30)))
-            .build(mgr)
+            .connection_timeout(Duration::from_secs(config.pool_acquire_timeout_secs.max(30)));
+        if let Some(setup) = SessionSetup::new(config) {
+            builder = builder.connection_customizer(Box::new(setup));
+        }
+        let pool = builder
+            .build(SessionManager(mgr))
             .await
             .map_err(|e| AppError::Database(format!("Failed to build MSSQL pool: {}", e)))?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            running: ServerHandles::new(),
+        })
+    }
+
+    /// Record the session id of `conn` under the current query id, if one is set.
+    fn track_session(&self, conn: &SessionClient) -> Option<TrackedQuery<'_, i16>> {
+        conn.spid.and_then(|spid| self.running.track(spid))
     }
 
     async fn query_rows(&self, sql: &str) -> Result<(Vec<ColumnDef>, Vec<Vec<CellValue>>), AppError> {
+        self.query_rows_params(sql, &[]).await
+    }
+
+    /// Run a row-returning statement, binding `params` as @P1, @P2, ... when given.
+    async fn query_rows_params(
+        &self,
+        sql: &str,
+        params: &[CellValue],
+    ) -> Result<(Vec<ColumnDef>, Vec<Vec<CellValue>>), AppError> {
         let mut conn = self.pool.get().await
             .map_err(|e| AppError::Database(format!("Failed to get MSSQL connection: {}", e)))?;
+        let _tracked = self.track_session(&conn);
+        Self::query_rows_on(&mut conn, sql, params).await
+    }
 
-        let stream = conn.simple_query(sql).await
-            .map_err(|e| AppError::Database(format!("MSSQL query error: {}", e)))?;
+    async fn query_rows_on(
+        conn: &mut PooledConnection<'_, SessionManager>,
+        sql: &str,
+        params: &[CellValue],
+    ) -> Result<(Vec<ColumnDef>, Vec<Vec<CellValue>>), AppError> {
+        let stream = if params.is_empty() {
+            conn.simple_query(sql).await
+        } else {
+            let bound = mssql_params(params)?;
+            let refs: Vec<&dyn ToSql> = bound.iter().map(|p| p.as_ref()).collect();
+            conn.query(sql, &refs).await
+        }
+        .map_err(|e| AppError::Database(format!("MSSQL query error: {}", e)))?;
 
         let results = stream.into_results().await
             .map_err(|e| AppError::Database(format!("MSSQL result error: {}", e)))?;
 
-        if results.is_empty() {
-            return Ok((Vec::new(), Vec::new()));
+        match results.first() {
+            Some(result_set) => Ok(result_set_to_rows(result_set)),
+            None => Ok((Vec::new(), Vec::new())),
         }
-
-        let result_set = &results[0];
-        if result_set.is_empty() {
-            return Ok((Vec::new(), Vec::new()));
-        }
-
-        // Extract columns from first row
-        let columns: Vec<ColumnDef> = result_set[0]
-            .columns()
-            .iter()
-            .map(|col| ColumnDef {
-                name: col.name().to_string(),
-                data_type: format!("{:?}", col.column_type()),
-            })
-            .collect();
-
-        let rows: Vec<Vec<CellValue>> = result_set
-            .iter()
-            .map(|row| {
-                (0..columns.len())
-                    .map(|i| {
-                        // Try bool (BIT)
-                        if let Ok(Some(v)) = row.try_get::<bool, _>(i) {
-                            return CellValue::Bool(v);
-                        }
-                        // Try i16 (SMALLINT)
-                        if let Ok(Some(v)) = row.try_get::<i16, _>(i) {
-                            return CellValue::Int(v as i64);
-                        }
-                        // Try i32 (INT)
-                        if let Ok(Some(v)) = row.try_get::<i32, _>(i) {
-                            return CellValue::Int(v as i64);
-                        }
-                        // Try i64 (BIGINT)
-                        if let Ok(Some(v)) = row.try_get::<i64, _>(i) {
-                            return CellValue::Int(v);
-                        }
-                        // Try f32 (REAL)
-                        if let Ok(Some(v)) = row.try_get::<f32, _>(i) {
-                            return CellValue::Float(v as f64);
-                        }
-                        // Try f64 (FLOAT)
-                        if let Ok(Some(v)) = row.try_get::<f64, _>(i) {
-                            return CellValue::Float(v);
-                        }
-                        // Try &str (VARCHAR, NVARCHAR, etc.)
-                        if let Ok(Some(v)) = row.try_get::<&str, _>(i) {
-                            return CellValue::Text(v.to_string());
-                        }
-                        // Try Numeric (DECIMAL, NUMERIC)
-                        if let Ok(Some(v)) = row.try_get::<tiberius::numeric::Numeric, _>(i) {
-                            return CellValue::Text(v.to_string());
-                        }
-                        // Try binary (VARBINARY, IMAGE, etc.)
-                        if let Ok(Some(v)) = row.try_get::<&[u8], _>(i) {
-                            return CellValue::Binary(v.to_vec());
-                        }
-                        CellValue::Null
-                    })
-                    .collect()
-            })
-            .collect();
-
-        Ok((columns, rows))
-    }
-}
-
-#[async_trait]
-impl DbDriver for MssqlDriver {
-    fn category(&self) -> DatabaseCategory {
-        DatabaseCategory::Relational
     }
 
-    fn dialect_hint(&self) -> &'static str {
-        "mssql"
-    }
-
-    async fn execute_raw(&self, sql: &str) -> Result<QueryResponse, AppError> {
+    /// Execute a statement or batch on `conn`. PRINT output and other info
+    /// messages are collected into the response.
+    async fn execute_on(
+        conn: &mut PooledConnection<'_, SessionManager>,
+        sql: &str,
+        params: &[CellValue],
+    ) -> Result<QueryResponse, AppError> {
         let start = Instant::now();
         let trimmed = sql.trim();
-        let upper = trimmed.to_uppercase();
+        let returns_rows = analyze(trimmed, "mssql").returns_rows;
 
-        let is_select = upper.starts_with("SELECT")
-            || upper.starts_with("WITH")
-            || upper.starts_with("EXEC")
-            || upper.starts_with("SP_");
-
-        if is_select {
-            let (columns, rows) = self.query_rows(trimmed).await?;
+        if returns_rows {
+            let (result, messages) =
+                capture(AssertUnwindSafe(Self::query_rows_on(conn, trimmed, params)).catch_unwind())
+                    .await;
+            let (columns, rows) = result.unwrap_or_else(|_| Err(decode_panic(conn)))?;
             let elapsed = start.elapsed().as_millis() as u64;
             let row_count = rows.len();
 
@@ -205,12 +290,18 @@ impl DbDriver for MssqlDriver {
                 affected_rows: None,
                 truncated: false,
                 max_rows_limit: None,
+                messages,
             })
         } else {
-            let mut conn = self.pool.get().await
-                .map_err(|e| AppError::Database(format!("Failed to get MSSQL connection: {}", e)))?;
-            let result = conn.execute(trimmed, &[]).await
-                .map_err(|e| AppError::Database(format!("MSSQL execute error: {}", e)))?;
+            let bound = mssql_params(params)?;
+            let refs: Vec<&dyn ToSql> = bound.iter().map(|p| p.as_ref()).collect();
+            let (result, messages) =
+                capture(AssertUnwindSafe(conn.execute(trimmed, &refs)).catch_unwind()).await;
+            let result = match result {
+                Ok(result) => result
+                    .map_err(|e| AppError::Database(format!("MSSQL execute error: {}", e)))?,
+                Err(_) => return Err(decode_panic(conn)),
+            };
             let elapsed = start.elapsed().as_millis() as u64;
 
             Ok(QueryResponse {
@@ -221,10 +312,285 @@ impl DbDriver for MssqlDriver {
                 affected_rows: Some(result.rows_affected().iter().sum::<u64>()),
                 truncated: false,
                 max_rows_limit: None,
+                messages,
             })
         }
     }
 
+    /// Run a batch or `EXEC` and return every result set it produces, in order.
+    /// tiberius does not report DONE row counts on a result stream, so affected
+    /// rows are only known for batches that return no result sets at all.
+    async fn result_sets_on(
+        conn: &mut PooledConnection<'_, SessionManager>,
+        sql: &str,
+    ) -> Result<Vec<QueryResponse>, AppError> {
+        let trimmed = sql.trim();
+        if !analyze(trimmed, "mssql").returns_rows {
+            return Ok(vec![Self::execute_on(conn, trimmed, &[]).await?]);
+        }
+
+        let (result, messages) =
+            capture(AssertUnwindSafe(Self::read_result_sets(conn, trimmed)).catch_unwind()).await;
+        let mut responses = result.unwrap_or_else(|_| Err(decode_panic(conn)))?;
+        if let Some(last) = responses.last_mut() {
+            last.messages = messages;
+        }
+        Ok(responses)
+    }
+
+    /// Collect the showplan XML documents for `sql`, one per statement.
+    /// `SHOWPLAN_XML` only compiles the batch; `STATISTICS XML` runs it inside
+    /// a transaction that is rolled back and adds the actual counters. The SET
+    /// options must be sent as batches of their own.
+    async fn showplan_on(
+        conn: &mut PooledConnection<'_, SessionManager>,
+        sql: &str,
+        analyze: bool,
+    ) -> Result<Vec<String>, AppError> {
+        let option = if analyze { "STATISTICS XML" } else { "SHOWPLAN_XML" };
+        Self::run_batch(conn, &format!("SET {} ON", option)).await?;
+        let result = if analyze {
+            let batch = format!("BEGIN TRANSACTION;\n{}\nIF @@TRANCOUNT > 0 ROLLBACK", sql);
+            let result = Self::read_result_sets(conn, &batch).await;
+            // An error can abort the batch before its ROLLBACK runs.
+            Self::run_batch(conn, "IF @@TRANCOUNT > 0 ROLLBACK").await.ok();
+            result
+        } else {
+            Self::read_result_sets(conn, sql).await
+        };
+        Self::run_batch(conn, &format!("SET {} OFF", option)).await.ok();
+
+        Ok(result?
+            .iter()
+            .filter(|set| set.columns.first().is_some_and(|c| c.name.contains("Showplan")))
+            .filter_map(|set| plan_document(set).ok())
+            .collect())
+    }
+
+    /// Send `sql` as its own batch and drain the results.
+    async fn run_batch(
+        conn: &mut PooledConnection<'_, SessionManager>,
+        sql: &str,
+    ) -> Result<(), AppError> {
+        conn.simple_query(sql).await?.into_results().await?;
+        Ok(())
+    }
+
+    async fn read_result_sets(
+        conn: &mut PooledConnection<'_, SessionManager>,
+        sql: &str,
+    ) -> Result<Vec<QueryResponse>, AppError> {
+        let mut stream = conn.simple_query(sql).await
+            .map_err(|e| AppError::Database(format!("MSSQL query error: {}", e)))?;
+
+        let mut set_start = Instant::now();
+        let mut sets: Vec<(Vec<ColumnDef>, Vec<Row>, u64)> = Vec::new();
+        while let Some(item) = stream.try_next().await
+            .map_err(|e| AppError::Database(format!("MSSQL result error: {}", e)))?
+        {
+            match item {
+                QueryItem::Metadata(meta) => {
+                    if let Some(previous) = sets.last_mut() {
+                        previous.2 = set_start.elapsed().as_millis() as u64;
+                        set_start = Instant::now();
+                    }
+                    let columns = meta
+                        .columns()
+                        .iter()
+                        .map(|col| ColumnDef {
+                            name: col.name().to_string(),
+                            data_type: format!("{:?}", col.column_type()),
+                        })
+                        .collect();
+                    sets.push((columns, Vec::new(), 0));
+                }
+                QueryItem::Row(row) => {
+                    if let Some((_, rows, _)) = sets.last_mut() {
+                        rows.push(row);
+                    }
+                }
+            }
+        }
+        if let Some(last) = sets.last_mut() {
+            last.2 = set_start.elapsed().as_millis() as u64;
+        }
+
+        Ok(sets
+            .into_iter()
+            .map(|(columns, rows, elapsed)| {
+                let (_, rows) = result_set_to_rows(&rows);
+                QueryResponse {
+                    columns,
+                    row_count: rows.len(),
+                    rows,
+                    execution_time_ms: elapsed,
+                    affected_rows: None,
+                    truncated: false,
+                    max_rows_limit: None,
+                    messages: Vec::new(),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Convert bind values into tiberius parameters.
+fn mssql_params(params: &[CellValue]) -> Result<Vec<Box<dyn ToSql>>, AppError> {
+    params
+        .iter()
+        .map(|cell| -> Result<Box<dyn ToSql>, AppError> {
+            Ok(match cell {
+                CellValue::Null => Box::new(Option::<String>::None),
+                CellValue::Bool(v) => Box::new(*v),
+                CellValue::Int(v) => Box::new(*v),
+                CellValue::Float(v) => Box::new(*v),
+                CellValue::Text(v) | CellValue::Json(v) => Box::new(v.clone()),
+                CellValue::Timestamp(v) => {
+                    match chrono::NaiveDateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f") {
+                        Ok(ts) => Box::new(ts),
+                        Err(_) => Box::new(v.clone()),
+                    }
+                }
+                CellValue::Binary(v) => Box::new(v.clone()),
+                CellValue::Decimal { digits, scale } => {
+                    match (digits.parse::<i128>(), u8::try_from(*scale)) {
+                        (Ok(value), Ok(scale)) if scale <= 37 => {
+                            Box::new(Numeric::new_with_scale(value, scale))
+                        }
+                        _ => Box::new(cell_text(cell)),
+                    }
+                }
+                CellValue::Uuid(v) => match uuid::Uuid::parse_str(v) {
+                    Ok(u) => Box::new(u),
+                    Err(_) => Box::new(v.clone()),
+                },
+                CellValue::Date(v) => match chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d") {
+                    Ok(date) => Box::new(date),
+                    Err(_) => Box::new(v.clone()),
+                },
+                CellValue::Time(v) => match chrono::NaiveTime::parse_from_str(v, "%H:%M:%S%.f") {
+                    Ok(time) => Box::new(time),
+                    Err(_) => Box::new(v.clone()),
+                },
+                CellValue::DateTime { tz: None, micros } => match naive_datetime(*micros) {
+                    Some(ts) => Box::new(ts),
+                    None => Box::new(cell_text(cell)),
+                },
+                CellValue::DateTime { tz: Some(tz), micros } => match zoned_datetime(*micros, tz) {
+                    Some(ts) => Box::new(ts),
+                    None => Box::new(cell_text(cell)),
+                },
+                CellValue::Interval { .. } | CellValue::Array(_) | CellValue::Enum(_) => {
+                    Box::new(cell_text(cell))
+                }
+                CellValue::LargeText { .. }
+                | CellValue::LargeJson { .. }
+                | CellValue::LargeBinary { .. } => return Err(unbindable_param()),
+            })
+        })
+        .collect()
+}
+
+/// Convert one tiberius result set into column definitions and cell rows.
+fn result_set_to_rows(result_set: &[Row]) -> (Vec<ColumnDef>, Vec<Vec<CellValue>>) {
+    if result_set.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+
+    // Extract columns from first row
+    let columns: Vec<ColumnDef> = result_set[0]
+        .columns()
+        .iter()
+        .map(|col| ColumnDef {
+            name: col.name().to_string(),
+            data_type: format!("{:?}", col.column_type()),
+        })
+        .collect();
+
+    let rows: Vec<Vec<CellValue>> = result_set.iter().map(row_to_cells).collect();
+
+    (columns, rows)
+}
+
+#[async_trait]
+impl DbDriver for MssqlDriver {
+    fn category(&self) -> DatabaseCategory {
+        DatabaseCategory::Relational
+    }
+
+    fn dialect_hint(&self) -> &'static str {
+        "mssql"
+    }
+
+    async fn execute_raw(&self, sql: &str) -> Result<QueryResponse, AppError> {
+        self.execute_params(sql, Vec::new()).await
+    }
+
+    async fn execute_params(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        let mut conn = self.pool.get().await
+            .map_err(|e| AppError::Database(format!("Failed to get MSSQL connection: {}", e)))?;
+        let _tracked = self.track_session(&conn);
+        Self::execute_on(&mut conn, sql, &params).await
+    }
+
+    async fn execute_result_sets(&self, sql: &str) -> Result<Vec<QueryResponse>, AppError> {
+        let mut conn = self.pool.get().await
+            .map_err(|e| AppError::Database(format!("Failed to get MSSQL connection: {}", e)))?;
+        let _tracked = self.track_session(&conn);
+        Self::result_sets_on(&mut conn, sql).await
+    }
+
+    async fn explain(&self, sql: &str, analyze: bool) -> Result<QueryPlan, AppError> {
+        let mut conn = self.pool.get().await
+            .map_err(|e| AppError::Database(format!("Failed to get MSSQL connection: {}", e)))?;
+        let _tracked = self.track_session(&conn);
+        let documents = Self::showplan_on(&mut conn, sql.trim(), analyze).await?;
+        parse_mssql(&documents, analyze)
+    }
+
+    /// Each entry is a `GO` batch; all batches run on one pooled connection,
+    /// which is closed afterwards so `USE`, `SET` options, temp tables and any
+    /// transaction the script left open do not carry over into later queries.
+    async fn execute_script(
+        &self,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.get().await
+            .map_err(|e| AppError::Database(format!("Failed to get MSSQL connection: {}", e)))?;
+        let _tracked = self.track_session(&conn);
+        for (index, statement) in statements.iter().enumerate() {
+            let result = Self::execute_on(&mut conn, statement, &[]).await;
+            // After `decode_panic` the connection is mid-response; stop there.
+            if !sink(index, result) || conn.discard {
+                break;
+            }
+        }
+        conn.discard = true;
+        Ok(())
+    }
+
+    /// tiberius cannot send a TDS attention from outside the task that owns the
+    /// client, so the statement's session is killed instead; bb8 discards the
+    /// dead connection on its next health check. KILL ends the whole pooled
+    /// session: an open transaction is rolled back and temp tables and SET
+    /// options are lost, not just the running statement.
+    async fn cancel(&self, query_id: &str) -> Result<bool, AppError> {
+        let spid = match self.running.get(query_id) {
+            Some(spid) => spid,
+            None => return Ok(false),
+        };
+        let mut conn = self.pool.get().await
+            .map_err(|e| AppError::Database(format!("Failed to get MSSQL connection: {}", e)))?;
+        conn.execute(format!("KILL {}", spid), &[]).await
+            .map_err(|e| AppError::Database(format!("MSSQL cancel error: {}", e)))?;
+        Ok(true)
+    }
+
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
         let schemas = self.get_schemas().await?;
         Ok(schemas.iter().map(ContainerInfo::from).collect())
@@ -484,10 +850,54 @@ impl SqlDriver for MssqlDriver {
         Ok(foreign_keys)
     }
 
+    /// `*`, unless the table has columns tiberius cannot read. CLR types
+    /// (hierarchyid, geography, geometry) are converted with `ToString()` and
+    /// sql_variant is cast to text, both on the server.
+    ///
+    /// Only table browsing reads through this select list. Statements run with
+    /// `execute_query` are sent as written, so selecting such a column there
+    /// fails with an error (see `decode_panic`) unless the query converts it.
+    async fn browse_columns(&self, schema: &str, table: &str) -> Result<String, AppError> {
+        let object = format!("{}.{}", quote_ident("mssql", schema), quote_ident("mssql", table));
+        let sql = format!(
+            "SELECT c.name, t.name, t.is_assembly_type \
+             FROM sys.columns c \
+             JOIN sys.types t ON c.user_type_id = t.user_type_id \
+             WHERE c.object_id = OBJECT_ID(N'{}') \
+             ORDER BY c.column_id",
+            object.replace('\'', "''")
+        );
+        let (_, rows) = self.query_rows(&sql).await?;
+
+        let mut converted = false;
+        let columns: Vec<String> = rows
+            .iter()
+            .filter_map(|row| {
+                let CellValue::Text(name) = row.first()? else {
+                    return None;
+                };
+                let column = quote_ident("mssql", name);
+                Some(match (row.get(1), row.get(2)) {
+                    (_, Some(CellValue::Bool(true))) => {
+                        converted = true;
+                        format!("{}.ToString() AS {}", column, column)
+                    }
+                    (Some(CellValue::Text(t)), _) if t == "sql_variant" => {
+                        converted = true;
+                        format!("CAST({} AS nvarchar(4000)) AS {}", column, column)
+                    }
+                    _ => column,
+                })
+            })
+            .collect();
+        Ok(if converted { columns.join(", ") } else { "*".to_string() })
+    }
+
     async fn get_table_data(&self, schema: &str, table: &str, limit: i64, offset: i64) -> Result<QueryResponse, AppError> {
+        let columns = self.browse_columns(schema, table).await?;
         let sql = format!(
-            "SELECT * FROM [{}].[{}] ORDER BY (SELECT NULL) OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
-            schema, table, offset, limit
+            "SELECT {} FROM [{}].[{}] ORDER BY (SELECT NULL) OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+            columns, schema, table, offset, limit
         );
         self.execute_raw(&sql).await
     }
@@ -2,16 +2,24 @@ use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use sqlx::mysql::{MySql, MySqlPool, MySqlPoolOptions};
+use futures::TryStreamExt;
+use log::warn;
+use sqlx::mysql::{MySql, MySqlConnection, MySqlPool, MySqlPoolOptions, MySqlRow};
 use sqlx::pool::PoolConnection;
-use sqlx::{Executor, Row};
+use sqlx::{Connection, Either, Executor, Row};
 use tokio::sync::Mutex;
 
+use crate::db::cancel::{current_query_id, report_session, take_session, ServerHandles, TrackedQuery};
+use crate::db::keyset::quote_ident;
+use crate::db::plan::{explain_target, parse_mysql, parse_text_plan, plan_document};
+use crate::db::statement::{analyze, tokenize, TokenKind};
+use crate::db::stream::{BatchCollector, BatchSink, StatementSink};
 use crate::db::traits::{DbDriver, SqlDriver};
-use crate::db::types::{mysql_columns_to_defs, mysql_row_to_cells};
+use crate::db::types::{mysql_bind_cell, mysql_columns_to_defs, mysql_row_to_cells};
 use crate::error::AppError;
-use crate::models::connection::{ConnectionConfig, DatabaseCategory};
-use crate::models::query::QueryResponse;
+use crate::models::connection::{ConnectionConfig, DatabaseCategory, DatabaseType};
+use crate::models::plan::QueryPlan;
+use crate::models::query::{CellValue, QueryResponse, ServerMessage, StreamSummary};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo,
     RoutineInfo, SchemaInfo, TableInfo, TableStats,
@@ -19,16 +27,99 @@ use crate::models::schema::{
 
 pub struct MySqlDriver {
     pool: MySqlPool,
-    txn_conn: Mutex<Option<PoolConnection<MySql>>>,
+    /// Connection holding the open transaction, and its connection id.
+    txn_conn: Mutex<Option<(PoolConnection<MySql>, u64)>>,
+    /// Connection ids of statements started under a query id, for server-side cancel.
+    running: ServerHandles<u64>,
+}
+
+/// Statements run on every new pooled connection: read-only mode, the
+/// configured server-side timeouts, the default schema, then the connection's
+/// init script.
+fn session_statements(config: &ConnectionConfig) -> Vec<String> {
+    let mut statements = Vec::new();
+    if config.read_only {
+        statements.push("SET SESSION TRANSACTION READ ONLY".to_string());
+    }
+    if let Some(ms) = config.statement_timeout_ms {
+        if config.db_type == DatabaseType::MariaDB {
+            // Seconds, applies to every statement.
+            statements.push(format!("SET SESSION max_statement_time = {}", ms as f64 / 1000.0));
+        } else {
+            // Milliseconds, applies to SELECT only.
+            statements.push(format!("SET SESSION max_execution_time = {}", ms));
+        }
+    }
+    if let Some(ms) = config.lock_timeout_ms {
+        let secs = ms.div_ceil(1000).max(1);
+        statements.push(format!(
+            "SET SESSION innodb_lock_wait_timeout = {}, lock_wait_timeout = {}",
+            secs, secs
+        ));
+    }
+    if let Some(schema) = config.default_schema.as_deref().filter(|s| !s.is_empty()) {
+        statements.push(format!("USE {}", quote_ident("mysql", schema)));
+    }
+    statements.extend(config.init_sql.iter().cloned());
+    statements
+}
+
+/// Report the connection id of `conn` to the query acquiring it. Runs in the
+/// pool's hooks, in place of sqlx's liveness ping for idle connections, so
+/// tracking a statement for `cancel` costs no extra round trip.
+async fn report_connection(conn: &mut MySqlConnection) -> Result<(), sqlx::Error> {
+    let connection_id: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()")
+        .fetch_one(conn)
+        .await?;
+    report_session(connection_id as i64);
+    Ok(())
+}
+
+/// Whether `VERSION()` names MySQL 8.0.18 or later, the first release with
+/// `EXPLAIN ANALYZE`. MariaDB reports its own version numbers.
+fn explain_analyze_supported(version: &str) -> bool {
+    if version.contains("MariaDB") {
+        return false;
+    }
+    let parts: Vec<u32> = version
+        .split(|c: char| !c.is_ascii_digit())
+        .take(3)
+        .filter_map(|part| part.parse().ok())
+        .collect();
+    parts.as_slice() >= [8, 0, 18].as_slice()
 }
 
 impl MySqlDriver {
     pub async fn connect(config: &ConnectionConfig) -> Result<Self, AppError> {
         let url = config.to_connection_url();
+        let session = session_statements(config);
         let pool = MySqlPoolOptions::new()
             .max_connections(config.pool_max_connections)
             .idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs))
             .acquire_timeout(Duration::from_secs(config.pool_acquire_timeout_secs))
+            .after_connect(move |conn, _meta| {
+                let session = session.clone();
+                Box::pin(async move {
+                    for statement in &session {
+                        conn.execute(statement.as_str()).await?;
+                    }
+                    if current_query_id().is_some() {
+                        report_connection(conn).await?;
+                    }
+                    Ok(())
+                })
+            })
+            .test_before_acquire(false)
+            .before_acquire(|conn, _meta| {
+                Box::pin(async move {
+                    if current_query_id().is_some() {
+                        report_connection(conn).await?;
+                    } else {
+                        conn.ping().await?;
+                    }
+                    Ok(true)
+                })
+            })
             .connect(&url)
             .await
             .map_err(|e| AppError::Database(format!("Failed to connect to MySQL: {}", e)))?;
@@ -36,27 +127,32 @@ impl MySqlDriver {
         Ok(Self {
             pool,
             txn_conn: Mutex::new(None),
+            running: ServerHandles::new(),
         })
     }
 
+    /// Record the connection id the pool reported for the connection the
+    /// current query just acquired, if the query has an id.
+    fn track_acquired(&self) -> Option<TrackedQuery<'_, u64>> {
+        take_session().and_then(|id| self.running.track(id as u64))
+    }
+
     async fn execute_on<'e, E: Executor<'e, Database = MySql>>(
         executor: E,
         sql: &str,
+        params: &[CellValue],
     ) -> Result<QueryResponse, AppError> {
         let start = Instant::now();
         let trimmed = sql.trim();
-        let upper = trimmed.to_uppercase();
-
-        let is_select = upper.starts_with("SELECT")
-            || upper.starts_with("WITH")
-            || upper.starts_with("SHOW")
-            || upper.starts_with("EXPLAIN")
-            || upper.starts_with("DESCRIBE")
-            || upper.starts_with("DESC")
-            || upper.starts_with("TABLE");
-
-        if is_select {
-            let rows = sqlx::query(trimmed).fetch_all(executor).await?;
+        let returns_rows = analyze(trimmed, "mysql").returns_rows;
+
+        let mut query = sqlx::query(trimmed);
+        for param in params {
+            query = mysql_bind_cell(query, param)?;
+        }
+
+        if returns_rows {
+            let rows = query.fetch_all(executor).await?;
             let elapsed = start.elapsed().as_millis() as u64;
 
             let columns = if rows.is_empty() {
@@ -76,9 +172,10 @@ impl MySqlDriver {
                 affected_rows: None,
                 truncated: false,
                 max_rows_limit: None,
+                messages: Vec::new(),
             })
         } else {
-            let result = sqlx::query(trimmed).execute(executor).await?;
+            let result = query.execute(executor).await?;
             let elapsed = start.elapsed().as_millis() as u64;
             let affected = result.rows_affected();
 
@@ -90,9 +187,175 @@ impl MySqlDriver {
                 affected_rows: Some(affected),
                 truncated: false,
                 max_rows_limit: None,
+                messages: Vec::new(),
             })
         }
     }
+
+    /// Execute on `conn` and attach the warnings the statement left in the
+    /// session's diagnostics area (`SHOW WARNINGS`).
+    /// Run `EXPLAIN ANALYZE` inside a transaction (or a savepoint of the open
+    /// one) that is always rolled back, so explaining a write changes nothing.
+    async fn explain_analyze_on(
+        conn: &mut MySqlConnection,
+        explain_sql: &str,
+        in_transaction: bool,
+    ) -> Result<QueryResponse, AppError> {
+        let (begin, rollback) = if in_transaction {
+            ("SAVEPOINT queryark_explain", "ROLLBACK TO SAVEPOINT queryark_explain")
+        } else {
+            ("BEGIN", "ROLLBACK")
+        };
+        sqlx::query(begin).execute(&mut *conn).await?;
+        let result = Self::execute_on(&mut *conn, explain_sql, &[]).await;
+        sqlx::query(rollback).execute(&mut *conn).await.ok();
+        if in_transaction {
+            sqlx::query("RELEASE SAVEPOINT queryark_explain")
+                .execute(&mut *conn)
+                .await
+                .ok();
+        }
+        result
+    }
+
+    async fn execute_with_warnings(
+        conn: &mut MySqlConnection,
+        sql: &str,
+        params: &[CellValue],
+    ) -> Result<QueryResponse, AppError> {
+        let mut response = Self::execute_on(&mut *conn, sql, params).await?;
+        if !is_diagnostics_statement(sql) {
+            response.messages = Self::fetch_warnings(conn).await;
+        }
+        Ok(response)
+    }
+
+    async fn fetch_warnings(conn: &mut MySqlConnection) -> Vec<ServerMessage> {
+        let rows = match sqlx::query("SHOW WARNINGS").fetch_all(conn).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                warn!("SHOW WARNINGS failed: {}", e);
+                return Vec::new();
+            }
+        };
+        rows.iter()
+            .map(|row| ServerMessage {
+                severity: match row.try_get::<String, _>("Level").as_deref() {
+                    Ok("Error") => "error",
+                    Ok("Note") => "notice",
+                    _ => "warning",
+                }
+                .to_string(),
+                code: row.try_get::<u32, _>("Code").ok().map(|c| c.to_string()),
+                text: row.try_get("Message").unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Run `sql` as unprepared text so `CALL` and multi-statement strings can
+    /// return several result sets, each followed by its OK packet. Sets that
+    /// produced no rows are reported by their affected-row count.
+    async fn result_sets_on(
+        conn: &mut MySqlConnection,
+        sql: &str,
+    ) -> Result<Vec<QueryResponse>, AppError> {
+        let mut responses = Vec::new();
+        let mut rows: Vec<MySqlRow> = Vec::new();
+        let mut set_start = Instant::now();
+        {
+            let mut stream = (&mut *conn).fetch_many(sql.trim());
+            while let Some(item) = stream.try_next().await? {
+                let result = match item {
+                    Either::Right(row) => {
+                        rows.push(row);
+                        continue;
+                    }
+                    Either::Left(result) => result,
+                };
+                let elapsed = set_start.elapsed().as_millis() as u64;
+                set_start = Instant::now();
+                responses.push(if rows.is_empty() {
+                    QueryResponse {
+                        columns: Vec::new(),
+                        rows: Vec::new(),
+                        row_count: 0,
+                        execution_time_ms: elapsed,
+                        affected_rows: Some(result.rows_affected()),
+                        truncated: false,
+                        max_rows_limit: None,
+                        messages: Vec::new(),
+                    }
+                } else {
+                    let set = std::mem::take(&mut rows);
+                    QueryResponse {
+                        columns: mysql_columns_to_defs(&set[0]),
+                        rows: set.iter().map(|r| mysql_row_to_cells(r)).collect(),
+                        row_count: set.len(),
+                        execution_time_ms: elapsed,
+                        affected_rows: None,
+                        truncated: false,
+                        max_rows_limit: None,
+                        messages: Vec::new(),
+                    }
+                });
+            }
+        }
+
+        let warnings = Self::fetch_warnings(conn).await;
+        if let Some(last) = responses.last_mut() {
+            last.messages = warnings;
+        }
+        Ok(responses)
+    }
+
+    /// Run script statements in order on one connection.
+    async fn run_script_on(
+        conn: &mut MySqlConnection,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) {
+        for (index, statement) in statements.iter().enumerate() {
+            let result = Self::execute_with_warnings(conn, statement, &[]).await;
+            if !sink(index, result) {
+                break;
+            }
+        }
+    }
+
+    /// Stream a query's rows in batches, stopping at `max_rows`.
+    async fn stream_on<'e, E: Executor<'e, Database = MySql>>(
+        executor: E,
+        sql: &str,
+        batch_size: usize,
+        max_rows: usize,
+        sink: BatchSink<'_>,
+    ) -> Result<StreamSummary, AppError> {
+        let trimmed = sql.trim();
+        let returns_rows = analyze(trimmed, "mysql").returns_rows;
+
+        if !returns_rows {
+            let response = Self::execute_on(executor, sql, &[]).await?;
+            return Ok(StreamSummary {
+                row_count: 0,
+                execution_time_ms: response.execution_time_ms,
+                affected_rows: response.affected_rows,
+                truncated: false,
+                max_rows_limit: None,
+            });
+        }
+
+        let mut collector = BatchCollector::new(sink, batch_size, max_rows);
+        let mut rows = sqlx::query(trimmed).fetch(executor);
+        while let Some(row) = rows.try_next().await? {
+            if collector.needs_columns() {
+                collector.set_columns(mysql_columns_to_defs(&row));
+            }
+            if !collector.push(mysql_row_to_cells(&row))? {
+                break;
+            }
+        }
+        collector.finish()
+    }
 }
 
 #[async_trait]
@@ -106,15 +369,146 @@ impl DbDriver for MySqlDriver {
     }
 
     async fn execute_raw(&self, sql: &str) -> Result<QueryResponse, AppError> {
+        self.execute_params(sql, Vec::new()).await
+    }
+
+    async fn execute_params(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        let mut guard = self.txn_conn.lock().await;
+        if let Some((ref mut conn, connection_id)) = *guard {
+            let _tracked = self.running.track(connection_id);
+            Self::execute_on(&mut **conn, sql, &params).await
+        } else {
+            drop(guard);
+            let mut conn = self.pool.acquire().await?;
+            let _tracked = self.track_acquired();
+            Self::execute_on(&mut *conn, sql, &params).await
+        }
+    }
+
+    async fn execute_with_messages(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        let mut guard = self.txn_conn.lock().await;
+        if let Some((ref mut conn, connection_id)) = *guard {
+            let _tracked = self.running.track(connection_id);
+            Self::execute_with_warnings(conn, sql, &params).await
+        } else {
+            drop(guard);
+            // Warnings are per session, so the statement and SHOW WARNINGS
+            // must run on the same pooled connection.
+            let mut conn = self.pool.acquire().await?;
+            let _tracked = self.track_acquired();
+            Self::execute_with_warnings(&mut conn, sql, &params).await
+        }
+    }
+
+    async fn execute_result_sets(&self, sql: &str) -> Result<Vec<QueryResponse>, AppError> {
         let mut guard = self.txn_conn.lock().await;
-        if let Some(ref mut conn) = *guard {
-            Self::execute_on(&mut **conn, sql).await
+        if let Some((ref mut conn, connection_id)) = *guard {
+            let _tracked = self.running.track(connection_id);
+            Self::result_sets_on(conn, sql).await
         } else {
             drop(guard);
-            Self::execute_on(&self.pool, sql).await
+            let mut conn = self.pool.acquire().await?;
+            let _tracked = self.track_acquired();
+            Self::result_sets_on(&mut conn, sql).await
         }
     }
 
+    /// MySQL 8.0.18 and later run `EXPLAIN ANALYZE`, which only produces the
+    /// text tree, inside a transaction that is rolled back. MariaDB and older
+    /// MySQL have no equivalent; their plans are the optimizer's estimates.
+    async fn explain(&self, sql: &str, analyze: bool) -> Result<QueryPlan, AppError> {
+        if analyze {
+            let version: String = sqlx::query_scalar("SELECT VERSION()")
+                .fetch_one(&self.pool)
+                .await?;
+            if explain_analyze_supported(&version) {
+                let explain_sql = format!("EXPLAIN ANALYZE {}", explain_target(sql));
+                let mut guard = self.txn_conn.lock().await;
+                let response = if let Some((ref mut conn, connection_id)) = *guard {
+                    let _tracked = self.running.track(connection_id);
+                    Self::explain_analyze_on(conn, &explain_sql, true).await?
+                } else {
+                    drop(guard);
+                    let mut conn = self.pool.acquire().await?;
+                    let _tracked = self.track_acquired();
+                    Self::explain_analyze_on(&mut conn, &explain_sql, false).await?
+                };
+                return Ok(parse_text_plan(&plan_document(&response)?, true));
+            }
+        }
+        let response = self
+            .execute_raw(&format!("EXPLAIN FORMAT=JSON {}", explain_target(sql)))
+            .await?;
+        parse_mysql(&plan_document(&response)?)
+    }
+
+    async fn execute_stream(
+        &self,
+        sql: &str,
+        batch_size: usize,
+        max_rows: usize,
+        sink: BatchSink<'_>,
+    ) -> Result<StreamSummary, AppError> {
+        let mut guard = self.txn_conn.lock().await;
+        if let Some((ref mut conn, connection_id)) = *guard {
+            let _tracked = self.running.track(connection_id);
+            Self::stream_on(&mut **conn, sql, batch_size, max_rows, sink).await
+        } else {
+            drop(guard);
+            if current_query_id().is_some() {
+                let mut conn = self.pool.acquire().await?;
+                let _tracked = self.track_acquired();
+                Self::stream_on(&mut *conn, sql, batch_size, max_rows, sink).await
+            } else {
+                Self::stream_on(&self.pool, sql, batch_size, max_rows, sink).await
+            }
+        }
+    }
+
+    /// Statements share one session so temp tables, variables and SET persist
+    /// between them. Outside an explicit transaction the connection is closed
+    /// afterwards instead of going back to the pool, so that session state (and
+    /// any transaction the script left open) does not carry over into later
+    /// queries or override the connection's session setup.
+    async fn execute_script(
+        &self,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) -> Result<(), AppError> {
+        let mut guard = self.txn_conn.lock().await;
+        if let Some((ref mut conn, connection_id)) = *guard {
+            let _tracked = self.running.track(connection_id);
+            Self::run_script_on(conn, statements, sink).await;
+            return Ok(());
+        }
+        drop(guard);
+
+        let mut conn = self.pool.acquire().await?;
+        let _tracked = self.track_acquired();
+        Self::run_script_on(&mut conn, statements, sink).await;
+        conn.close_on_drop();
+        Ok(())
+    }
+
+    async fn cancel(&self, query_id: &str) -> Result<bool, AppError> {
+        let connection_id = match self.running.get(query_id) {
+            Some(id) => id,
+            None => return Ok(false),
+        };
+        sqlx::query(&format!("KILL QUERY {}", connection_id))
+            .execute(&self.pool)
+            .await?;
+        Ok(true)
+    }
+
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
         let schemas = self.get_schemas().await?;
         Ok(schemas.iter().map(ContainerInfo::from).collect())
@@ -528,13 +922,16 @@ impl SqlDriver for MySqlDriver {
         }
         let mut conn = self.pool.acquire().await?;
         sqlx::query("BEGIN").execute(&mut *conn).await?;
-        *guard = Some(conn);
+        let connection_id: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()")
+            .fetch_one(&mut *conn)
+            .await?;
+        *guard = Some((conn, connection_id));
         Ok(())
     }
 
     async fn commit_transaction(&self) -> Result<(), AppError> {
         let mut guard = self.txn_conn.lock().await;
-        if let Some(ref mut conn) = *guard {
+        if let Some((ref mut conn, _)) = *guard {
             sqlx::query("COMMIT").execute(&mut **conn).await?;
             *guard = None;
             Ok(())
@@ -545,7 +942,7 @@ impl SqlDriver for MySqlDriver {
 
     async fn rollback_transaction(&self) -> Result<(), AppError> {
         let mut guard = self.txn_conn.lock().await;
-        if let Some(ref mut conn) = *guard {
+        if let Some((ref mut conn, _)) = *guard {
             sqlx::query("ROLLBACK").execute(&mut **conn).await?;
             *guard = None;
             Ok(())
@@ -605,3 +1002,15 @@ fn format_bytes(bytes: i64) -> String {
         format!("{} B", bytes)
     }
 }
+
+/// `SHOW WARNINGS` / `SHOW ERRORS` read the diagnostics area without clearing
+/// it, so fetching warnings after them would report everything twice.
+fn is_diagnostics_statement(sql: &str) -> bool {
+    let mut words = tokenize(sql, "mysql")
+        .into_iter()
+        .filter(|t| t.kind != TokenKind::Comment);
+    words.next().is_some_and(|t| t.is_keyword("SHOW"))
+        && words
+            .next()
+            .is_some_and(|t| t.is_keyword("WARNINGS") || t.is_keyword("ERRORS"))
+}
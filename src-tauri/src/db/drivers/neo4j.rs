@@ -4,6 +4,10 @@ use async_trait::async_trait;
 use neo4rs::{Graph, ConfigBuilder};
 
 use crate::db::traits::{DbDriver, GraphDriver};
+use crate::db::types::{
+    cell_text, date_cell, datetime_cell, datetime_tz_cell, naive_datetime, time_cell,
+    unbindable_param, zoned_datetime,
+};
 use crate::error::AppError;
 use crate::models::connection::{ConnectionConfig, DatabaseCategory};
 use crate::models::query::{CellValue, ColumnDef, QueryResponse};
@@ -31,6 +35,70 @@ impl Neo4jDriver {
         Ok(Self { graph })
     }
 
+    /// Run a Cypher query and collect every returned row.
+    async fn run_query(&self, query: neo4rs::Query) -> Result<QueryResponse, AppError> {
+        let start = Instant::now();
+
+        let mut result = self
+            .graph
+            .execute(query)
+            .await
+            .map_err(|e| AppError::Database(format!("Neo4j query error: {}", e)))?;
+
+        let mut columns: Vec<ColumnDef> = Vec::new();
+        let mut column_keys: Vec<String> = Vec::new();
+        let mut rows: Vec<Vec<CellValue>> = Vec::new();
+        let mut columns_set = false;
+
+        while let Ok(Some(row)) = result.next().await {
+            // Deserialize the row as a BoltMap to access its keys and values
+            let bolt_map: neo4rs::BoltMap = match row.to::<neo4rs::BoltMap>() {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+
+            if !columns_set {
+                column_keys = bolt_map.value.keys().map(|k| k.value.clone()).collect::<Vec<_>>();
+                column_keys.sort(); // Ensure consistent column ordering
+                columns = column_keys
+                    .iter()
+                    .map(|k| ColumnDef {
+                        name: k.clone(),
+                        data_type: "mixed".to_string(),
+                    })
+                    .collect();
+                columns_set = true;
+            }
+
+            let cells: Vec<CellValue> = column_keys
+                .iter()
+                .map(|k| {
+                    let bolt_key = neo4rs::BoltString::new(k);
+                    match bolt_map.value.get(&bolt_key) {
+                        Some(val) => Neo4jDriver::bolt_value_to_cell(val),
+                        None => CellValue::Null,
+                    }
+                })
+                .collect();
+
+            rows.push(cells);
+        }
+
+        let elapsed = start.elapsed().as_millis() as u64;
+        let row_count = rows.len();
+
+        Ok(QueryResponse {
+            columns,
+            rows,
+            row_count,
+            execution_time_ms: elapsed,
+            affected_rows: None,
+            truncated: false,
+            max_rows_limit: None,
+            messages: Vec::new(),
+        })
+    }
+
     fn bolt_value_to_cell(value: &neo4rs::BoltType) -> CellValue {
         use neo4rs::BoltType;
         match value {
@@ -40,11 +108,31 @@ impl Neo4jDriver {
             BoltType::Float(f) => CellValue::Float(f.value),
             BoltType::String(s) => CellValue::Text(s.value.clone()),
             BoltType::List(l) => {
-                let items: Vec<serde_json::Value> = l.value
-                    .iter()
-                    .map(|v| bolt_to_json(v))
-                    .collect();
-                CellValue::Json(serde_json::to_string(&items).unwrap_or_default())
+                CellValue::Array(l.value.iter().map(Self::bolt_value_to_cell).collect())
+            }
+            BoltType::Date(d) => match chrono::NaiveDate::try_from(d) {
+                Ok(v) => date_cell(v),
+                Err(_) => CellValue::Text(format!("{:?}", value)),
+            },
+            BoltType::LocalTime(t) => time_cell(chrono::NaiveTime::from(t)),
+            BoltType::Time(t) => {
+                let (time, offset) = <(chrono::NaiveTime, chrono::FixedOffset)>::from(t);
+                CellValue::Time(format!("{}{}", time.format("%H:%M:%S%.f"), offset))
+            }
+            BoltType::LocalDateTime(dt) => match chrono::NaiveDateTime::try_from(dt) {
+                Ok(v) => datetime_cell(v),
+                Err(_) => CellValue::Text(format!("{:?}", value)),
+            },
+            BoltType::DateTime(dt) => match chrono::DateTime::<chrono::FixedOffset>::try_from(dt) {
+                Ok(v) => datetime_tz_cell(v),
+                Err(_) => CellValue::Text(format!("{:?}", value)),
+            },
+            // The zone name is dropped; the offset it resolved to is kept.
+            BoltType::DateTimeZoneId(dt) => {
+                match chrono::DateTime::<chrono::FixedOffset>::try_from(dt) {
+                    Ok(v) => datetime_tz_cell(v),
+                    Err(_) => CellValue::Text(format!("{:?}", value)),
+                }
             }
             BoltType::Map(m) => {
                 let map: serde_json::Map<String, serde_json::Value> = m.value
@@ -79,6 +167,53 @@ impl Neo4jDriver {
     }
 }
 
+fn cell_to_bolt(value: CellValue) -> Result<neo4rs::BoltType, AppError> {
+    use neo4rs::BoltType;
+    Ok(match value {
+        CellValue::Null => BoltType::Null(neo4rs::BoltNull),
+        CellValue::Bool(v) => v.into(),
+        CellValue::Int(v) => v.into(),
+        CellValue::Float(v) => v.into(),
+        CellValue::Text(v) | CellValue::Timestamp(v) | CellValue::Json(v) => v.into(),
+        CellValue::Date(ref v) => match chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d") {
+            Ok(date) => BoltType::Date(date.into()),
+            Err(_) => v.clone().into(),
+        },
+        CellValue::Time(ref v) => match chrono::NaiveTime::parse_from_str(v, "%H:%M:%S%.f") {
+            Ok(time) => BoltType::LocalTime(time.into()),
+            Err(_) => v.clone().into(),
+        },
+        CellValue::DateTime { tz: None, micros } => match naive_datetime(micros) {
+            Some(ts) => BoltType::LocalDateTime(ts.into()),
+            None => micros.into(),
+        },
+        CellValue::DateTime { tz: Some(ref tz), micros } => match zoned_datetime(micros, tz) {
+            Some(ts) => BoltType::DateTime(ts.into()),
+            None => micros.into(),
+        },
+        CellValue::Array(items) => BoltType::List(
+            items
+                .into_iter()
+                .map(cell_to_bolt)
+                .collect::<Result<Vec<_>, _>>()?
+                .into(),
+        ),
+        // Neo4j has no decimal, UUID or enum types; they are passed as strings.
+        CellValue::Decimal { .. }
+        | CellValue::Uuid(_)
+        | CellValue::Interval { .. }
+        | CellValue::Enum(_) => cell_text(&value).unwrap_or_default().into(),
+        CellValue::Binary(_) => {
+            return Err(AppError::UnsupportedOperation(
+                "Binary parameters are not supported for Neo4j".to_string(),
+            ));
+        }
+        CellValue::LargeText { .. } | CellValue::LargeJson { .. } | CellValue::LargeBinary { .. } => {
+            return Err(unbindable_param());
+        }
+    })
+}
+
 fn bolt_to_json(value: &neo4rs::BoltType) -> serde_json::Value {
     use neo4rs::BoltType;
     match value {
@@ -108,66 +243,20 @@ impl DbDriver for Neo4jDriver {
     }
 
     async fn execute_raw(&self, query: &str) -> Result<QueryResponse, AppError> {
-        let start = Instant::now();
-        let trimmed = query.trim();
-
-        let mut result = self
-            .graph
-            .execute(neo4rs::query(trimmed))
-            .await
-            .map_err(|e| AppError::Database(format!("Neo4j query error: {}", e)))?;
-
-        let mut columns: Vec<ColumnDef> = Vec::new();
-        let mut column_keys: Vec<String> = Vec::new();
-        let mut rows: Vec<Vec<CellValue>> = Vec::new();
-        let mut columns_set = false;
-
-        while let Ok(Some(row)) = result.next().await {
-            // Deserialize the row as a BoltMap to access its keys and values
-            let bolt_map: neo4rs::BoltMap = match row.to::<neo4rs::BoltMap>() {
-                Ok(m) => m,
-                Err(_) => continue,
-            };
-
-            if !columns_set {
-                column_keys = bolt_map.value.keys().map(|k| k.value.clone()).collect::<Vec<_>>();
-                column_keys.sort(); // Ensure consistent column ordering
-                columns = column_keys
-                    .iter()
-                    .map(|k| ColumnDef {
-                        name: k.clone(),
-                        data_type: "mixed".to_string(),
-                    })
-                    .collect();
-                columns_set = true;
-            }
-
-            let cells: Vec<CellValue> = column_keys
-                .iter()
-                .map(|k| {
-                    let bolt_key = neo4rs::BoltString::new(k);
-                    match bolt_map.value.get(&bolt_key) {
-                        Some(val) => Neo4jDriver::bolt_value_to_cell(val),
-                        None => CellValue::Null,
-                    }
-                })
-                .collect();
+        self.run_query(neo4rs::query(query.trim())).await
+    }
 
-            rows.push(cells);
+    /// Parameters are referenced as `$p1`, `$p2`, ... in the Cypher text.
+    async fn execute_params(
+        &self,
+        query: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        let mut cypher = neo4rs::query(query.trim());
+        for (i, param) in params.into_iter().enumerate() {
+            cypher = cypher.param(&format!("p{}", i + 1), cell_to_bolt(param)?);
         }
-
-        let elapsed = start.elapsed().as_millis() as u64;
-        let row_count = rows.len();
-
-        Ok(QueryResponse {
-            columns,
-            rows,
-            row_count,
-            execution_time_ms: elapsed,
-            affected_rows: None,
-            truncated: false,
-            max_rows_limit: None,
-        })
+        self.run_query(cypher).await
     }
 
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
@@ -2,16 +2,24 @@ use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use futures::TryStreamExt;
 use sqlx::pool::PoolConnection;
-use sqlx::postgres::{PgPool, PgPoolOptions, Postgres};
-use sqlx::{Executor, Row};
+use sqlx::postgres::{PgConnection, PgPool, PgPoolOptions, Postgres};
+use sqlx::{Connection, Executor, Row};
 use tokio::sync::Mutex;
 
+use crate::db::cancel::{current_query_id, report_session, take_session, ServerHandles, TrackedQuery};
+use crate::db::keyset::quote_ident;
+use crate::db::messages::capture;
+use crate::db::plan::{explain_target, parse_postgres, plan_document};
+use crate::db::statement::analyze;
+use crate::db::stream::{BatchCollector, BatchSink, StatementSink};
 use crate::db::traits::{DbDriver, SqlDriver};
-use crate::db::types::{pg_columns_to_defs, pg_row_to_cells};
+use crate::db::types::{pg_bind_cell, pg_columns_to_defs, pg_row_to_cells};
 use crate::error::AppError;
-use crate::models::connection::{ConnectionConfig, DatabaseCategory};
-use crate::models::query::QueryResponse;
+use crate::models::connection::{ConnectionConfig, DatabaseCategory, DatabaseType};
+use crate::models::plan::QueryPlan;
+use crate::models::query::{CellValue, QueryResponse, StreamSummary};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, EnumInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo,
     RoutineInfo, SchemaInfo, SequenceInfo, TableInfo, TableStats,
@@ -19,16 +27,74 @@ use crate::models::schema::{
 
 pub struct PostgresDriver {
     pool: PgPool,
-    txn_conn: Mutex<Option<PoolConnection<Postgres>>>,
+    /// Connection holding the open transaction, and its backend pid.
+    txn_conn: Mutex<Option<(PoolConnection<Postgres>, i32)>>,
+    /// Backend pids of statements started under a query id, for server-side cancel.
+    running: ServerHandles<i32>,
+}
+
+/// Statements run on every new pooled connection: the configured server-side
+/// timeouts, the default schema, then the connection's init script.
+fn session_statements(config: &ConnectionConfig) -> Vec<String> {
+    let mut statements = Vec::new();
+    if let Some(ms) = config.statement_timeout_ms {
+        statements.push(format!("SET statement_timeout = {}", ms));
+    }
+    // Redshift has no lock_timeout; lock waits count against statement_timeout.
+    if let Some(ms) = config.lock_timeout_ms {
+        if config.db_type != DatabaseType::Redshift {
+            statements.push(format!("SET lock_timeout = {}", ms));
+        }
+    }
+    if let Some(schema) = config.default_schema.as_deref().filter(|s| !s.is_empty()) {
+        statements.push(format!("SET search_path TO {}", quote_ident("postgres", schema)));
+    }
+    statements.extend(config.init_sql.iter().cloned());
+    statements
+}
+
+/// Report the backend pid of `conn` to the query acquiring it. Runs in the
+/// pool's hooks, in place of sqlx's liveness ping for idle connections, so
+/// tracking a statement for `cancel` costs no extra round trip.
+async fn report_backend(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(conn)
+        .await?;
+    report_session(pid.into());
+    Ok(())
 }
 
 impl PostgresDriver {
     pub async fn connect(config: &ConnectionConfig) -> Result<Self, AppError> {
         let url = config.to_connection_url();
+        let session = session_statements(config);
         let pool = PgPoolOptions::new()
             .max_connections(config.pool_max_connections)
             .idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs))
             .acquire_timeout(Duration::from_secs(config.pool_acquire_timeout_secs))
+            .after_connect(move |conn, _meta| {
+                let session = session.clone();
+                Box::pin(async move {
+                    for statement in &session {
+                        conn.execute(statement.as_str()).await?;
+                    }
+                    if current_query_id().is_some() {
+                        report_backend(conn).await?;
+                    }
+                    Ok(())
+                })
+            })
+            .test_before_acquire(false)
+            .before_acquire(|conn, _meta| {
+                Box::pin(async move {
+                    if current_query_id().is_some() {
+                        report_backend(conn).await?;
+                    } else {
+                        conn.ping().await?;
+                    }
+                    Ok(true)
+                })
+            })
             .connect(&url)
             .await
             .map_err(|e| AppError::Database(format!("Failed to connect to PostgreSQL: {}", e)))?;
@@ -36,27 +102,34 @@ impl PostgresDriver {
         Ok(Self {
             pool,
             txn_conn: Mutex::new(None),
+            running: ServerHandles::new(),
         })
     }
 
+    /// Record the backend pid the pool reported for the connection the current
+    /// query just acquired, if the query has an id.
+    fn track_acquired(&self) -> Option<TrackedQuery<'_, i32>> {
+        take_session().and_then(|pid| self.running.track(pid as i32))
+    }
+
     /// Execute a query using the transaction connection if active, otherwise pool.
     async fn execute_on<'e, E: Executor<'e, Database = Postgres>>(
         executor: E,
         sql: &str,
+        params: &[CellValue],
     ) -> Result<QueryResponse, AppError> {
         let start = Instant::now();
         let trimmed = sql.trim();
-        let upper = trimmed.to_uppercase();
+        let returns_rows = analyze(trimmed, "postgres").returns_rows;
 
-        let is_select = upper.starts_with("SELECT")
-            || upper.starts_with("WITH")
-            || upper.starts_with("SHOW")
-            || upper.starts_with("EXPLAIN")
-            || upper.starts_with("TABLE")
-            || upper.starts_with("VALUES");
+        let mut query = sqlx::query(trimmed);
+        for param in params {
+            query = pg_bind_cell(query, param)?;
+        }
 
-        if is_select {
-            let rows = sqlx::query(trimmed).fetch_all(executor).await?;
+        if returns_rows {
+            let (rows, messages) = capture(query.fetch_all(executor)).await;
+            let rows = rows?;
             let elapsed = start.elapsed().as_millis() as u64;
 
             let columns = if rows.is_empty() {
@@ -76,9 +149,11 @@ impl PostgresDriver {
                 affected_rows: None,
                 truncated: false,
                 max_rows_limit: None,
+                messages,
             })
         } else {
-            let result = sqlx::query(trimmed).execute(executor).await?;
+            let (result, messages) = capture(query.execute(executor)).await;
+            let result = result?;
             let elapsed = start.elapsed().as_millis() as u64;
             let affected = result.rows_affected();
 
@@ -90,9 +165,102 @@ impl PostgresDriver {
                 affected_rows: Some(affected),
                 truncated: false,
                 max_rows_limit: None,
+                messages,
             })
         }
     }
+
+    /// Run script statements in order on one connection.
+    async fn run_script_on(
+        conn: &mut PgConnection,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) {
+        for (index, statement) in statements.iter().enumerate() {
+            let result = Self::execute_on(&mut *conn, statement, &[]).await;
+            if !sink(index, result) {
+                break;
+            }
+        }
+    }
+
+    /// Run `EXPLAIN ANALYZE` inside a transaction (or a savepoint of the open
+    /// one) that is always rolled back, so explaining a write changes nothing.
+    async fn explain_analyze_on(
+        conn: &mut PgConnection,
+        explain_sql: &str,
+        in_transaction: bool,
+    ) -> Result<QueryResponse, AppError> {
+        let (begin, rollback) = if in_transaction {
+            ("SAVEPOINT queryark_explain", "ROLLBACK TO SAVEPOINT queryark_explain")
+        } else {
+            ("BEGIN", "ROLLBACK")
+        };
+        sqlx::query(begin).execute(&mut *conn).await?;
+        let result = Self::execute_on(&mut *conn, explain_sql, &[]).await;
+        sqlx::query(rollback).execute(&mut *conn).await.ok();
+        if in_transaction {
+            sqlx::query("RELEASE SAVEPOINT queryark_explain")
+                .execute(&mut *conn)
+                .await
+                .ok();
+        }
+        result
+    }
+
+    /// Run an `EXPLAIN ANALYZE` statement on the transaction connection if one
+    /// is open, otherwise on a pooled one, rolling back what it executed. Also
+    /// used by the wrappers whose engines only explain in text.
+    pub(crate) async fn run_explain_analyze(
+        &self,
+        explain_sql: &str,
+    ) -> Result<QueryResponse, AppError> {
+        let mut guard = self.txn_conn.lock().await;
+        if let Some((ref mut conn, pid)) = *guard {
+            let _tracked = self.running.track(pid);
+            Self::explain_analyze_on(conn, explain_sql, true).await
+        } else {
+            drop(guard);
+            let mut conn = self.pool.acquire().await?;
+            let _tracked = self.track_acquired();
+            Self::explain_analyze_on(&mut conn, explain_sql, false).await
+        }
+    }
+
+    /// Stream a query's rows in batches, stopping at `max_rows`.
+    async fn stream_on<'e, E: Executor<'e, Database = Postgres>>(
+        executor: E,
+        sql: &str,
+        batch_size: usize,
+        max_rows: usize,
+        sink: BatchSink<'_>,
+    ) -> Result<StreamSummary, AppError> {
+        let trimmed = sql.trim();
+        let returns_rows = analyze(trimmed, "postgres").returns_rows;
+
+        if !returns_rows {
+            let response = Self::execute_on(executor, sql, &[]).await?;
+            return Ok(StreamSummary {
+                row_count: 0,
+                execution_time_ms: response.execution_time_ms,
+                affected_rows: response.affected_rows,
+                truncated: false,
+                max_rows_limit: None,
+            });
+        }
+
+        let mut collector = BatchCollector::new(sink, batch_size, max_rows);
+        let mut rows = sqlx::query(trimmed).fetch(executor);
+        while let Some(row) = rows.try_next().await? {
+            if collector.needs_columns() {
+                collector.set_columns(pg_columns_to_defs(&row));
+            }
+            if !collector.push(pg_row_to_cells(&row))? {
+                break;
+            }
+        }
+        collector.finish()
+    }
 }
 
 #[async_trait]
@@ -106,13 +274,105 @@ impl DbDriver for PostgresDriver {
     }
 
     async fn execute_raw(&self, sql: &str) -> Result<QueryResponse, AppError> {
+        self.execute_params(sql, Vec::new()).await
+    }
+
+    async fn execute_params(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        let mut guard = self.txn_conn.lock().await;
+        if let Some((ref mut conn, pid)) = *guard {
+            let _tracked = self.running.track(pid);
+            Self::execute_on(&mut **conn, sql, &params).await
+        } else {
+            drop(guard);
+            if current_query_id().is_some() {
+                let mut conn = self.pool.acquire().await?;
+                let _tracked = self.track_acquired();
+                Self::execute_on(&mut *conn, sql, &params).await
+            } else {
+                Self::execute_on(&self.pool, sql, &params).await
+            }
+        }
+    }
+
+    async fn execute_stream(
+        &self,
+        sql: &str,
+        batch_size: usize,
+        max_rows: usize,
+        sink: BatchSink<'_>,
+    ) -> Result<StreamSummary, AppError> {
         let mut guard = self.txn_conn.lock().await;
-        if let Some(ref mut conn) = *guard {
-            Self::execute_on(&mut **conn, sql).await
+        if let Some((ref mut conn, pid)) = *guard {
+            let _tracked = self.running.track(pid);
+            Self::stream_on(&mut **conn, sql, batch_size, max_rows, sink).await
         } else {
             drop(guard);
-            Self::execute_on(&self.pool, sql).await
+            if current_query_id().is_some() {
+                let mut conn = self.pool.acquire().await?;
+                let _tracked = self.track_acquired();
+                Self::stream_on(&mut *conn, sql, batch_size, max_rows, sink).await
+            } else {
+                Self::stream_on(&self.pool, sql, batch_size, max_rows, sink).await
+            }
+        }
+    }
+
+    /// Statements share one session so temp tables, variables and SET persist
+    /// between them. Outside an explicit transaction the connection is closed
+    /// afterwards instead of going back to the pool, so that session state (and
+    /// any transaction the script left open) does not carry over into later
+    /// queries or override the connection's session setup.
+    async fn execute_script(
+        &self,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) -> Result<(), AppError> {
+        let mut guard = self.txn_conn.lock().await;
+        if let Some((ref mut conn, pid)) = *guard {
+            let _tracked = self.running.track(pid);
+            Self::run_script_on(conn, statements, sink).await;
+            return Ok(());
+        }
+        drop(guard);
+
+        let mut conn = self.pool.acquire().await?;
+        let _tracked = self.track_acquired();
+        Self::run_script_on(&mut conn, statements, sink).await;
+        conn.close_on_drop();
+        Ok(())
+    }
+
+    async fn explain(&self, sql: &str, analyze: bool) -> Result<QueryPlan, AppError> {
+        if !analyze {
+            let response = self
+                .execute_raw(&format!("EXPLAIN (FORMAT JSON) {}", explain_target(sql)))
+                .await?;
+            return parse_postgres(&plan_document(&response)?, false);
         }
+
+        let response = self
+            .run_explain_analyze(&format!(
+                "EXPLAIN (FORMAT JSON, ANALYZE, BUFFERS) {}",
+                explain_target(sql)
+            ))
+            .await?;
+        parse_postgres(&plan_document(&response)?, true)
+    }
+
+    async fn cancel(&self, query_id: &str) -> Result<bool, AppError> {
+        let pid = match self.running.get(query_id) {
+            Some(id) => id,
+            None => return Ok(false),
+        };
+        let cancelled: bool = sqlx::query_scalar("SELECT pg_cancel_backend($1)")
+            .bind(pid)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(cancelled)
     }
 
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
@@ -199,11 +459,23 @@ impl SqlDriver for PostgresDriver {
         Ok(tables)
     }
 
+    /// information_schema reports enum, composite and array columns
+    /// as `USER-DEFINED` and `ARRAY`; those get their real type name from
+    /// `format_type`, which is also what bind parameters are cast to.
     async fn get_columns(&self, schema: &str, table: &str) -> Result<Vec<ColumnInfo>, AppError> {
         let rows = sqlx::query(
-            "SELECT c.column_name, c.data_type, c.is_nullable, c.column_default, c.ordinal_position, \
+            "SELECT c.column_name, \
+             CASE WHEN c.data_type IN ('USER-DEFINED', 'ARRAY') \
+               THEN COALESCE(format_type(a.atttypid, a.atttypmod), c.data_type) \
+               ELSE c.data_type END AS data_type, \
+             c.is_nullable, c.column_default, c.ordinal_position, \
              CASE WHEN tc.constraint_type = 'PRIMARY KEY' THEN true ELSE false END as is_pk \
              FROM information_schema.columns c \
+             LEFT JOIN pg_catalog.pg_namespace n ON n.nspname = c.table_schema \
+             LEFT JOIN pg_catalog.pg_class cl \
+               ON cl.relnamespace = n.oid AND cl.relname = c.table_name \
+             LEFT JOIN pg_catalog.pg_attribute a \
+               ON a.attrelid = cl.oid AND a.attname = c.column_name \
              LEFT JOIN information_schema.key_column_usage kcu \
                ON c.table_schema = kcu.table_schema \
                AND c.table_name = kcu.table_name \
@@ -596,13 +868,16 @@ impl SqlDriver for PostgresDriver {
         }
         let mut conn = self.pool.acquire().await?;
         sqlx::query("BEGIN").execute(&mut *conn).await?;
-        *guard = Some(conn);
+        let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+            .fetch_one(&mut *conn)
+            .await?;
+        *guard = Some((conn, pid));
         Ok(())
     }
 
     async fn commit_transaction(&self) -> Result<(), AppError> {
         let mut guard = self.txn_conn.lock().await;
-        if let Some(ref mut conn) = *guard {
+        if let Some((ref mut conn, _)) = *guard {
             sqlx::query("COMMIT").execute(&mut **conn).await?;
             *guard = None;
             Ok(())
@@ -613,7 +888,7 @@ impl SqlDriver for PostgresDriver {
 
     async fn rollback_transaction(&self) -> Result<(), AppError> {
         let mut guard = self.txn_conn.lock().await;
-        if let Some(ref mut conn) = *guard {
+        if let Some((ref mut conn, _)) = *guard {
             sqlx::query("ROLLBACK").execute(&mut **conn).await?;
             *guard = None;
             Ok(())
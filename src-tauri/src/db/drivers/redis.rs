@@ -72,6 +72,7 @@ impl DbDriver for RedisDriver {
                     affected_rows: None,
                     truncated: false,
                     max_rows_limit: None,
+                    messages: Vec::new(),
                 })
             }
             Err(e) => Err(AppError::Database(format!("Redis error: {}", e))),
@@ -152,6 +153,7 @@ impl DbDriver for RedisDriver {
             affected_rows: None,
             truncated: false,
             max_rows_limit: None,
+            messages: Vec::new(),
         })
     }
 
@@ -1,10 +1,13 @@
 use async_trait::async_trait;
 
 use crate::db::drivers::postgres::PostgresDriver;
+use crate::db::plan::{explain_target, parse_text_plan, plan_document};
+use crate::db::stream::{BatchSink, StatementSink};
 use crate::db::traits::{DbDriver, SqlDriver};
 use crate::error::AppError;
 use crate::models::connection::{ConnectionConfig, DatabaseCategory};
-use crate::models::query::QueryResponse;
+use crate::models::plan::QueryPlan;
+use crate::models::query::{CellValue, QueryResponse, StreamSummary};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, EnumInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo,
     RoutineInfo, SchemaInfo, SequenceInfo, TableInfo, TableStats,
@@ -36,6 +39,50 @@ impl DbDriver for RedshiftDriver {
         self.inner.execute_raw(sql).await
     }
 
+    async fn execute_params(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        self.inner.execute_params(sql, params).await
+    }
+
+    async fn execute_result_sets(&self, sql: &str) -> Result<Vec<QueryResponse>, AppError> {
+        self.inner.execute_result_sets(sql).await
+    }
+
+    async fn execute_stream(
+        &self,
+        sql: &str,
+        batch_size: usize,
+        max_rows: usize,
+        sink: BatchSink<'_>,
+    ) -> Result<StreamSummary, AppError> {
+        self.inner.execute_stream(sql, batch_size, max_rows, sink).await
+    }
+
+    async fn execute_script(
+        &self,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) -> Result<(), AppError> {
+        self.inner.execute_script(statements, sink).await
+    }
+
+    /// Redshift only explains in text and has no `EXPLAIN ANALYZE`, so plans
+    /// are always estimates and `analyze` is ignored.
+    async fn explain(&self, sql: &str, _analyze: bool) -> Result<QueryPlan, AppError> {
+        let response = self
+            .inner
+            .execute_raw(&format!("EXPLAIN {}", explain_target(sql)))
+            .await?;
+        Ok(parse_text_plan(&plan_document(&response)?, false))
+    }
+
+    async fn cancel(&self, query_id: &str) -> Result<bool, AppError> {
+        self.inner.cancel(query_id).await
+    }
+
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
         let schemas = self.get_schemas().await?;
         Ok(schemas.iter().map(ContainerInfo::from).collect())
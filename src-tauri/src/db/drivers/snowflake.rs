@@ -3,102 +3,30 @@
 use std::sync::Arc;
 use std::time::Instant;
 
-use arrow::array::{
-    Array, BooleanArray, Float32Array, Float64Array, Int8Array, Int16Array, Int32Array,
-    Int64Array, StringArray, RecordBatch,
-};
-use arrow::datatypes::DataType as ArrowDataType;
 use async_trait::async_trait;
 use snowflake_api::SnowflakeApi;
 
+use crate::db::arrow_convert;
+use crate::db::cancel::{current_query_id, ServerHandles, TrackedQuery};
 use crate::db::escape::escape_sql_literal;
+use crate::db::params::inline_params;
+use crate::db::plan::{explain_target, parse_snowflake, plan_document};
+use crate::db::statement::analyze;
 use crate::db::traits::{DbDriver, SqlDriver};
 use crate::error::AppError;
 use crate::models::connection::{ConnectionConfig, DatabaseCategory};
+use crate::models::plan::QueryPlan;
 use crate::models::query::{CellValue, ColumnDef, QueryResponse};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo, SchemaInfo, TableInfo,
 };
 
-/// Convert Arrow RecordBatches into ColumnDefs and rows of CellValues.
-fn arrow_batches_to_response(batches: &[RecordBatch]) -> (Vec<ColumnDef>, Vec<Vec<CellValue>>) {
-    if batches.is_empty() {
-        return (Vec::new(), Vec::new());
-    }
-
-    let schema = batches[0].schema();
-    let columns: Vec<ColumnDef> = schema
-        .fields()
-        .iter()
-        .map(|f| ColumnDef {
-            name: f.name().clone(),
-            data_type: format!("{}", f.data_type()),
-        })
-        .collect();
-
-    let mut rows = Vec::new();
-
-    for batch in batches {
-        for row_idx in 0..batch.num_rows() {
-            let mut row = Vec::with_capacity(batch.num_columns());
-            for col_idx in 0..batch.num_columns() {
-                let col = batch.column(col_idx);
-                if col.is_null(row_idx) {
-                    row.push(CellValue::Null);
-                    continue;
-                }
-                let cell = match col.data_type() {
-                    ArrowDataType::Boolean => {
-                        let arr = col.as_any().downcast_ref::<BooleanArray>().unwrap();
-                        CellValue::Bool(arr.value(row_idx))
-                    }
-                    ArrowDataType::Int8 => {
-                        let arr = col.as_any().downcast_ref::<Int8Array>().unwrap();
-                        CellValue::Int(arr.value(row_idx) as i64)
-                    }
-                    ArrowDataType::Int16 => {
-                        let arr = col.as_any().downcast_ref::<Int16Array>().unwrap();
-                        CellValue::Int(arr.value(row_idx) as i64)
-                    }
-                    ArrowDataType::Int32 => {
-                        let arr = col.as_any().downcast_ref::<Int32Array>().unwrap();
-                        CellValue::Int(arr.value(row_idx) as i64)
-                    }
-                    ArrowDataType::Int64 => {
-                        let arr = col.as_any().downcast_ref::<Int64Array>().unwrap();
-                        CellValue::Int(arr.value(row_idx))
-                    }
-                    ArrowDataType::Float32 => {
-                        let arr = col.as_any().downcast_ref::<Float32Array>().unwrap();
-                        CellValue::Float(arr.value(row_idx) as f64)
-                    }
-                    ArrowDataType::Float64 => {
-                        let arr = col.as_any().downcast_ref::<Float64Array>().unwrap();
-                        CellValue::Float(arr.value(row_idx))
-                    }
-                    ArrowDataType::Utf8 => {
-                        let arr = col.as_any().downcast_ref::<StringArray>().unwrap();
-                        CellValue::Text(arr.value(row_idx).to_string())
-                    }
-                    _ => {
-                        // Fallback: render as display string
-                        let arr_str = arrow::util::display::array_value_to_string(col, row_idx)
-                            .unwrap_or_default();
-                        CellValue::Text(arr_str)
-                    }
-                };
-                row.push(cell);
-            }
-            rows.push(row);
-        }
-    }
-
-    (columns, rows)
-}
-
 pub struct SnowflakeDriver {
     client: Arc<SnowflakeApi>,
     database: String,
+    /// Comment tags prepended to statements started under a query id, used to
+    /// find the Snowflake query id in the session history when cancelling.
+    running: ServerHandles<String>,
 }
 
 impl SnowflakeDriver {
@@ -126,7 +54,7 @@ impl SnowflakeDriver {
             account,
             Some(warehouse),
             Some(&database),
-            None, // schema
+            config.default_schema.as_deref().filter(|s| !s.is_empty()),
             username,
             role,
             password,
@@ -138,12 +66,49 @@ impl SnowflakeDriver {
             .await
             .map_err(|e| AppError::Database(format!("Failed to connect to Snowflake: {}", e)))?;
 
+        // The client keeps a single session, so session settings and the init
+        // script only need to run once.
+        let mut timeouts = Vec::new();
+        if let Some(ms) = config.statement_timeout_ms {
+            timeouts.push(format!("STATEMENT_TIMEOUT_IN_SECONDS = {}", ms.div_ceil(1000)));
+        }
+        if let Some(ms) = config.lock_timeout_ms {
+            timeouts.push(format!("LOCK_TIMEOUT = {}", ms.div_ceil(1000)));
+        }
+        if !timeouts.is_empty() {
+            api.exec(&format!("ALTER SESSION SET {}", timeouts.join(" ")))
+                .await
+                .map_err(|e| AppError::Database(format!("Failed to set Snowflake session timeouts: {}", e)))?;
+        }
+        for statement in &config.init_sql {
+            api.exec(statement)
+                .await
+                .map_err(|e| AppError::Database(format!("Snowflake init statement failed: {}", e)))?;
+        }
+
         Ok(Self {
             client: Arc::new(api),
             database,
+            running: ServerHandles::new(),
         })
     }
 
+    /// Prefix the statement with a comment tag when a query id is set, so it
+    /// can be located in QUERY_HISTORY_BY_SESSION and cancelled.
+    fn tag_statement(&self, sql: &str) -> (String, Option<TrackedQuery<'_, String>>) {
+        match current_query_id() {
+            Some(query_id) => {
+                let safe_id: String = query_id
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+                    .collect();
+                let tag = format!("/* queryark:{} */", safe_id);
+                (format!("{} {}", tag, sql), self.running.track(tag))
+            }
+            None => (sql.to_string(), None),
+        }
+    }
+
     /// Execute a query and return Arrow-converted results.
     async fn query_to_response(&self, sql: &str) -> Result<(Vec<ColumnDef>, Vec<Vec<CellValue>>), AppError> {
         let result = self.client
@@ -153,7 +118,7 @@ impl SnowflakeDriver {
 
         match result {
             snowflake_api::QueryResult::Arrow(batches) => {
-                Ok(arrow_batches_to_response(&batches))
+                Ok(arrow_convert::batches_to_response(&batches))
             }
             snowflake_api::QueryResult::Json(json_result) => {
                 // json_result is JsonResult { value: serde_json::Value, schema: Vec<FieldSchema> }
@@ -215,17 +180,12 @@ impl DbDriver for SnowflakeDriver {
     async fn execute_raw(&self, sql: &str) -> Result<QueryResponse, AppError> {
         let start = Instant::now();
         let trimmed = sql.trim();
-        let upper = trimmed.to_uppercase();
+        let returns_rows = analyze(trimmed, "snowflake").returns_rows;
 
-        let is_query = upper.starts_with("SELECT")
-            || upper.starts_with("WITH")
-            || upper.starts_with("SHOW")
-            || upper.starts_with("DESCRIBE")
-            || upper.starts_with("EXPLAIN")
-            || upper.starts_with("LIST");
+        let (tagged, _tracked) = self.tag_statement(trimmed);
 
-        if is_query {
-            let (columns, rows) = self.query_to_response(trimmed).await?;
+        if returns_rows {
+            let (columns, rows) = self.query_to_response(&tagged).await?;
             let elapsed = start.elapsed().as_millis() as u64;
             let row_count = rows.len();
 
@@ -237,11 +197,12 @@ impl DbDriver for SnowflakeDriver {
                 affected_rows: None,
                 truncated: false,
                 max_rows_limit: None,
+                messages: Vec::new(),
             })
         } else {
             // DML / DDL
             self.client
-                .exec(trimmed)
+                .exec(&tagged)
                 .await
                 .map_err(|e| AppError::Database(format!("Snowflake execute error: {}", e)))?;
             let elapsed = start.elapsed().as_millis() as u64;
@@ -254,10 +215,46 @@ impl DbDriver for SnowflakeDriver {
                 affected_rows: Some(0),
                 truncated: false,
                 max_rows_limit: None,
+                messages: Vec::new(),
             })
         }
     }
 
+    /// The Snowflake REST client has no bind support, so parameters are inlined
+    /// as escaped literals.
+    async fn execute_params(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        let inlined = inline_params(sql, "snowflake", &params)?;
+        self.execute_raw(&inlined).await
+    }
+
+    /// The compiled plan from `EXPLAIN USING JSON`; it carries pruning figures
+    /// but no row estimates, and `analyze` is ignored.
+    async fn explain(&self, sql: &str, _analyze: bool) -> Result<QueryPlan, AppError> {
+        let response = self
+            .execute_raw(&format!("EXPLAIN USING JSON {}", explain_target(sql)))
+            .await?;
+        parse_snowflake(&plan_document(&response)?)
+    }
+
+    async fn cancel(&self, query_id: &str) -> Result<bool, AppError> {
+        let tag = match self.running.get(query_id) {
+            Some(tag) => tag,
+            None => return Ok(false),
+        };
+        let sql = format!(
+            "SELECT SYSTEM$CANCEL_QUERY(query_id) \
+             FROM TABLE(INFORMATION_SCHEMA.QUERY_HISTORY_BY_SESSION()) \
+             WHERE execution_status = 'RUNNING' AND STARTSWITH(query_text, '{}')",
+            escape_sql_literal(&tag)
+        );
+        let (_, rows) = self.query_to_response(&sql).await?;
+        Ok(!rows.is_empty())
+    }
+
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
         let schemas = self.get_schemas().await?;
         Ok(schemas.iter().map(ContainerInfo::from).collect())
@@ -1,15 +1,22 @@
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use futures::TryStreamExt;
 use sqlx::pool::PoolConnection;
-use sqlx::sqlite::{Sqlite, SqlitePool, SqlitePoolOptions};
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments, SqliteConnection, SqlitePool, SqlitePoolOptions};
 use sqlx::{Column, Executor, Row, TypeInfo, ValueRef};
 use tokio::sync::Mutex;
 
+use crate::db::plan::{explain_target, parse_sqlite};
+use crate::db::statement::{analyze, StatementKind};
+use crate::db::stream::{BatchCollector, BatchSink, StatementSink};
 use crate::db::traits::{DbDriver, SqlDriver};
+use crate::db::types::{cell_text, date_cell, datetime_cell, unbindable_param};
 use crate::error::AppError;
 use crate::models::connection::{ConnectionConfig, DatabaseCategory};
-use crate::models::query::{CellValue, ColumnDef, QueryResponse};
+use crate::models::plan::QueryPlan;
+use crate::models::query::{CellValue, ColumnDef, QueryResponse, ServerMessage, StreamSummary};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo, SchemaInfo, TableInfo,
 };
@@ -22,10 +29,27 @@ pub struct SqliteDriver {
 impl SqliteDriver {
     pub async fn connect(config: &ConnectionConfig) -> Result<Self, AppError> {
         let url = config.to_connection_url();
+        // SQLite has no statement timeout; the lock timeout is how long a
+        // connection waits on a locked database before failing with SQLITE_BUSY.
+        // The init script (PRAGMAs, ATTACH) runs on every new connection.
+        let mut session = Vec::new();
+        if let Some(ms) = config.lock_timeout_ms {
+            session.push(format!("PRAGMA busy_timeout = {}", ms));
+        }
+        session.extend(config.init_sql.iter().cloned());
         let pool = SqlitePoolOptions::new()
             .max_connections(config.pool_max_connections)
             .idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs))
             .acquire_timeout(Duration::from_secs(config.pool_acquire_timeout_secs))
+            .after_connect(move |conn, _meta| {
+                let session = session.clone();
+                Box::pin(async move {
+                    for statement in &session {
+                        conn.execute(statement.as_str()).await?;
+                    }
+                    Ok(())
+                })
+            })
             .connect(&url)
             .await
             .map_err(|e| AppError::Database(format!("Failed to connect to SQLite: {}", e)))?;
@@ -36,22 +60,22 @@ impl SqliteDriver {
         })
     }
 
-    async fn execute_on<'e, E: Executor<'e, Database = Sqlite>>(
+    pub(crate) async fn execute_on<'e, E: Executor<'e, Database = Sqlite>>(
         executor: E,
         sql: &str,
+        params: &[CellValue],
     ) -> Result<QueryResponse, AppError> {
         let start = Instant::now();
         let trimmed = sql.trim();
-        let upper = trimmed.to_uppercase();
+        let returns_rows = analyze(trimmed, "sqlite").returns_rows;
 
-        let is_select = upper.starts_with("SELECT")
-            || upper.starts_with("WITH")
-            || upper.starts_with("EXPLAIN")
-            || upper.starts_with("PRAGMA")
-            || upper.starts_with("VALUES");
+        let mut query = sqlx::query(trimmed);
+        for param in params {
+            query = sqlite_bind_cell(query, param)?;
+        }
 
-        if is_select {
-            let rows = sqlx::query(trimmed).fetch_all(executor).await?;
+        if returns_rows {
+            let rows = query.fetch_all(executor).await?;
             let elapsed = start.elapsed().as_millis() as u64;
 
             let columns = if rows.is_empty() {
@@ -71,9 +95,10 @@ impl SqliteDriver {
                 affected_rows: None,
                 truncated: false,
                 max_rows_limit: None,
+                messages: Vec::new(),
             })
         } else {
-            let result = sqlx::query(trimmed).execute(executor).await?;
+            let result = query.execute(executor).await?;
             let elapsed = start.elapsed().as_millis() as u64;
             let affected = result.rows_affected();
 
@@ -85,9 +110,99 @@ impl SqliteDriver {
                 affected_rows: Some(affected),
                 truncated: false,
                 max_rows_limit: None,
+                messages: Vec::new(),
             })
         }
     }
+
+    /// Execute on `conn`, reporting rows changed by triggers and foreign key
+    /// actions. SQLite has no server messages; `changes()` excludes those rows,
+    /// so they are only visible through the `total_changes()` delta.
+    async fn execute_with_messages(
+        conn: &mut SqliteConnection,
+        sql: &str,
+        params: &[CellValue],
+    ) -> Result<QueryResponse, AppError> {
+        if analyze(sql.trim(), "sqlite").returns_rows {
+            return Self::execute_on(&mut *conn, sql, params).await;
+        }
+
+        let before = Self::total_changes(conn).await;
+        let mut response = Self::execute_on(&mut *conn, sql, params).await?;
+        if let (Some(before), Some(after), Some(affected)) =
+            (before, Self::total_changes(conn).await, response.affected_rows)
+        {
+            let cascaded = after.saturating_sub(before).saturating_sub(affected);
+            if cascaded > 0 {
+                response.messages.push(ServerMessage {
+                    severity: "info".to_string(),
+                    code: None,
+                    text: format!(
+                        "{} additional row(s) changed by triggers or foreign key actions",
+                        cascaded
+                    ),
+                });
+            }
+        }
+        Ok(response)
+    }
+
+    async fn total_changes(conn: &mut SqliteConnection) -> Option<u64> {
+        sqlx::query_scalar::<_, i64>("SELECT total_changes()")
+            .fetch_one(conn)
+            .await
+            .ok()
+            .map(|n| n as u64)
+    }
+
+    /// Run script statements in order on one connection.
+    async fn run_script_on(
+        conn: &mut SqliteConnection,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) {
+        for (index, statement) in statements.iter().enumerate() {
+            let result = Self::execute_with_messages(conn, statement, &[]).await;
+            if !sink(index, result) {
+                break;
+            }
+        }
+    }
+
+    /// Stream a query's rows in batches, stopping at `max_rows`.
+    async fn stream_on<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
+        sql: &str,
+        batch_size: usize,
+        max_rows: usize,
+        sink: BatchSink<'_>,
+    ) -> Result<StreamSummary, AppError> {
+        let trimmed = sql.trim();
+        let returns_rows = analyze(trimmed, "sqlite").returns_rows;
+
+        if !returns_rows {
+            let response = Self::execute_on(executor, sql, &[]).await?;
+            return Ok(StreamSummary {
+                row_count: 0,
+                execution_time_ms: response.execution_time_ms,
+                affected_rows: response.affected_rows,
+                truncated: false,
+                max_rows_limit: None,
+            });
+        }
+
+        let mut collector = BatchCollector::new(sink, batch_size, max_rows);
+        let mut rows = sqlx::query(trimmed).fetch(executor);
+        while let Some(row) = rows.try_next().await? {
+            if collector.needs_columns() {
+                collector.set_columns(sqlite_columns_to_defs(&row));
+            }
+            if !collector.push(sqlite_row_to_cells(&row))? {
+                break;
+            }
+        }
+        collector.finish()
+    }
 }
 
 fn sqlite_columns_to_defs(row: &sqlx::sqlite::SqliteRow) -> Vec<ColumnDef> {
@@ -141,7 +256,7 @@ fn sqlite_row_to_cells(row: &sqlx::sqlite::SqliteRow) -> Vec<CellValue> {
                 Err(_) => CellValue::Null,
             },
             _ => match row.try_get::<String, _>(i) {
-                Ok(v) => CellValue::Text(v),
+                Ok(v) => sqlite_text_cell(&type_name, v),
                 Err(_) => match row.try_get::<i64, _>(i) {
                     Ok(v) => CellValue::Int(v),
                     Err(_) => match row.try_get::<f64, _>(i) {
@@ -158,6 +273,48 @@ fn sqlite_row_to_cells(row: &sqlx::sqlite::SqliteRow) -> Vec<CellValue> {
     cells
 }
 
+/// Dates are stored as text; values of declared date columns are converted
+/// when they are in the standard format.
+fn sqlite_text_cell(type_name: &str, text: String) -> CellValue {
+    let converted = match type_name {
+        "DATE" => chrono::NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+            .ok()
+            .map(date_cell),
+        "DATETIME" => chrono::NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f")
+            .or_else(|_| chrono::NaiveDateTime::parse_from_str(&text, "%Y-%m-%dT%H:%M:%S%.f"))
+            .ok()
+            .map(datetime_cell),
+        _ => None,
+    };
+    converted.unwrap_or(CellValue::Text(text))
+}
+
+/// Bind a CellValue to a SQLite query using the closest native type.
+fn sqlite_bind_cell<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    cell: &CellValue,
+) -> Result<Query<'q, Sqlite, SqliteArguments<'q>>, AppError> {
+    Ok(match cell {
+        CellValue::Null => query.bind(None::<String>),
+        CellValue::Bool(v) => query.bind(*v),
+        CellValue::Int(v) => query.bind(*v),
+        CellValue::Float(v) => query.bind(*v),
+        CellValue::Text(v) | CellValue::Timestamp(v) | CellValue::Json(v) => query.bind(v.clone()),
+        CellValue::Binary(v) => query.bind(v.clone()),
+        CellValue::Decimal { .. }
+        | CellValue::Uuid(_)
+        | CellValue::Date(_)
+        | CellValue::Time(_)
+        | CellValue::DateTime { .. }
+        | CellValue::Interval { .. }
+        | CellValue::Array(_)
+        | CellValue::Enum(_) => query.bind(cell_text(cell)),
+        CellValue::LargeText { .. } | CellValue::LargeJson { .. } | CellValue::LargeBinary { .. } => {
+            return Err(unbindable_param());
+        }
+    })
+}
+
 #[async_trait]
 impl DbDriver for SqliteDriver {
     fn category(&self) -> DatabaseCategory {
@@ -169,15 +326,85 @@ impl DbDriver for SqliteDriver {
     }
 
     async fn execute_raw(&self, sql: &str) -> Result<QueryResponse, AppError> {
+        self.execute_params(sql, Vec::new()).await
+    }
+
+    async fn execute_params(
+        &self,
+        sql: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        let mut guard = self.txn_conn.lock().await;
+        if let Some(ref mut conn) = *guard {
+            Self::execute_with_messages(conn, sql, &params).await
+        } else {
+            drop(guard);
+            let mut conn = self.pool.acquire().await?;
+            Self::execute_with_messages(&mut conn, sql, &params).await
+        }
+    }
+
+    async fn execute_stream(
+        &self,
+        sql: &str,
+        batch_size: usize,
+        max_rows: usize,
+        sink: BatchSink<'_>,
+    ) -> Result<StreamSummary, AppError> {
         let mut guard = self.txn_conn.lock().await;
         if let Some(ref mut conn) = *guard {
-            Self::execute_on(&mut **conn, sql).await
+            Self::stream_on(&mut **conn, sql, batch_size, max_rows, sink).await
         } else {
             drop(guard);
-            Self::execute_on(&self.pool, sql).await
+            Self::stream_on(&self.pool, sql, batch_size, max_rows, sink).await
         }
     }
 
+    /// SQLite keeps no runtime statistics, so `analyze` is ignored.
+    async fn explain(&self, sql: &str, _analyze: bool) -> Result<QueryPlan, AppError> {
+        let response = self
+            .execute_raw(&format!("EXPLAIN QUERY PLAN {}", explain_target(sql)))
+            .await?;
+        // Columns: id, parent, notused, detail.
+        let rows: Vec<(i64, i64, String)> = response
+            .rows
+            .iter()
+            .filter_map(|row| match row.as_slice() {
+                [CellValue::Int(id), CellValue::Int(parent), _, CellValue::Text(detail)] => {
+                    Some((*id, *parent, detail.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        Ok(parse_sqlite(&rows))
+    }
+
+    /// Statements share one session so temp tables, variables and SET persist
+    /// between them. A transaction the script leaves open is rolled back before
+    /// the connection goes back to the pool.
+    async fn execute_script(
+        &self,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) -> Result<(), AppError> {
+        let mut guard = self.txn_conn.lock().await;
+        if let Some(ref mut conn) = *guard {
+            Self::run_script_on(conn, statements, sink).await;
+            return Ok(());
+        }
+        drop(guard);
+
+        let mut conn = self.pool.acquire().await?;
+        Self::run_script_on(&mut conn, statements, sink).await;
+        if statements
+            .iter()
+            .any(|s| analyze(s, "sqlite").kind == StatementKind::Transaction)
+        {
+            sqlx::query("ROLLBACK").execute(&mut *conn).await.ok();
+        }
+        Ok(())
+    }
+
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
         // SQLite has a single "main" database
         Ok(vec![ContainerInfo {
@@ -0,0 +1,538 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use tokio::time::timeout;
+use uuid::Uuid;
+
+use crate::db::read_only::is_write;
+use crate::db::statement::{analyze, split_script, tokenize, StatementKind, Token, TokenKind};
+use crate::db::traits::DbDriver;
+use crate::error::AppError;
+use crate::models::connection::GuardrailLevel;
+use crate::models::query::CellValue;
+
+/// How long a confirmation token stays valid after it is issued.
+const TOKEN_TTL: Duration = Duration::from_secs(300);
+
+/// Budget for the COUNT run to estimate how many rows a statement affects.
+const ESTIMATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest statement excerpt quoted in a summary.
+const EXCERPT_LEN: usize = 80;
+
+/// How to estimate the number of rows or keys a risky statement affects.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Estimate {
+    /// A query returning the count in its first cell.
+    Query(String),
+    /// `DbDriver::get_item_count` for a whole collection.
+    Items { container: String, item: String },
+}
+
+/// A statement that needs explicit confirmation before it runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Risk {
+    pub summary: String,
+    pub estimate: Option<Estimate>,
+}
+
+impl Risk {
+    fn new(summary: String, estimate: Option<Estimate>) -> Self {
+        Self { summary, estimate }
+    }
+}
+
+/// Risky statements in `query`, a raw query for a driver with the given
+/// `dialect_hint`.
+pub fn assess(query: &str, dialect: &str, level: GuardrailLevel) -> Vec<Risk> {
+    if level == GuardrailLevel::Off {
+        return Vec::new();
+    }
+    let strict = level == GuardrailLevel::Strict;
+    match dialect {
+        "redis" => {
+            let command = query.split_whitespace().next().unwrap_or("").to_uppercase();
+            let risk = match command.as_str() {
+                "FLUSHALL" => Some(Risk::new(
+                    "FLUSHALL deletes every key in every database".to_string(),
+                    None,
+                )),
+                "FLUSHDB" => Some(Risk::new(
+                    "FLUSHDB deletes every key in the current database".to_string(),
+                    Some(Estimate::Query("DBSIZE".to_string())),
+                )),
+                _ if strict && is_write(query, dialect) => Some(strict_risk(query)),
+                _ => None,
+            };
+            risk.into_iter().collect()
+        }
+        "mongodb" => {
+            let command: serde_json::Value = match serde_json::from_str(query) {
+                Ok(command) => command,
+                Err(_) => return Vec::new(),
+            };
+            let operation = command.get("operation").and_then(|op| op.as_str());
+            if matches!(operation, Some("deleteMany" | "delete_many")) {
+                let container = command["database"].as_str().unwrap_or("test");
+                let collection = command["collection"].as_str().unwrap_or("");
+                assess_delete_documents(container, collection, &command["filter"], level)
+                    .into_iter()
+                    .collect()
+            } else if strict && is_write(query, dialect) {
+                vec![strict_risk(query)]
+            } else {
+                Vec::new()
+            }
+        }
+        "dynamodb" | "neo4j" => {
+            if strict && is_write(query, dialect) {
+                vec![strict_risk(query)]
+            } else {
+                Vec::new()
+            }
+        }
+        _ => split_script(query, dialect)
+            .into_iter()
+            .filter_map(|range| assess_statement(&query[range], dialect, strict))
+            .collect(),
+    }
+}
+
+/// Risk of deleting the documents of `container.collection` matching `filter`:
+/// an empty or missing filter deletes the whole collection.
+pub fn assess_delete_documents(
+    container: &str,
+    collection: &str,
+    filter: &serde_json::Value,
+    level: GuardrailLevel,
+) -> Option<Risk> {
+    let unfiltered = match filter {
+        serde_json::Value::Null => true,
+        serde_json::Value::Object(fields) => fields.is_empty(),
+        _ => false,
+    };
+    if level == GuardrailLevel::Off || !unfiltered {
+        return None;
+    }
+    Some(Risk::new(
+        format!(
+            "deleteMany with an empty filter deletes every document in {}.{}",
+            container, collection
+        ),
+        Some(Estimate::Items {
+            container: container.to_string(),
+            item: collection.to_string(),
+        }),
+    ))
+}
+
+fn assess_statement(sql: &str, dialect: &str, strict: bool) -> Option<Risk> {
+    let info = analyze(sql, dialect);
+    let tokens: Vec<Token> = tokenize(sql, dialect)
+        .into_iter()
+        .filter(|t| t.kind != TokenKind::Comment)
+        .collect();
+
+    match info.kind {
+        StatementKind::Delete | StatementKind::Update => {
+            let verb = if info.kind == StatementKind::Delete { "DELETE" } else { "UPDATE" };
+            let main = main_keyword(&tokens, verb)?;
+            if has_top_level_where(&tokens, main) {
+                return strict.then(|| strict_risk(sql));
+            }
+            let skip: &[&str] = if verb == "DELETE" {
+                &["FROM", "ONLY", "LOW_PRIORITY", "QUICK", "IGNORE"]
+            } else {
+                &["ONLY", "LOW_PRIORITY", "IGNORE"]
+            };
+            let table = object_name(sql, &tokens, skip_modifiers(&tokens, main + 1, skip));
+            let summary = match table {
+                Some(table) => format!("{} without WHERE affects every row of {}", verb, table),
+                None => format!("{} without WHERE affects every row", verb),
+            };
+            Some(Risk::new(summary, table.map(count_query)))
+        }
+        StatementKind::Truncate => {
+            let start = skip_modifiers(&tokens, 1, &["TABLE", "ONLY", "IF", "EXISTS"]);
+            let table = object_name(sql, &tokens, start);
+            let summary = match table {
+                Some(table) => format!("TRUNCATE removes every row of {}", table),
+                None => "TRUNCATE removes every row".to_string(),
+            };
+            Some(Risk::new(summary, table.map(count_query)))
+        }
+        StatementKind::Drop => {
+            let table = if tokens.get(1).is_some_and(|t| t.is_keyword("TABLE")) {
+                object_name(sql, &tokens, skip_modifiers(&tokens, 2, &["IF", "EXISTS"]))
+            } else {
+                None
+            };
+            Some(Risk::new(
+                format!("DROP cannot be undone: {}", excerpt(sql)),
+                table.map(count_query),
+            ))
+        }
+        StatementKind::Alter => Some(Risk::new(
+            format!("ALTER changes the schema: {}", excerpt(sql)),
+            None,
+        )),
+        _ if strict && !info.read_only => Some(strict_risk(sql)),
+        _ => None,
+    }
+}
+
+fn strict_risk(statement: &str) -> Risk {
+    Risk::new(
+        format!("Writes on a protected connection: {}", excerpt(statement)),
+        None,
+    )
+}
+
+fn count_query(table: &str) -> Estimate {
+    Estimate::Query(format!("SELECT COUNT(*) FROM {}", table))
+}
+
+/// Index of the top-level `verb` that starts the main statement (after any CTEs).
+fn main_keyword(tokens: &[Token], verb: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, t) in tokens.iter().enumerate() {
+        if t.is_punct('(') {
+            depth += 1;
+        } else if t.is_punct(')') {
+            depth -= 1;
+        } else if depth == 0 && t.is_keyword(verb) {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn has_top_level_where(tokens: &[Token], from: usize) -> bool {
+    let mut depth = 0;
+    for t in &tokens[from..] {
+        if t.is_punct('(') {
+            depth += 1;
+        } else if t.is_punct(')') {
+            depth -= 1;
+        } else if depth == 0 && t.is_keyword("WHERE") {
+            return true;
+        }
+    }
+    false
+}
+
+/// Skip `words` and SQL Server `TOP (n)` starting at `i`.
+fn skip_modifiers(tokens: &[Token], mut i: usize, words: &[&str]) -> usize {
+    while let Some(t) = tokens.get(i) {
+        if words.iter().any(|w| t.is_keyword(w)) {
+            i += 1;
+        } else if t.is_keyword("TOP") {
+            i += 1;
+            if tokens.get(i).is_some_and(|t| t.is_punct('(')) {
+                while tokens.get(i).is_some_and(|t| !t.is_punct(')')) {
+                    i += 1;
+                }
+            }
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    i
+}
+
+/// The (possibly qualified) object name starting at token `i`, as written.
+fn object_name<'a>(sql: &'a str, tokens: &[Token], i: usize) -> Option<&'a str> {
+    fn is_name(t: &Token) -> bool {
+        matches!(t.kind, TokenKind::Word | TokenKind::QuotedIdent)
+    }
+    let first = tokens.get(i).filter(|t| is_name(t))?;
+    let mut end = first.end();
+    let mut j = i + 1;
+    while tokens.get(j).is_some_and(|t| t.is_punct('.'))
+        && tokens.get(j + 1).is_some_and(is_name)
+    {
+        end = tokens[j + 1].end();
+        j += 2;
+    }
+    Some(&sql[first.start..end])
+}
+
+/// The statement on one line, shortened for display.
+fn excerpt(statement: &str) -> String {
+    let line = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+    let line = line.trim_end_matches(';');
+    if line.chars().count() > EXCERPT_LEN {
+        let cut: String = line.chars().take(EXCERPT_LEN).collect();
+        format!("{}...", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+/// Estimated rows affected by `risks`, summed over the estimates that could be
+/// computed. Failures only cost the estimate, never the confirmation.
+async fn estimate_rows(driver: &dyn DbDriver, risks: &[Risk]) -> Option<u64> {
+    let mut total = None;
+    for estimate in risks.iter().filter_map(|r| r.estimate.as_ref()) {
+        let count = match estimate {
+            Estimate::Query(query) => timeout(ESTIMATE_TIMEOUT, driver.execute_raw(query))
+                .await
+                .ok()
+                .and_then(|r| r.ok())
+                .and_then(|response| match response.rows.first()?.first()? {
+                    CellValue::Int(n) => u64::try_from(*n).ok(),
+                    CellValue::Text(s) => s.trim().parse().ok(),
+                    _ => None,
+                }),
+            Estimate::Items { container, item } => {
+                timeout(ESTIMATE_TIMEOUT, driver.get_item_count(container, item))
+                    .await
+                    .ok()
+                    .and_then(|r| r.ok())
+                    .and_then(|n| u64::try_from(n).ok())
+            }
+        };
+        if let Some(count) = count {
+            total = Some(total.unwrap_or(0) + count);
+        }
+    }
+    total
+}
+
+struct Pending {
+    connection_id: String,
+    statement: String,
+    issued: Instant,
+}
+
+/// Per-connection guardrail levels and the confirmation tokens handed out for
+/// held-back statements. A token is single-use and only confirms the exact
+/// statement it was issued for, on the same connection.
+pub struct Guardrails {
+    levels: Mutex<HashMap<String, GuardrailLevel>>,
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+impl Guardrails {
+    pub fn new() -> Self {
+        Self {
+            levels: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_level(&self, connection_id: &str, level: GuardrailLevel) {
+        self.levels
+            .lock()
+            .unwrap()
+            .insert(connection_id.to_string(), level);
+    }
+
+    /// Forget a disconnected connection and any tokens issued for it.
+    pub fn remove(&self, connection_id: &str) {
+        self.levels.lock().unwrap().remove(connection_id);
+        self.pending
+            .lock()
+            .unwrap()
+            .retain(|_, p| p.connection_id != connection_id);
+    }
+
+    pub fn level(&self, connection_id: &str) -> GuardrailLevel {
+        self.levels
+            .lock()
+            .unwrap()
+            .get(connection_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Let `query` through unless it is risky and `token` does not confirm it.
+    pub async fn check_query(
+        &self,
+        connection_id: &str,
+        driver: &dyn DbDriver,
+        query: &str,
+        token: Option<&str>,
+    ) -> Result<(), AppError> {
+        let risks = assess(query, driver.dialect_hint(), self.level(connection_id));
+        self.require(connection_id, driver, query, risks, token).await
+    }
+
+    /// `check_query` for `DocumentDriver::delete_documents`.
+    pub async fn check_delete_documents(
+        &self,
+        connection_id: &str,
+        driver: &dyn DbDriver,
+        container: &str,
+        collection: &str,
+        filter: &serde_json::Value,
+        token: Option<&str>,
+    ) -> Result<(), AppError> {
+        let risk =
+            assess_delete_documents(container, collection, filter, self.level(connection_id));
+        let statement = format!("deleteMany {}.{} {}", container, collection, filter);
+        self.require(connection_id, driver, &statement, risk.into_iter().collect(), token)
+            .await
+    }
+
+    async fn require(
+        &self,
+        connection_id: &str,
+        driver: &dyn DbDriver,
+        statement: &str,
+        risks: Vec<Risk>,
+        token: Option<&str>,
+    ) -> Result<(), AppError> {
+        if risks.is_empty() {
+            return Ok(());
+        }
+        if let Some(token) = token {
+            if self.redeem(token, connection_id, statement) {
+                info!("Destructive statement confirmed on '{}'", connection_id);
+                return Ok(());
+            }
+            warn!("Rejected stale or mismatched confirmation token on '{}'", connection_id);
+        }
+
+        let summary = risks
+            .iter()
+            .map(|r| r.summary.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        let estimated_rows = estimate_rows(driver, &risks).await;
+        warn!("Holding back destructive statement on '{}': {}", connection_id, summary);
+        Err(AppError::ConfirmationRequired {
+            summary,
+            estimated_rows,
+            token: self.issue(connection_id, statement),
+        })
+    }
+
+    fn issue(&self, connection_id: &str, statement: &str) -> String {
+        let token = Uuid::new_v4().to_string();
+        let mut pending = self.pending.lock().unwrap();
+        pending.retain(|_, p| p.issued.elapsed() < TOKEN_TTL);
+        pending.insert(
+            token.clone(),
+            Pending {
+                connection_id: connection_id.to_string(),
+                statement: statement.to_string(),
+                issued: Instant::now(),
+            },
+        );
+        token
+    }
+
+    fn redeem(&self, token: &str, connection_id: &str, statement: &str) -> bool {
+        match self.pending.lock().unwrap().remove(token) {
+            Some(p) => {
+                p.connection_id == connection_id
+                    && p.statement == statement
+                    && p.issued.elapsed() < TOKEN_TTL
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summaries(query: &str, dialect: &str, level: GuardrailLevel) -> Vec<String> {
+        assess(query, dialect, level)
+            .into_iter()
+            .map(|r| r.summary)
+            .collect()
+    }
+
+    #[test]
+    fn test_unfiltered_delete_and_update_are_counted() {
+        let risks = assess("DELETE FROM public.orders", "postgres", GuardrailLevel::Standard);
+        assert_eq!(
+            risks,
+            vec![Risk::new(
+                "DELETE without WHERE affects every row of public.orders".to_string(),
+                Some(Estimate::Query("SELECT COUNT(*) FROM public.orders".to_string())),
+            )]
+        );
+
+        let risks = assess("UPDATE TOP (10) [dbo].[t] SET a = 1", "mssql", GuardrailLevel::Standard);
+        assert_eq!(
+            risks[0].estimate,
+            Some(Estimate::Query("SELECT COUNT(*) FROM [dbo].[t]".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_filtered_statements_pass() {
+        let sql = "DELETE FROM t WHERE id = 1; UPDATE t SET a = (SELECT 1 WHERE 1 = 1) WHERE id = 2";
+        assert!(assess(sql, "postgres", GuardrailLevel::Standard).is_empty());
+        // A WHERE inside a subquery does not filter the update itself.
+        let sql = "UPDATE t SET a = (SELECT b FROM u WHERE u.id = 1)";
+        assert_eq!(assess(sql, "postgres", GuardrailLevel::Standard).len(), 1);
+        // Nor does a WHERE inside a CTE.
+        let sql = "WITH x AS (SELECT id FROM u WHERE id > 1) DELETE FROM t";
+        assert_eq!(assess(sql, "postgres", GuardrailLevel::Standard).len(), 1);
+    }
+
+    #[test]
+    fn test_schema_changes_and_truncate() {
+        let risks = assess(
+            "DROP TABLE IF EXISTS `app`.`users`; TRUNCATE TABLE logs; ALTER TABLE t ADD c INT",
+            "mysql",
+            GuardrailLevel::Standard,
+        );
+        assert_eq!(risks.len(), 3);
+        assert_eq!(
+            risks[0].estimate,
+            Some(Estimate::Query("SELECT COUNT(*) FROM `app`.`users`".to_string()))
+        );
+        assert_eq!(risks[1].summary, "TRUNCATE removes every row of logs");
+        assert_eq!(risks[2].summary, "ALTER changes the schema: ALTER TABLE t ADD c INT");
+        assert_eq!(assess("DROP VIEW v", "postgres", GuardrailLevel::Standard)[0].estimate, None);
+    }
+
+    #[test]
+    fn test_levels() {
+        let sql = "INSERT INTO t VALUES (1)";
+        assert!(assess(sql, "postgres", GuardrailLevel::Standard).is_empty());
+        assert_eq!(assess(sql, "postgres", GuardrailLevel::Strict).len(), 1);
+        assert!(assess("SELECT * FROM t", "postgres", GuardrailLevel::Strict).is_empty());
+        assert!(assess("DROP TABLE t", "postgres", GuardrailLevel::Off).is_empty());
+    }
+
+    #[test]
+    fn test_redis_and_mongodb() {
+        assert_eq!(
+            assess("flushdb", "redis", GuardrailLevel::Standard)[0].estimate,
+            Some(Estimate::Query("DBSIZE".to_string()))
+        );
+        assert_eq!(summaries("FLUSHALL ASYNC", "redis", GuardrailLevel::Standard).len(), 1);
+        assert!(assess("DEL a", "redis", GuardrailLevel::Standard).is_empty());
+        assert_eq!(assess("DEL a", "redis", GuardrailLevel::Strict).len(), 1);
+
+        let query = r#"{"database":"shop","collection":"carts","operation":"deleteMany","filter":{}}"#;
+        assert_eq!(
+            summaries(query, "mongodb", GuardrailLevel::Standard),
+            vec!["deleteMany with an empty filter deletes every document in shop.carts"]
+        );
+        let filtered = serde_json::json!({ "user": 1 });
+        assert!(assess_delete_documents("shop", "carts", &filtered, GuardrailLevel::Strict).is_none());
+    }
+
+    #[test]
+    fn test_tokens_are_single_use_and_bound_to_the_statement() {
+        let guardrails = Guardrails::new();
+        let token = guardrails.issue("c1", "DELETE FROM t");
+        assert!(!guardrails.redeem(&token, "c1", "DELETE FROM u"));
+        let token = guardrails.issue("c1", "DELETE FROM t");
+        assert!(!guardrails.redeem(&token, "c2", "DELETE FROM t"));
+        let token = guardrails.issue("c1", "DELETE FROM t");
+        assert!(guardrails.redeem(&token, "c1", "DELETE FROM t"));
+        assert!(!guardrails.redeem(&token, "c1", "DELETE FROM t"));
+    }
+}
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use crate::error::AppError;
 
+use super::read_only::ReadOnly;
 use super::traits::{DbDriver, DocumentDriver, GraphDriver, KeyValueDriver, SqlDriver};
 
 /// Typed wrapper that knows which trait category a driver supports.
@@ -23,6 +24,16 @@ impl DriverHandle {
         }
     }
 
+    /// Wrap the driver so every write is rejected (see `db::read_only`).
+    pub fn into_read_only(self) -> Self {
+        match self {
+            DriverHandle::Sql(d) => DriverHandle::Sql(Arc::new(ReadOnly::new(d))),
+            DriverHandle::Document(d) => DriverHandle::Document(Arc::new(ReadOnly::new(d))),
+            DriverHandle::KeyValue(d) => DriverHandle::KeyValue(Arc::new(ReadOnly::new(d))),
+            DriverHandle::Graph(d) => DriverHandle::Graph(Arc::new(ReadOnly::new(d))),
+        }
+    }
+
     pub fn as_sql(&self) -> Result<&dyn SqlDriver, AppError> {
         match self {
             DriverHandle::Sql(d) => Ok(d.as_ref()),
@@ -0,0 +1,309 @@
+use std::path::Path;
+use std::time::Duration;
+
+use log::warn;
+use sqlx::sqlite::{Sqlite, SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::{Executor, QueryBuilder, Row};
+
+use crate::error::AppError;
+use crate::models::history::{HistoryEntry, HistoryFilter};
+
+const DEFAULT_SEARCH_LIMIT: i64 = 200;
+
+/// `history_fts` is an external-content index over `history.sql`, kept in
+/// sync by triggers. Entries are never edited apart from `pinned`, so there
+/// is no update trigger.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id TEXT NOT NULL,
+    sql TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    row_count INTEGER,
+    error_code TEXT,
+    error_message TEXT,
+    executed_at INTEGER NOT NULL,
+    pinned INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS history_executed_at ON history (executed_at);
+CREATE INDEX IF NOT EXISTS history_connection ON history (connection_id, executed_at);
+CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5 (
+    sql, content = 'history', content_rowid = 'id'
+);
+CREATE TRIGGER IF NOT EXISTS history_ai AFTER INSERT ON history BEGIN
+    INSERT INTO history_fts (rowid, sql) VALUES (new.id, new.sql);
+END;
+CREATE TRIGGER IF NOT EXISTS history_ad AFTER DELETE ON history BEGIN
+    INSERT INTO history_fts (history_fts, rowid, sql) VALUES ('delete', old.id, old.sql);
+END;
+";
+
+const COLUMNS: &str = "h.id, h.connection_id, h.sql, h.duration_ms, h.row_count, \
+     h.error_code, h.error_message, h.executed_at, h.pinned";
+
+/// Query history kept in a SQLite database in the app data dir, so it
+/// outlives the frontend store and can be searched and backed up.
+pub struct QueryHistory {
+    pool: SqlitePool,
+}
+
+impl QueryHistory {
+    pub async fn open(path: &Path) -> Result<Self, AppError> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(2)
+            .connect_with(options)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to open query history: {}", e)))?;
+        pool.execute(SCHEMA)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to create query history: {}", e)))?;
+        Ok(Self { pool })
+    }
+
+    /// Record a statement. `outcome` carries the row count of a successful
+    /// run or the error it failed with. Failures to write are logged rather
+    /// than surfaced, so history never breaks a query.
+    pub async fn record(
+        &self,
+        connection_id: &str,
+        sql: &str,
+        duration: Duration,
+        outcome: Result<Option<u64>, &AppError>,
+    ) {
+        let (row_count, error_code, error_message) = match outcome {
+            Ok(rows) => (rows.map(|n| n as i64), None, None),
+            Err(e) => (None, Some(e.error_code()), Some(e.to_string())),
+        };
+        let result = sqlx::query(
+            "INSERT INTO history (connection_id, sql, duration_ms, row_count, error_code, \
+             error_message, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(connection_id)
+        .bind(sql.trim())
+        .bind(duration.as_millis() as i64)
+        .bind(row_count)
+        .bind(error_code)
+        .bind(error_message)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .execute(&self.pool)
+        .await;
+        if let Err(e) = result {
+            warn!("Failed to record query history for '{}': {}", connection_id, e);
+        }
+    }
+
+    pub async fn search(&self, filter: &HistoryFilter) -> Result<Vec<HistoryEntry>, AppError> {
+        let mut query = QueryBuilder::<Sqlite>::new(format!("SELECT {} FROM history h", COLUMNS));
+        let fts = filter.text.as_deref().and_then(fts_query);
+        if fts.is_some() {
+            query.push(" JOIN history_fts ON history_fts.rowid = h.id");
+        }
+        query.push(" WHERE 1 = 1");
+        if let Some(fts) = fts {
+            query.push(" AND history_fts MATCH ").push_bind(fts);
+        }
+        if let Some(connection_id) = &filter.connection_id {
+            query.push(" AND h.connection_id = ").push_bind(connection_id.clone());
+        }
+        if filter.pinned_only {
+            query.push(" AND h.pinned = 1");
+        }
+        if filter.errors_only {
+            query.push(" AND h.error_code IS NOT NULL");
+        }
+        if let Some(since) = filter.since {
+            query.push(" AND h.executed_at >= ").push_bind(since);
+        }
+        if let Some(until) = filter.until {
+            query.push(" AND h.executed_at < ").push_bind(until);
+        }
+        query
+            .push(" ORDER BY h.pinned DESC, h.executed_at DESC, h.id DESC LIMIT ")
+            .push_bind(filter.limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+            .push(" OFFSET ")
+            .push_bind(filter.offset.unwrap_or(0));
+
+        let rows = query
+            .build()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to search query history: {}", e)))?;
+        Ok(rows.iter().map(entry_from_row).collect())
+    }
+
+    pub async fn set_pinned(&self, id: i64, pinned: bool) -> Result<(), AppError> {
+        let result = sqlx::query("UPDATE history SET pinned = ? WHERE id = ?")
+            .bind(pinned)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to update query history: {}", e)))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::InvalidConfig(format!("History entry {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Delete entries executed before `before` (all of them when `None`),
+    /// optionally for one connection only. Pinned entries are kept unless
+    /// `include_pinned` is set. Returns the number of entries removed.
+    pub async fn purge(
+        &self,
+        before: Option<i64>,
+        connection_id: Option<&str>,
+        include_pinned: bool,
+    ) -> Result<u64, AppError> {
+        let mut query = QueryBuilder::<Sqlite>::new("DELETE FROM history WHERE 1 = 1");
+        if let Some(before) = before {
+            query.push(" AND executed_at < ").push_bind(before);
+        }
+        if let Some(connection_id) = connection_id {
+            query.push(" AND connection_id = ").push_bind(connection_id.to_string());
+        }
+        if !include_pinned {
+            query.push(" AND pinned = 0");
+        }
+        let result = query
+            .build()
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to purge query history: {}", e)))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Every entry, oldest first, for inclusion in a config backup.
+    pub async fn export(&self) -> Result<Vec<HistoryEntry>, AppError> {
+        let rows = sqlx::query(&format!("SELECT {} FROM history h ORDER BY h.id", COLUMNS))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to read query history: {}", e)))?;
+        Ok(rows.iter().map(entry_from_row).collect())
+    }
+
+    /// Replace the whole history with `entries`, as taken from a backup.
+    pub async fn import(&self, entries: &[HistoryEntry]) -> Result<(), AppError> {
+        let map_err = |e: sqlx::Error| AppError::Database(format!("Failed to restore query history: {}", e));
+        let mut tx = self.pool.begin().await.map_err(map_err)?;
+        sqlx::query("DELETE FROM history")
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+        for entry in entries {
+            sqlx::query(
+                "INSERT INTO history (id, connection_id, sql, duration_ms, row_count, error_code, \
+                 error_message, executed_at, pinned) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(entry.id)
+            .bind(&entry.connection_id)
+            .bind(&entry.sql)
+            .bind(entry.duration_ms as i64)
+            .bind(entry.row_count.map(|n| n as i64))
+            .bind(&entry.error_code)
+            .bind(&entry.error_message)
+            .bind(entry.executed_at)
+            .bind(entry.pinned)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+        }
+        tx.commit().await.map_err(map_err)
+    }
+}
+
+fn entry_from_row(row: &SqliteRow) -> HistoryEntry {
+    HistoryEntry {
+        id: row.get("id"),
+        connection_id: row.get("connection_id"),
+        sql: row.get("sql"),
+        duration_ms: row.get::<i64, _>("duration_ms").max(0) as u64,
+        row_count: row.get::<Option<i64>, _>("row_count").map(|n| n.max(0) as u64),
+        error_code: row.get("error_code"),
+        error_message: row.get("error_message"),
+        executed_at: row.get("executed_at"),
+        pinned: row.get("pinned"),
+    }
+}
+
+/// Turn free text into an FTS5 query: every word becomes a quoted prefix
+/// term, so operators and punctuation in SQL are matched literally.
+/// Returns `None` when there is nothing to search for.
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query_quotes_prefix_terms() {
+        assert_eq!(fts_query("select users").as_deref(), Some("\"select\"* \"users\"*"));
+        assert_eq!(fts_query("a\"b OR").as_deref(), Some("\"a\"\"b\"* \"OR\"*"));
+    }
+
+    #[test]
+    fn test_fts_query_empty() {
+        assert_eq!(fts_query("   "), None);
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_by_text_and_pinned() {
+        let dir = std::env::temp_dir().join(format!("queryark-history-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let history = QueryHistory::open(&dir.join("history.db")).await.unwrap();
+
+        let ms = Duration::from_millis(5);
+        history.record("c1", "SELECT * FROM users", ms, Ok(Some(3))).await;
+        history.record("c1", "SELECT * FROM orders", ms, Ok(Some(7))).await;
+        let err = AppError::Database("no such table".to_string());
+        history.record("c2", "DELETE FROM user_roles", ms, Err(&err)).await;
+
+        let search = |text: Option<&str>, pinned_only: bool| HistoryFilter {
+            text: text.map(str::to_string),
+            pinned_only,
+            ..Default::default()
+        };
+        let sql = |entries: Vec<HistoryEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.sql).collect()
+        };
+
+        assert_eq!(history.search(&search(None, false)).await.unwrap().len(), 3);
+        // Each word matches as a prefix, so "user" finds both tables.
+        assert_eq!(
+            sql(history.search(&search(Some("user"), false)).await.unwrap()),
+            ["DELETE FROM user_roles", "SELECT * FROM users"]
+        );
+
+        let orders = history.search(&search(Some("orders"), false)).await.unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].row_count, Some(7));
+        history.set_pinned(orders[0].id, true).await.unwrap();
+
+        assert_eq!(
+            sql(history.search(&search(None, true)).await.unwrap()),
+            ["SELECT * FROM orders"]
+        );
+        assert!(history.search(&search(Some("user"), true)).await.unwrap().is_empty());
+        // Pinned entries sort first.
+        assert_eq!(
+            sql(history.search(&search(Some("select"), false)).await.unwrap()),
+            ["SELECT * FROM orders", "SELECT * FROM users"]
+        );
+
+        history.pool.close().await;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
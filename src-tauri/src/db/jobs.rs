@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use log::info;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::job::{JobInfo, JobStatus};
+use crate::models::query::QueryResponse;
+
+struct Job {
+    info: JobInfo,
+    started: Instant,
+    /// Set once the job finishes; taken by `take_result`.
+    outcome: Option<Result<QueryResponse, AppError>>,
+}
+
+impl Job {
+    /// Snapshot with the elapsed time of a running job brought up to date.
+    fn snapshot(&mut self) -> JobInfo {
+        if self.info.status == JobStatus::Running {
+            self.info.elapsed_ms = self.started.elapsed().as_millis() as u64;
+        }
+        self.info.clone()
+    }
+}
+
+/// Registry of queries running detached from the command that started them.
+/// A finished job keeps its result in memory until it is fetched or discarded.
+pub struct JobManager {
+    jobs: Mutex<HashMap<String, Job>>,
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a new running job and return its snapshot.
+    pub fn start(&self, connection_id: &str, sql: &str) -> JobInfo {
+        let info = JobInfo {
+            id: Uuid::new_v4().to_string(),
+            connection_id: connection_id.to_string(),
+            sql: sql.to_string(),
+            status: JobStatus::Running,
+            submitted_at: chrono::Utc::now().timestamp_millis(),
+            elapsed_ms: 0,
+            row_count: None,
+            error: None,
+        };
+        let mut jobs = self.jobs.lock().unwrap();
+        info!("Starting job '{}' on '{}' (total: {})", info.id, connection_id, jobs.len() + 1);
+        jobs.insert(
+            info.id.clone(),
+            Job {
+                info: info.clone(),
+                started: Instant::now(),
+                outcome: None,
+            },
+        );
+        info
+    }
+
+    /// Current snapshot of a job.
+    pub fn get(&self, job_id: &str) -> Option<JobInfo> {
+        self.jobs.lock().unwrap().get_mut(job_id).map(Job::snapshot)
+    }
+
+    /// All jobs, oldest first.
+    pub fn list(&self) -> Vec<JobInfo> {
+        let mut infos: Vec<JobInfo> = self
+            .jobs
+            .lock()
+            .unwrap()
+            .values_mut()
+            .map(Job::snapshot)
+            .collect();
+        infos.sort_by_key(|info| info.submitted_at);
+        infos
+    }
+
+    /// Record the outcome of a job and return its final snapshot.
+    pub fn finish(&self, job_id: &str, outcome: Result<QueryResponse, AppError>) -> Option<JobInfo> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = jobs.get_mut(job_id)?;
+        job.info.elapsed_ms = job.started.elapsed().as_millis() as u64;
+        match &outcome {
+            Ok(response) => {
+                job.info.status = JobStatus::Completed;
+                job.info.row_count = Some(response.row_count);
+            }
+            Err(AppError::QueryCancelled) => {
+                job.info.status = JobStatus::Cancelled;
+                job.info.error = Some(AppError::QueryCancelled.to_string());
+            }
+            Err(e) => {
+                job.info.status = JobStatus::Failed;
+                job.info.error = Some(e.to_string());
+            }
+        }
+        job.outcome = Some(outcome);
+        Some(job.info.clone())
+    }
+
+    /// Hand over a finished job's result and forget the job.
+    pub fn take_result(&self, job_id: &str) -> Result<QueryResponse, AppError> {
+        let mut jobs = self.jobs.lock().unwrap();
+        match jobs.get(job_id) {
+            None => return Err(job_not_found(job_id)),
+            Some(job) if job.outcome.is_none() => {
+                return Err(AppError::InvalidConfig(format!(
+                    "Job '{}' is still running",
+                    job_id
+                )));
+            }
+            Some(_) => {}
+        }
+        jobs.remove(job_id)
+            .and_then(|job| job.outcome)
+            .unwrap_or_else(|| Err(job_not_found(job_id)))
+    }
+
+    /// Whether the job exists and has not finished yet.
+    pub fn is_running(&self, job_id: &str) -> bool {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(job_id)
+            .is_some_and(|job| job.outcome.is_none())
+    }
+
+    /// Forget a job and any result it holds.
+    pub fn remove(&self, job_id: &str) -> Result<(), AppError> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .remove(job_id)
+            .map(|_| ())
+            .ok_or_else(|| job_not_found(job_id))
+    }
+}
+
+fn job_not_found(job_id: &str) -> AppError {
+    AppError::InvalidConfig(format!("Job '{}' not found", job_id))
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use crate::db::params::ParamBuilder;
+use crate::db::traits::SqlDriver;
+use crate::error::AppError;
+use crate::models::query::{CellValue, ColumnDef, QueryResponse};
+use crate::models::schema::{ColumnInfo, KeyColumn};
+
+/// Primary key columns in ordinal order; empty when the table has none.
+pub fn primary_key(columns: &[ColumnInfo]) -> Vec<KeyColumn> {
+    let mut key: Vec<&ColumnInfo> = columns.iter().filter(|c| c.is_primary_key).collect();
+    key.sort_by_key(|c| c.ordinal_position);
+    key.into_iter()
+        .map(|c| KeyColumn {
+            name: c.name.clone(),
+            data_type: c.data_type.clone(),
+        })
+        .collect()
+}
+
+/// Whether the dialect can express the keyset condition. CQL has no `OR` and
+/// only pages partitions by token, so Cassandra keeps using OFFSET.
+pub fn supports_keyset(dialect: &str) -> bool {
+    !matches!(dialect, "cassandra" | "dynamodb" | "mongodb" | "neo4j" | "redis")
+}
+
+/// Quote an identifier for the dialect.
+pub fn quote_ident(dialect: &str, name: &str) -> String {
+    match dialect {
+        "mysql" | "clickhouse" | "bigquery" => format!("`{}`", name.replace('`', "``")),
+        "mssql" => format!("[{}]", name.replace(']', "]]")),
+        _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+/// Build `SELECT columns` for one page of `schema.table` ordered by `key`.
+/// With `after`, the page starts after that key (`WHERE (k1, k2) > (v1, v2)`,
+/// expanded so every dialect accepts it); `offset` is only used without it.
+/// `columns` is the select list (`*`, or the driver's `browse_columns`) and
+/// `filter` an existing ` WHERE ...` clause, or empty.
+#[allow(clippy::too_many_arguments)]
+pub fn select_page(
+    dialect: &str,
+    columns: &str,
+    schema: &str,
+    table: &str,
+    filter: &str,
+    key: &[KeyColumn],
+    after: Option<&[CellValue]>,
+    limit: i64,
+    offset: i64,
+    params: &mut ParamBuilder,
+) -> String {
+    let mut sql = format!(
+        "SELECT {} FROM {}.{}{}",
+        columns,
+        quote_ident(dialect, schema),
+        quote_ident(dialect, table),
+        filter
+    );
+
+    if let Some(values) = after {
+        let mut branches = Vec::with_capacity(key.len());
+        for (i, column) in key.iter().enumerate() {
+            let mut terms = Vec::with_capacity(i + 1);
+            for (prev, value) in key[..i].iter().zip(values) {
+                let ph = params.push_typed(value.clone(), Some(&prev.data_type));
+                terms.push(format!("{} = {}", quote_ident(dialect, &prev.name), ph));
+            }
+            let ph = params.push_typed(values[i].clone(), Some(&column.data_type));
+            terms.push(format!("{} > {}", quote_ident(dialect, &column.name), ph));
+            branches.push(format!("({})", terms.join(" AND ")));
+        }
+        let condition = branches.join(" OR ");
+        if filter.is_empty() {
+            sql.push_str(&format!(" WHERE ({})", condition));
+        } else {
+            sql.push_str(&format!(" AND ({})", condition));
+        }
+    }
+
+    let order: Vec<String> = key.iter().map(|c| quote_ident(dialect, &c.name)).collect();
+    if !order.is_empty() {
+        sql.push_str(&format!(" ORDER BY {}", order.join(", ")));
+    }
+
+    let offset = if after.is_some() { 0 } else { offset };
+    if dialect == "mssql" {
+        sql.push_str(&format!(" OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", offset, limit));
+    } else if offset > 0 {
+        sql.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
+    } else {
+        sql.push_str(&format!(" LIMIT {}", limit));
+    }
+    sql
+}
+
+/// Key values of `row`, looked up by column name. `None` if a key column is
+/// missing or holds a value that cannot be compared (NULL, truncated cells).
+pub fn row_key(columns: &[ColumnDef], row: &[CellValue], key: &[KeyColumn]) -> Option<Vec<CellValue>> {
+    let positions: HashMap<&str, usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (c.name.as_str(), i))
+        .collect();
+    key.iter()
+        .map(|k| comparable(row.get(*positions.get(k.name.as_str())?)?))
+        .collect()
+}
+
+/// Like `row_key`, for a row sent by the frontend as column name -> value.
+pub fn named_key(row: &HashMap<String, CellValue>, key: &[KeyColumn]) -> Option<Vec<CellValue>> {
+    key.iter().map(|k| comparable(row.get(&k.name)?)).collect()
+}
+
+fn comparable(value: &CellValue) -> Option<CellValue> {
+    match value {
+        CellValue::Null
+        | CellValue::LargeText { .. }
+        | CellValue::LargeJson { .. }
+        | CellValue::LargeBinary { .. } => None,
+        _ => Some(value.clone()),
+    }
+}
+
+/// Reads a whole table page by page, seeking on the primary key when there is
+/// one so each page is an index range scan and concurrent writes cannot shift
+/// rows between pages. Tables without a usable key fall back to OFFSET.
+pub struct TableScan<'a> {
+    driver: &'a dyn SqlDriver,
+    schema: &'a str,
+    table: &'a str,
+    columns: String,
+    key: Vec<KeyColumn>,
+    page_size: i64,
+    offset: i64,
+    after: Option<Vec<CellValue>>,
+    done: bool,
+}
+
+impl<'a> TableScan<'a> {
+    pub async fn new(
+        driver: &'a dyn SqlDriver,
+        schema: &'a str,
+        table: &'a str,
+        page_size: i64,
+    ) -> Result<Self, AppError> {
+        let key = if supports_keyset(driver.dialect_hint()) {
+            primary_key(&driver.get_columns(schema, table).await?)
+        } else {
+            Vec::new()
+        };
+        // The select list is only needed for keyset pages; OFFSET pages go
+        // through the driver's own `get_table_data`.
+        let columns = if key.is_empty() {
+            String::new()
+        } else {
+            driver.browse_columns(schema, table).await?
+        };
+        Ok(Self {
+            driver,
+            schema,
+            table,
+            columns,
+            key,
+            page_size,
+            offset: 0,
+            after: None,
+            done: false,
+        })
+    }
+
+    /// The next non-empty page, or `None` once the table is exhausted.
+    pub async fn next_page(&mut self) -> Result<Option<QueryResponse>, AppError> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let response = if self.key.is_empty() {
+            self.driver
+                .get_table_data(self.schema, self.table, self.page_size, self.offset)
+                .await?
+        } else {
+            self.driver
+                .get_table_data_after(
+                    self.schema,
+                    self.table,
+                    &self.columns,
+                    &self.key,
+                    self.after.as_deref(),
+                    self.page_size,
+                )
+                .await?
+        };
+
+        if (response.rows.len() as i64) < self.page_size {
+            self.done = true;
+        }
+        let last = match response.rows.last() {
+            Some(row) => row,
+            None => {
+                self.done = true;
+                return Ok(None);
+            }
+        };
+
+        self.offset += response.rows.len() as i64;
+        if !self.key.is_empty() && !self.done {
+            let key = row_key(&response.columns, last, &self.key).ok_or_else(|| {
+                AppError::Database(format!(
+                    "Cannot continue paging {}.{}: key value missing from result",
+                    self.schema, self.table
+                ))
+            })?;
+            self.after = Some(key);
+        }
+        Ok(Some(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(names: &[&str]) -> Vec<KeyColumn> {
+        names
+            .iter()
+            .map(|n| KeyColumn {
+                name: n.to_string(),
+                data_type: "integer".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_first_page_orders_by_key() {
+        let mut params = ParamBuilder::new("mysql");
+        let sql = select_page("mysql", "*", "app", "users", "", &key(&["id"]), None, 100, 0, &mut params);
+        assert_eq!(sql, "SELECT * FROM `app`.`users` ORDER BY `id` LIMIT 100");
+        assert!(params.into_params().is_empty());
+    }
+
+    #[test]
+    fn test_composite_key_seek_expands_row_comparison() {
+        let mut params = ParamBuilder::new("postgres");
+        let after = [CellValue::Int(3), CellValue::Int(7)];
+        let sql = select_page("postgres", "*", "public", "t", "", &key(&["a", "b"]), Some(&after), 50, 0, &mut params);
+        assert_eq!(
+            sql,
+            "SELECT * FROM \"public\".\"t\" WHERE ((\"a\" > CAST($1 AS integer)) OR \
+             (\"a\" = CAST($2 AS integer) AND \"b\" > CAST($3 AS integer))) ORDER BY \"a\", \"b\" LIMIT 50"
+        );
+        assert_eq!(params.into_params().len(), 3);
+    }
+
+    #[test]
+    fn test_seek_is_combined_with_filter() {
+        let mut params = ParamBuilder::new("sqlite");
+        let after = [CellValue::Int(10)];
+        let sql = select_page("sqlite", "*", "main", "t", " WHERE \"x\" IS NULL", &key(&["id"]), Some(&after), 5, 40, &mut params);
+        assert_eq!(
+            sql,
+            "SELECT * FROM \"main\".\"t\" WHERE \"x\" IS NULL AND ((\"id\" > ?)) ORDER BY \"id\" LIMIT 5"
+        );
+    }
+
+    #[test]
+    fn test_mssql_uses_offset_fetch() {
+        let mut params = ParamBuilder::new("mssql");
+        let sql = select_page("mssql", "[id], [v].ToString() AS [v]", "dbo", "t", "", &key(&["id"]), None, 20, 40, &mut params);
+        assert_eq!(
+            sql,
+            "SELECT [id], [v].ToString() AS [v] FROM [dbo].[t] ORDER BY [id] OFFSET 40 ROWS FETCH NEXT 20 ROWS ONLY"
+        );
+    }
+
+    #[test]
+    fn test_row_key_rejects_null_and_missing() {
+        let columns = vec![
+            ColumnDef { name: "id".into(), data_type: "int".into() },
+            ColumnDef { name: "name".into(), data_type: "text".into() },
+        ];
+        let k = key(&["id"]);
+        assert_eq!(
+            row_key(&columns, &[CellValue::Int(1), CellValue::Null], &k).map(|v| v.len()),
+            Some(1)
+        );
+        assert!(row_key(&columns, &[CellValue::Null, CellValue::Null], &k).is_none());
+        assert!(row_key(&columns, &[CellValue::Int(1)], &key(&["other"])).is_none());
+    }
+}
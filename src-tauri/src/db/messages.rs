@@ -0,0 +1,150 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::models::query::ServerMessage;
+
+/// sqlx reports Postgres `NOTICE`/`WARNING`/`INFO` responses only as log
+/// events on this target; the message text is the record body.
+const PG_NOTICE_TARGET: &str = "sqlx::postgres::notice";
+
+/// tiberius logs INFO tokens (PRINT output, `RAISERROR` with severity <= 10)
+/// from its token stream instead of returning them.
+const TDS_TOKEN_TARGET: &str = "tiberius::tds::stream::token";
+
+/// Other records tiberius logs at INFO on the same target: environment
+/// changes (`USE`, transactions, packet size), login and feature acks.
+const TDS_STATUS_PREFIXES: &[&str] = &[
+    "Database change from ",
+    "Packet size change from ",
+    "SQL collation change",
+    "Begin transaction",
+    "Commit transaction",
+    "Rollback transaction",
+    "Defect transaction",
+    "Server requested routing to ",
+    "Fallback mirror server: ",
+    "Ignored env change: ",
+    "FeatureExtAck with ",
+    "Microsoft SQL Server version ",
+];
+
+tokio::task_local! {
+    /// Messages raised by the statement running on the current task.
+    static MESSAGES: Arc<Mutex<Vec<ServerMessage>>>;
+}
+
+/// Run `fut` and collect the server messages the drivers log while it runs.
+/// Only the current task is observed, so concurrent queries never see each
+/// other's messages.
+pub async fn capture<F: Future>(fut: F) -> (F::Output, Vec<ServerMessage>) {
+    let sink = Arc::new(Mutex::new(Vec::new()));
+    let output = MESSAGES.scope(sink.clone(), fut).await;
+    let messages = std::mem::take(&mut *sink.lock().unwrap());
+    (output, messages)
+}
+
+/// Route driver notice logs into `capture`. Must be called once at startup,
+/// before any connection is opened. Every other record goes to `next`, the
+/// application's logger with its level (e.g. from tauri-plugin-log's
+/// `Builder::split`); without one they are discarded.
+///
+/// Neither driver passes the notice's SQLSTATE or message number to its log
+/// record, so captured messages carry no `code`.
+pub fn install(next: Option<(Box<dyn Log>, LevelFilter)>) {
+    let (next, next_level) = match next {
+        Some((logger, level)) => (Some(logger), level),
+        None => (None, LevelFilter::Off),
+    };
+    let logger: &'static MessageLogger = Box::leak(Box::new(MessageLogger { next, next_level }));
+    // PostgreSQL INFO and LOG notices are logged at TRACE.
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(LevelFilter::Trace);
+    }
+}
+
+struct MessageLogger {
+    next: Option<Box<dyn Log>>,
+    next_level: LevelFilter,
+}
+
+/// Severity of a driver record that is a server message, or `None` for any
+/// other record.
+fn severity(target: &str, level: Level, text: &str) -> Option<&'static str> {
+    match (target, level) {
+        (PG_NOTICE_TARGET, Level::Error) => Some("error"),
+        (PG_NOTICE_TARGET, Level::Warn) => Some("warning"),
+        (PG_NOTICE_TARGET, Level::Info) => Some("notice"),
+        (PG_NOTICE_TARGET, _) => Some("debug"),
+        (TDS_TOKEN_TARGET, Level::Info)
+            if !TDS_STATUS_PREFIXES.iter().any(|p| text.starts_with(p)) =>
+        {
+            Some("info")
+        }
+        _ => None,
+    }
+}
+
+/// Whether a record may be a server message for a running `capture`.
+fn capturing(target: &str) -> bool {
+    matches!(target, PG_NOTICE_TARGET | TDS_TOKEN_TARGET) && MESSAGES.try_with(|_| ()).is_ok()
+}
+
+impl MessageLogger {
+    fn forwards(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.next_level
+            && self.next.as_ref().is_some_and(|next| next.enabled(metadata))
+    }
+}
+
+impl Log for MessageLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        capturing(metadata.target()) || self.forwards(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if capturing(record.target()) {
+            let text = record.args().to_string();
+            if let Some(severity) = severity(record.target(), record.level(), &text) {
+                MESSAGES
+                    .try_with(|sink| {
+                        sink.lock().unwrap().push(ServerMessage {
+                            severity: severity.to_string(),
+                            code: None,
+                            text,
+                        });
+                    })
+                    .ok();
+                return;
+            }
+        }
+        if self.forwards(record.metadata()) {
+            if let Some(next) = &self.next {
+                next.log(record);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(next) = &self.next {
+            next.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_severity_skips_tds_status_records() {
+        assert_eq!(severity(TDS_TOKEN_TARGET, Level::Info, "Hello from PRINT"), Some("info"));
+        assert_eq!(severity(TDS_TOKEN_TARGET, Level::Info, "Database change from 'master' to 'app'"), None);
+        assert_eq!(severity(TDS_TOKEN_TARGET, Level::Info, "Begin transaction"), None);
+        assert_eq!(severity(TDS_TOKEN_TARGET, Level::Info, "Microsoft SQL Server version 16.0.1000"), None);
+        assert_eq!(severity(TDS_TOKEN_TARGET, Level::Trace, "Done"), None);
+        assert_eq!(severity(PG_NOTICE_TARGET, Level::Warn, "careful"), Some("warning"));
+        assert_eq!(severity("sqlx::query", Level::Info, "SELECT 1"), None);
+    }
+}
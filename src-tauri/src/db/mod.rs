@@ -1,9 +1,28 @@
+pub mod arrow_convert;
+pub mod audit;
+pub mod benchmark;
 pub mod cancel;
+pub mod clickhouse_types;
 pub mod drivers;
 pub mod escape;
+pub mod guardrails;
 pub mod handle;
+pub mod history;
+pub mod jobs;
 pub mod keychain;
+pub mod keyset;
+pub mod messages;
+pub mod mssql_types;
+pub mod multi;
+pub mod params;
+pub mod pg_types;
+pub mod plan;
 pub mod pool;
+pub mod read_only;
+pub mod result_sets;
+pub mod statement;
+pub mod stream;
 pub mod traits;
 pub mod tunnel;
 pub mod types;
+pub mod variables;
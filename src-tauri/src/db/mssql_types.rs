@@ -0,0 +1,231 @@
+use tiberius::time::{Date, DateTime2, Time};
+use tiberius::{ColumnData, ColumnType, Row};
+
+use crate::models::query::CellValue;
+
+/// Days from 0001-01-01, the epoch of DATE and DATETIME2, to the Unix epoch.
+const DAYS_FROM_YEAR_ONE: i64 = 719_162;
+/// Days from 1900-01-01, the epoch of DATETIME and SMALLDATETIME, to the Unix epoch.
+const DAYS_FROM_1900: i64 = 25_567;
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+pub fn row_to_cells(row: &Row) -> Vec<CellValue> {
+    row.cells()
+        .map(|(column, data)| column_data_to_cell(column.column_type(), data))
+        .collect()
+}
+
+/// Convert one value as tiberius decoded it. `column_type` tells MONEY apart
+/// from FLOAT, since tiberius hands both over as `f64`.
+pub fn column_data_to_cell(column_type: ColumnType, data: &ColumnData<'_>) -> CellValue {
+    let cell = match data {
+        ColumnData::U8(v) => v.map(|v| CellValue::Int(v as i64)),
+        ColumnData::I16(v) => v.map(|v| CellValue::Int(v as i64)),
+        ColumnData::I32(v) => v.map(|v| CellValue::Int(v as i64)),
+        ColumnData::I64(v) => v.map(CellValue::Int),
+        ColumnData::F32(v) => v.map(|v| CellValue::Float(v as f64)),
+        ColumnData::F64(v) => v.map(|v| match column_type {
+            // MONEY and SMALLMONEY are integers of 1/10000 units on the wire.
+            ColumnType::Money | ColumnType::Money4 => CellValue::Decimal {
+                digits: ((v * 10_000.0).round() as i64).to_string(),
+                scale: 4,
+            },
+            _ => CellValue::Float(v),
+        }),
+        ColumnData::Bit(v) => v.map(CellValue::Bool),
+        ColumnData::String(v) => v.as_ref().map(|v| CellValue::Text(v.to_string())),
+        ColumnData::Guid(v) => v.map(|v| CellValue::Uuid(v.to_string())),
+        ColumnData::Binary(v) => v.as_ref().map(|v| CellValue::Binary(v.to_vec())),
+        ColumnData::Numeric(v) => v.map(|v| CellValue::Decimal {
+            digits: v.value().to_string(),
+            scale: v.scale() as u32,
+        }),
+        ColumnData::Xml(v) => v.as_ref().map(|v| CellValue::Text(v.to_string())),
+        // DATETIME counts 1/300 second ticks, which SQL Server shows rounded
+        // to the millisecond.
+        ColumnData::DateTime(v) => v.map(|v| CellValue::DateTime {
+            tz: None,
+            micros: (v.days() as i64 - DAYS_FROM_1900) * MICROS_PER_DAY
+                + (v.seconds_fragments() as i64 * 10 + 1) / 3 * 1000,
+        }),
+        // SMALLDATETIME counts whole minutes.
+        ColumnData::SmallDateTime(v) => v.map(|v| CellValue::DateTime {
+            tz: None,
+            micros: (v.days() as i64 - DAYS_FROM_1900) * MICROS_PER_DAY
+                + v.seconds_fragments() as i64 * 60_000_000,
+        }),
+        ColumnData::Date(v) => v.map(|v| CellValue::Date(date_text(v))),
+        ColumnData::Time(v) => v.map(|v| CellValue::Time(time_text(v))),
+        ColumnData::DateTime2(v) => v.map(|v| CellValue::DateTime {
+            tz: None,
+            micros: datetime2_micros(v),
+        }),
+        // The date and time of a DATETIMEOFFSET are in UTC.
+        ColumnData::DateTimeOffset(v) => v.map(|v| {
+            let offset = v.offset() as i32;
+            CellValue::DateTime {
+                tz: Some(format!(
+                    "{}{:02}:{:02}",
+                    if offset < 0 { '-' } else { '+' },
+                    offset.abs() / 60,
+                    offset.abs() % 60
+                )),
+                micros: datetime2_micros(v.datetime2()),
+            }
+        }),
+    };
+    cell.unwrap_or(CellValue::Null)
+}
+
+fn date_text(date: Date) -> String {
+    let days = date.days() as i64 - DAYS_FROM_YEAR_ONE;
+    chrono::DateTime::from_timestamp(days * 86_400, 0)
+        .map(|ts| ts.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// `HH:MM:SS` with as many fractional digits as the column's scale, the way
+/// SQL Server shows TIME(n).
+fn time_text(time: Time) -> String {
+    let scale = time.scale() as u32;
+    let per_second = 10u64.pow(scale);
+    let seconds = time.increments() / per_second;
+    let mut text = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    if scale > 0 {
+        text.push_str(&format!(
+            ".{:0width$}",
+            time.increments() % per_second,
+            width = scale as usize
+        ));
+    }
+    text
+}
+
+/// DATETIME2 as Unix epoch microseconds. The seventh fractional digit of
+/// DATETIME2(7) is below microsecond precision and is dropped.
+fn datetime2_micros(value: DateTime2) -> i64 {
+    let time = value.time();
+    let nanos = time.increments() as i64 * 10i64.pow(9 - time.scale() as u32);
+    (value.date().days() as i64 - DAYS_FROM_YEAR_ONE) * MICROS_PER_DAY + nanos / 1000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use tiberius::numeric::Numeric;
+    use tiberius::time::{DateTimeOffset, SmallDateTime};
+    use tiberius::xml::XmlData;
+
+    use crate::db::types::cell_text;
+
+    fn text(column_type: ColumnType, data: ColumnData<'_>) -> Option<String> {
+        cell_text(&column_data_to_cell(column_type, &data))
+    }
+
+    /// 2024-02-29 as days since 0001-01-01.
+    const LEAP_DAY: u32 = 738_944;
+
+    #[test]
+    fn test_decimal_keeps_all_digits() {
+        let value = Numeric::new_with_scale(12_345_678_901_234_567_890_123_456_789_012_345_678, 6);
+        assert_eq!(
+            text(ColumnType::Decimaln, ColumnData::Numeric(Some(value))).as_deref(),
+            Some("12345678901234567890123456789012.345678")
+        );
+        let value = Numeric::new_with_scale(-5, 2);
+        assert_eq!(text(ColumnType::Numericn, ColumnData::Numeric(Some(value))).as_deref(), Some("-0.05"));
+    }
+
+    #[test]
+    fn test_money_is_exact() {
+        assert_eq!(text(ColumnType::Money, ColumnData::F64(Some(12.34))).as_deref(), Some("12.3400"));
+        assert_eq!(text(ColumnType::Money4, ColumnData::F64(Some(-0.0001))).as_deref(), Some("-0.0001"));
+        assert!(matches!(
+            column_data_to_cell(ColumnType::Float8, &ColumnData::F64(Some(12.34))),
+            CellValue::Float(v) if v == 12.34
+        ));
+    }
+
+    #[test]
+    fn test_uniqueidentifier() {
+        let id = uuid::Uuid::parse_str("6f9619ff-8b86-d011-b42d-00c04fc964ff").unwrap();
+        assert!(matches!(
+            column_data_to_cell(ColumnType::Guid, &ColumnData::Guid(Some(id))),
+            CellValue::Uuid(v) if v == "6f9619ff-8b86-d011-b42d-00c04fc964ff"
+        ));
+    }
+
+    #[test]
+    fn test_datetime_rounds_ticks_to_milliseconds() {
+        // 1900-01-02 00:00:00 plus one and two 1/300 second ticks.
+        let one = ColumnData::DateTime(Some(tiberius::time::DateTime::new(1, 1)));
+        assert_eq!(text(ColumnType::Datetime, one).as_deref(), Some("1900-01-02 00:00:00.003"));
+        let two = ColumnData::DateTime(Some(tiberius::time::DateTime::new(1, 2)));
+        assert_eq!(text(ColumnType::Datetimen, two).as_deref(), Some("1900-01-02 00:00:00.007"));
+        let small = ColumnData::SmallDateTime(Some(SmallDateTime::new(45_000, 61)));
+        assert_eq!(text(ColumnType::Datetime4, small).as_deref(), Some("2023-03-17 01:01:00"));
+    }
+
+    #[test]
+    fn test_datetime2_and_offset() {
+        let value = DateTime2::new(Date::new(LEAP_DAY), Time::new(453_010_123_456, 7));
+        assert_eq!(
+            text(ColumnType::Datetime2, ColumnData::DateTime2(Some(value))).as_deref(),
+            Some("2024-02-29 12:35:01.012345")
+        );
+        // 10:00 UTC at +05:30.
+        let utc = DateTime2::new(Date::new(LEAP_DAY), Time::new(3_600_000, 2));
+        let value = DateTimeOffset::new(utc, 330);
+        assert_eq!(
+            text(ColumnType::DatetimeOffsetn, ColumnData::DateTimeOffset(Some(value))).as_deref(),
+            Some("2024-02-29 15:30:00+05:30")
+        );
+        let value = DateTimeOffset::new(utc, -480);
+        assert_eq!(
+            text(ColumnType::DatetimeOffsetn, ColumnData::DateTimeOffset(Some(value))).as_deref(),
+            Some("2024-02-29 02:00:00-08:00")
+        );
+    }
+
+    #[test]
+    fn test_date_and_time_keep_scale() {
+        assert_eq!(text(ColumnType::Daten, ColumnData::Date(Some(Date::new(LEAP_DAY)))).as_deref(), Some("2024-02-29"));
+        assert_eq!(text(ColumnType::Daten, ColumnData::Date(Some(Date::new(0)))).as_deref(), Some("0001-01-01"));
+        let time = ColumnData::Time(Some(Time::new(453_011_234_567, 7)));
+        assert_eq!(text(ColumnType::Timen, time).as_deref(), Some("12:35:01.1234567"));
+        let time = ColumnData::Time(Some(Time::new(45_301, 0)));
+        assert_eq!(text(ColumnType::Timen, time).as_deref(), Some("12:35:01"));
+    }
+
+    #[test]
+    fn test_text_xml_and_binary() {
+        let xml = ColumnData::Xml(Some(Cow::Owned(XmlData::new("<a>1</a>"))));
+        assert_eq!(text(ColumnType::Xml, xml).as_deref(), Some("<a>1</a>"));
+        let data = ColumnData::String(Some(Cow::Borrowed("héllo")));
+        assert_eq!(text(ColumnType::NVarchar, data).as_deref(), Some("héllo"));
+        let blob = ColumnData::Binary(Some(Cow::Owned(vec![0xde, 0xad])));
+        assert!(matches!(
+            column_data_to_cell(ColumnType::BigVarBin, &blob),
+            CellValue::Binary(v) if v == [0xde, 0xad]
+        ));
+    }
+
+    #[test]
+    fn test_nulls() {
+        for data in [
+            ColumnData::I32(None),
+            ColumnData::String(None),
+            ColumnData::Numeric(None),
+            ColumnData::DateTimeOffset(None),
+            ColumnData::F64(None),
+        ] {
+            assert!(matches!(column_data_to_cell(ColumnType::Money, &data), CellValue::Null));
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::models::query::{
+    CellValue, ColumnDef, ConnectionQueryResult, QueryResponse, ServerMessage, StatementError,
+};
+
+/// Column added in front of merged rows, naming the connection they came from.
+pub const CONNECTION_COLUMN: &str = "_connection";
+
+/// Per-connection entry for a finished run.
+pub fn connection_result(
+    connection_id: String,
+    outcome: Result<QueryResponse, AppError>,
+    elapsed: Duration,
+) -> ConnectionQueryResult {
+    let (response, error, confirm_token) = match outcome {
+        Ok(response) => (Some(response), None, None),
+        Err(e) => {
+            let token = match &e {
+                AppError::ConfirmationRequired { token, .. } => Some(token.clone()),
+                _ => None,
+            };
+            let error = StatementError {
+                code: e.error_code().to_string(),
+                message: e.to_string(),
+            };
+            (None, Some(error), token)
+        }
+    };
+    ConnectionQueryResult {
+        connection_id,
+        response,
+        error,
+        confirm_token,
+        execution_time_ms: elapsed.as_millis() as u64,
+    }
+}
+
+fn same_columns(a: &[ColumnDef], b: &[ColumnDef]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.name == y.name)
+}
+
+/// Move the rows of every successful result into one response, in result
+/// order. A result whose column names differ from the first one keeps its
+/// response and is marked failed instead.
+pub fn merge(results: &mut [ConnectionQueryResult]) -> QueryResponse {
+    let mut merged: Option<QueryResponse> = None;
+
+    for result in results.iter_mut() {
+        let Some(response) = result.response.take() else {
+            continue;
+        };
+        if let Some(m) = &merged {
+            if !same_columns(&m.columns[1..], &response.columns) {
+                result.error = Some(StatementError {
+                    code: "COLUMN_MISMATCH".to_string(),
+                    message: "Result columns differ from the other connections; rows not merged"
+                        .to_string(),
+                });
+                result.response = Some(response);
+                continue;
+            }
+        }
+
+        let label = CellValue::Text(result.connection_id.clone());
+        let rows = response.rows.into_iter().map(|row| {
+            let mut labelled = Vec::with_capacity(row.len() + 1);
+            labelled.push(label.clone());
+            labelled.extend(row);
+            labelled
+        });
+        let messages = response.messages.into_iter().map(|m| ServerMessage {
+            text: format!("[{}] {}", result.connection_id, m.text),
+            ..m
+        });
+
+        match merged.as_mut() {
+            None => {
+                let mut columns = Vec::with_capacity(response.columns.len() + 1);
+                columns.push(ColumnDef {
+                    name: CONNECTION_COLUMN.to_string(),
+                    data_type: "text".to_string(),
+                });
+                columns.extend(response.columns);
+                merged = Some(QueryResponse {
+                    columns,
+                    rows: rows.collect(),
+                    row_count: 0,
+                    execution_time_ms: response.execution_time_ms,
+                    affected_rows: response.affected_rows,
+                    truncated: response.truncated,
+                    max_rows_limit: response.max_rows_limit,
+                    messages: messages.collect(),
+                });
+            }
+            Some(m) => {
+                m.rows.extend(rows);
+                m.messages.extend(messages);
+                m.execution_time_ms = m.execution_time_ms.max(response.execution_time_ms);
+                m.affected_rows = match (m.affected_rows, response.affected_rows) {
+                    (Some(a), Some(b)) => Some(a + b),
+                    (a, b) => a.or(b),
+                };
+                m.truncated |= response.truncated;
+                m.max_rows_limit = m.max_rows_limit.or(response.max_rows_limit);
+            }
+        }
+    }
+
+    let mut merged = merged.unwrap_or_else(|| QueryResponse {
+        columns: Vec::new(),
+        rows: Vec::new(),
+        row_count: 0,
+        execution_time_ms: 0,
+        affected_rows: None,
+        truncated: false,
+        max_rows_limit: None,
+        messages: Vec::new(),
+    });
+    merged.row_count = merged.rows.len();
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(columns: &[&str], rows: Vec<Vec<CellValue>>) -> QueryResponse {
+        QueryResponse {
+            columns: columns
+                .iter()
+                .map(|c| ColumnDef {
+                    name: c.to_string(),
+                    data_type: "int".to_string(),
+                })
+                .collect(),
+            row_count: rows.len(),
+            rows,
+            execution_time_ms: 5,
+            affected_rows: None,
+            truncated: false,
+            max_rows_limit: None,
+            messages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_merge_labels_rows_and_skips_failures() {
+        let mut results = vec![
+            connection_result(
+                "a".into(),
+                Ok(response(&["id"], vec![vec![CellValue::Int(1)]])),
+                Duration::ZERO,
+            ),
+            connection_result(
+                "b".into(),
+                Err(AppError::Database("down".into())),
+                Duration::ZERO,
+            ),
+            connection_result(
+                "c".into(),
+                Ok(response(&["id"], vec![vec![CellValue::Int(2)], vec![CellValue::Int(3)]])),
+                Duration::ZERO,
+            ),
+        ];
+        let merged = merge(&mut results);
+        assert_eq!(merged.columns[0].name, CONNECTION_COLUMN);
+        assert_eq!(merged.row_count, 3);
+        assert!(matches!(&merged.rows[2][0], CellValue::Text(c) if c == "c"));
+        assert!(results.iter().all(|r| r.response.is_none()));
+        assert!(results[1].error.is_some());
+    }
+
+    #[test]
+    fn test_merge_rejects_mismatched_columns() {
+        let mut results = vec![
+            connection_result("a".into(), Ok(response(&["id"], vec![])), Duration::ZERO),
+            connection_result("b".into(), Ok(response(&["name"], vec![])), Duration::ZERO),
+        ];
+        let merged = merge(&mut results);
+        assert_eq!(merged.columns.len(), 2);
+        assert_eq!(results[1].error.as_ref().unwrap().code, "COLUMN_MISMATCH");
+        assert!(results[1].response.is_some());
+    }
+}
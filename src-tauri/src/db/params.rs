@@ -0,0 +1,233 @@
+use crate::error::AppError;
+use crate::models::query::CellValue;
+
+/// Placeholder for the `index`-th (1-based) bind parameter in a driver dialect.
+pub fn placeholder(dialect: &str, index: usize) -> String {
+    match dialect {
+        "postgres" => format!("${}", index),
+        "mssql" => format!("@P{}", index),
+        "neo4j" => format!("$p{}", index),
+        _ => "?".to_string(),
+    }
+}
+
+/// Collects bind values while building a statement, handing out placeholders
+/// in the target driver's dialect.
+pub struct ParamBuilder {
+    dialect: &'static str,
+    params: Vec<CellValue>,
+}
+
+impl ParamBuilder {
+    pub fn new(dialect: &'static str) -> Self {
+        Self {
+            dialect,
+            params: Vec::new(),
+        }
+    }
+
+    /// Add a value and return the placeholder that refers to it.
+    pub fn push(&mut self, value: CellValue) -> String {
+        self.params.push(value);
+        placeholder(self.dialect, self.params.len())
+    }
+
+    /// Like `push`, but on PostgreSQL casts the placeholder to `sql_type`, since
+    /// text binds are not implicitly coerced to the compared column's type there.
+    /// Enum and array columns need their `format_type` name, e.g. `"Mood"` or
+    /// `integer[]`; the generic `USER-DEFINED` and `ARRAY` are left uncast.
+    pub fn push_typed(&mut self, value: CellValue, sql_type: Option<&str>) -> String {
+        let ph = self.push(value);
+        match sql_type {
+            Some(ty) if self.dialect == "postgres" && is_castable_pg_type(ty) => {
+                format!("CAST({} AS {})", ph, ty)
+            }
+            _ => ph,
+        }
+    }
+
+    pub fn into_params(self) -> Vec<CellValue> {
+        self.params
+    }
+}
+
+/// information_schema reports arrays and user-defined types generically;
+/// those names cannot be used in a CAST. Anything else must look like a type
+/// name as `format_type` prints it: possibly schema-qualified or quoted, with
+/// a modifier and array brackets.
+fn is_castable_pg_type(ty: &str) -> bool {
+    !ty.is_empty()
+        && ty != "ARRAY"
+        && ty != "USER-DEFINED"
+        && ty.matches('"').count() % 2 == 0
+        && ty.chars().all(|c| {
+            c.is_ascii_alphanumeric()
+                || matches!(c, ' ' | '_' | '"' | '.' | '(' | ')' | ',' | '[' | ']')
+        })
+}
+
+/// Escape `s` for a single-quoted literal in `dialect`. Standard SQL engines
+/// only double quotes; the others read backslashes as escapes too, so they
+/// (and any dialect not listed here) get MySQL-style escaping.
+fn escape_literal(s: &str, dialect: &str) -> String {
+    use crate::db::escape::escape_sql_literal;
+
+    match dialect {
+        "postgres" | "sqlite" | "mssql" | "cassandra" => s.replace('\'', "''"),
+        _ => escape_sql_literal(s),
+    }
+}
+
+/// Render a bind value as an escaped SQL literal for `dialect`.
+pub(crate) fn param_literal(value: &CellValue, dialect: &str) -> Result<String, AppError> {
+    use crate::db::types::{cell_text, decimal_text};
+
+    Ok(match value {
+        CellValue::Null => "NULL".to_string(),
+        CellValue::Bool(v) => if *v { "TRUE".to_string() } else { "FALSE".to_string() },
+        CellValue::Int(v) => v.to_string(),
+        CellValue::Float(v) => v.to_string(),
+        CellValue::Text(v) | CellValue::Timestamp(v) | CellValue::Json(v) => {
+            format!("'{}'", escape_literal(v, dialect))
+        }
+        CellValue::Decimal { digits, scale } => decimal_text(digits, *scale),
+        CellValue::Uuid(_)
+        | CellValue::Date(_)
+        | CellValue::Time(_)
+        | CellValue::DateTime { .. }
+        | CellValue::Interval { .. }
+        | CellValue::Enum(_) => {
+            format!("'{}'", escape_literal(&cell_text(value).unwrap_or_default(), dialect))
+        }
+        CellValue::Array(_)
+        | CellValue::Binary(_)
+        | CellValue::LargeText { .. }
+        | CellValue::LargeJson { .. }
+        | CellValue::LargeBinary { .. } => {
+            return Err(AppError::InvalidConfig(
+                "Parameter type cannot be inlined for this driver".to_string(),
+            ));
+        }
+    })
+}
+
+/// Substitute `?` placeholders with escaped literals, skipping quoted strings,
+/// quoted identifiers and comments. Only used by drivers whose client library
+/// cannot bind parameters (REST-based engines).
+pub fn inline_params(sql: &str, dialect: &str, params: &[CellValue]) -> Result<String, AppError> {
+    let mut out = String::with_capacity(sql.len() + params.len() * 8);
+    let mut chars = sql.chars().peekable();
+    let mut next = 0;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                out.push(c);
+                while let Some(inner) = chars.next() {
+                    out.push(inner);
+                    if inner == '\\' && c == '\'' {
+                        if let Some(escaped) = chars.next() {
+                            out.push(escaped);
+                        }
+                    } else if inner == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                out.push(c);
+                for inner in chars.by_ref() {
+                    out.push(inner);
+                    if inner == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                out.push(c);
+                out.push(chars.next().unwrap_or('*'));
+                let mut prev = '\0';
+                for inner in chars.by_ref() {
+                    out.push(inner);
+                    if prev == '*' && inner == '/' {
+                        break;
+                    }
+                    prev = inner;
+                }
+            }
+            '?' => {
+                let value = params.get(next).ok_or_else(|| {
+                    AppError::InvalidConfig(format!(
+                        "Query has more placeholders than the {} parameter(s) supplied",
+                        params.len()
+                    ))
+                })?;
+                out.push_str(&param_literal(value, dialect)?);
+                next += 1;
+            }
+            _ => out.push(c),
+        }
+    }
+
+    if next != params.len() {
+        return Err(AppError::InvalidConfig(format!(
+            "Query has {} placeholder(s) but {} parameter(s) were supplied",
+            next,
+            params.len()
+        )));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_dialects() {
+        assert_eq!(placeholder("postgres", 2), "$2");
+        assert_eq!(placeholder("mssql", 1), "@P1");
+        assert_eq!(placeholder("mysql", 3), "?");
+    }
+
+    #[test]
+    fn test_push_typed_casts_on_postgres_only() {
+        let mut pg = ParamBuilder::new("postgres");
+        assert_eq!(pg.push_typed(CellValue::Text("1".into()), Some("integer")), "CAST($1 AS integer)");
+        assert_eq!(pg.push_typed(CellValue::Text("a".into()), Some("USER-DEFINED")), "$2");
+        assert_eq!(
+            pg.push_typed(CellValue::Text("happy".into()), Some("\"Mood\"")),
+            "CAST($3 AS \"Mood\")"
+        );
+        assert_eq!(
+            pg.push_typed(CellValue::Text("{1,2}".into()), Some("public.tags[]")),
+            "CAST($4 AS public.tags[])"
+        );
+        assert_eq!(pg.push_typed(CellValue::Text("x".into()), Some("a\"; DROP")), "$5");
+
+        let mut my = ParamBuilder::new("mysql");
+        assert_eq!(my.push_typed(CellValue::Text("1".into()), Some("int")), "?");
+    }
+
+    #[test]
+    fn test_inline_params_skips_strings_and_comments() {
+        let sql = "SELECT '?' AS q, \"a?\" FROM t -- ?\nWHERE a = ? /* ? */ AND b = ?";
+        let out = inline_params(sql, "snowflake", &[CellValue::Text("it's".into()), CellValue::Int(5)]).unwrap();
+        assert_eq!(out, "SELECT '?' AS q, \"a?\" FROM t -- ?\nWHERE a = 'it''s' /* ? */ AND b = 5");
+    }
+
+    #[test]
+    fn test_param_literal_escapes_by_dialect() {
+        let value = CellValue::Text("it's C:\\temp".into());
+        assert_eq!(param_literal(&value, "postgres").unwrap(), "'it''s C:\\temp'");
+        assert_eq!(param_literal(&value, "mssql").unwrap(), "'it''s C:\\temp'");
+        assert_eq!(param_literal(&value, "mysql").unwrap(), "'it''s C:\\\\temp'");
+        assert_eq!(param_literal(&value, "snowflake").unwrap(), "'it''s C:\\\\temp'");
+    }
+
+    #[test]
+    fn test_inline_params_count_mismatch() {
+        assert!(inline_params("SELECT ?", "snowflake", &[]).is_err());
+        assert!(inline_params("SELECT 1", "snowflake", &[CellValue::Int(1)]).is_err());
+    }
+}
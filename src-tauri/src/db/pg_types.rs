@@ -0,0 +1,716 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use sqlx::postgres::{PgTypeInfo, PgTypeKind, PgValueFormat, PgValueRef};
+use sqlx::TypeInfo;
+
+use crate::db::types::{cell_text, date_cell, time_cell};
+use crate::models::query::CellValue;
+
+/// Microseconds from the Unix epoch to PostgreSQL's, 2000-01-01.
+const PG_EPOCH_MICROS: i64 = 946_684_800_000_000;
+
+/// Decode a value of a type sqlx has no mapping for: arrays, ranges, domains,
+/// network and bit string types, hstore, XML and geometries. Values fetched
+/// in text format are kept as the server rendered them, which it accepts back.
+pub fn decode(type_info: &PgTypeInfo, value: &PgValueRef<'_>) -> Option<CellValue> {
+    match value.format() {
+        PgValueFormat::Binary => decode_binary(type_info, value.as_bytes().ok()?),
+        PgValueFormat::Text => {
+            let text = value.as_str().ok()?.to_string();
+            Some(match type_info.kind() {
+                PgTypeKind::Enum(_) => CellValue::Enum(text),
+                _ => CellValue::Text(text),
+            })
+        }
+    }
+}
+
+/// Decode a value sent in the binary format. `None` for types not known here.
+pub fn decode_binary(type_info: &PgTypeInfo, buf: &[u8]) -> Option<CellValue> {
+    match type_info.kind() {
+        PgTypeKind::Domain(base) => return decode_binary(base, buf),
+        PgTypeKind::Array(element) => return array(element, buf),
+        PgTypeKind::Range(element) => return range(element, buf).map(CellValue::Text),
+        // Enum labels are sent as text in both formats.
+        PgTypeKind::Enum(_) => return utf8(buf).map(CellValue::Enum),
+        _ => {}
+    }
+
+    let mut r = Reader::new(buf);
+    let cell = match type_info.name().to_uppercase().as_str() {
+        "BOOL" => CellValue::Bool(r.u8()? != 0),
+        "INT2" => CellValue::Int(r.i16()? as i64),
+        "INT4" => CellValue::Int(r.i32()? as i64),
+        "INT8" => CellValue::Int(r.i64()?),
+        "OID" => CellValue::Int(r.u32()? as i64),
+        "FLOAT4" => CellValue::Float(f32::from_bits(r.u32()?) as f64),
+        "FLOAT8" => CellValue::Float(r.f64()?),
+        "NUMERIC" => numeric(buf)?,
+        // MONEY is sent as an integer count of cents.
+        "MONEY" => CellValue::Decimal {
+            digits: r.i64()?.to_string(),
+            scale: 2,
+        },
+        "TEXT" | "VARCHAR" | "CHAR" | "\"CHAR\"" | "NAME" | "CITEXT" | "UNKNOWN" | "LTREE"
+        | "JSONPATH" | "XML" => CellValue::Text(utf8(buf)?),
+        "JSON" => CellValue::Json(utf8(buf)?),
+        // JSONB carries a format version byte before the text.
+        "JSONB" => CellValue::Json(utf8(buf.get(1..)?)?),
+        "BYTEA" => CellValue::Binary(buf.to_vec()),
+        "UUID" => CellValue::Uuid(uuid::Uuid::from_slice(buf).ok()?.to_string()),
+        "DATE" => match r.i32()? {
+            i32::MAX => CellValue::Text("infinity".to_string()),
+            i32::MIN => CellValue::Text("-infinity".to_string()),
+            days => date_cell(
+                chrono::NaiveDate::from_ymd_opt(2000, 1, 1)?
+                    .checked_add_signed(chrono::Duration::days(days as i64))?,
+            ),
+        },
+        "TIME" => time(r.i64()?),
+        "TIMETZ" => {
+            let time = time(r.i64()?);
+            // The offset is sent in seconds west of UTC.
+            let offset = chrono::FixedOffset::east_opt(-r.i32()?)?;
+            CellValue::Time(format!("{}{}", cell_text(&time)?, offset))
+        }
+        "TIMESTAMP" => timestamp(r.i64()?, None),
+        "TIMESTAMPTZ" => timestamp(r.i64()?, Some("+00:00")),
+        "INTERVAL" => {
+            let micros = r.i64()?;
+            let days = r.i32()?;
+            CellValue::Interval {
+                months: r.i32()?,
+                days,
+                micros,
+            }
+        }
+        "INET" | "CIDR" => CellValue::Text(inet(buf)?),
+        "MACADDR" | "MACADDR8" => CellValue::Text(
+            buf.iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(":"),
+        ),
+        "BIT" | "VARBIT" => CellValue::Text(bit_string(buf)?),
+        "HSTORE" => CellValue::Text(hstore(buf)?),
+        "GEOMETRY" | "GEOGRAPHY" => CellValue::Text(ewkt(buf)?),
+        "POINT" => CellValue::Text(format!("({})", r.point()?)),
+        "LSEG" => CellValue::Text(format!("[({}),({})]", r.point()?, r.point()?)),
+        "BOX" => CellValue::Text(format!("({}),({})", r.point()?, r.point()?)),
+        "LINE" => CellValue::Text(format!("{{{},{},{}}}", r.f64()?, r.f64()?, r.f64()?)),
+        "CIRCLE" => CellValue::Text(format!("<({}),{}>", r.point()?, r.f64()?)),
+        "PATH" => {
+            let closed = r.u8()? != 0;
+            let points = r.points()?;
+            CellValue::Text(if closed {
+                format!("({})", points)
+            } else {
+                format!("[{}]", points)
+            })
+        }
+        "POLYGON" => CellValue::Text(format!("({})", r.points()?)),
+        _ => return None,
+    };
+    Some(cell)
+}
+
+/// Binary NUMERIC: digit count, weight of the first digit, sign and display
+/// scale, then base-10000 digits. NaN and infinities become text.
+pub fn numeric(buf: &[u8]) -> Option<CellValue> {
+    let word = |i: usize| {
+        buf.get(i * 2..i * 2 + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+    };
+    let ndigits = word(0)? as usize;
+    let weight = word(1)? as i16 as i64;
+    let sign = word(2)?;
+    let dscale = word(3)? as usize;
+    match sign {
+        0xC000 => return Some(CellValue::Text("NaN".to_string())),
+        0xD000 => return Some(CellValue::Text("Infinity".to_string())),
+        0xF000 => return Some(CellValue::Text("-Infinity".to_string())),
+        _ => {}
+    }
+    let groups: Vec<u16> = (0..ndigits).map(|k| word(4 + k)).collect::<Option<_>>()?;
+    let group = |index: i64| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| groups.get(i).copied())
+            .unwrap_or(0)
+    };
+
+    let mut digits: String = (0..=weight).map(|g| format!("{:04}", group(g))).collect();
+    let mut fraction: String = (weight + 1..weight + 1 + (dscale as i64 + 3) / 4)
+        .map(|g| format!("{:04}", group(g)))
+        .collect();
+    fraction.truncate(dscale);
+    digits.push_str(&fraction);
+    let digits = match digits.trim_start_matches('0') {
+        "" => "0".to_string(),
+        d if sign == 0x4000 => format!("-{}", d),
+        d => d.to_string(),
+    };
+    Some(CellValue::Decimal {
+        digits,
+        scale: dscale as u32,
+    })
+}
+
+fn utf8(buf: &[u8]) -> Option<String> {
+    std::str::from_utf8(buf).ok().map(str::to_string)
+}
+
+fn time(micros: i64) -> CellValue {
+    let time = u32::try_from(micros / 1_000_000).ok().and_then(|secs| {
+        chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+            secs,
+            (micros % 1_000_000) as u32 * 1000,
+        )
+    });
+    match time {
+        Some(time) => time_cell(time),
+        // 24:00:00 is a valid TIME but not a valid chrono::NaiveTime.
+        None => CellValue::Time("24:00:00".to_string()),
+    }
+}
+
+fn timestamp(micros: i64, tz: Option<&str>) -> CellValue {
+    match micros {
+        i64::MAX => CellValue::Text("infinity".to_string()),
+        i64::MIN => CellValue::Text("-infinity".to_string()),
+        _ => CellValue::DateTime {
+            tz: tz.map(str::to_string),
+            micros: micros.saturating_add(PG_EPOCH_MICROS),
+        },
+    }
+}
+
+/// Arrays of any element type, nested for more than one dimension. Lower
+/// bounds other than 1 are not kept.
+fn array(element: &PgTypeInfo, buf: &[u8]) -> Option<CellValue> {
+    let mut r = Reader::new(buf);
+    let ndim = usize::try_from(r.i32()?).ok()?;
+    let _has_nulls = r.i32()?;
+    let _element_oid = r.u32()?;
+    let mut dims = Vec::with_capacity(ndim);
+    for _ in 0..ndim {
+        dims.push(usize::try_from(r.i32()?).ok()?);
+        let _lower_bound = r.i32()?;
+    }
+    if dims.is_empty() {
+        return Some(CellValue::Array(Vec::new()));
+    }
+    array_dim(element, &dims, &mut r)
+}
+
+fn array_dim(element: &PgTypeInfo, dims: &[usize], r: &mut Reader<'_>) -> Option<CellValue> {
+    let mut items = Vec::with_capacity(dims[0]);
+    for _ in 0..dims[0] {
+        items.push(if dims.len() > 1 {
+            array_dim(element, &dims[1..], r)?
+        } else {
+            match r.value()? {
+                Some(bytes) => element_cell(element, bytes),
+                None => CellValue::Null,
+            }
+        });
+    }
+    Some(CellValue::Array(items))
+}
+
+/// An array element or range bound, kept as text or bytes when its type is
+/// not known here.
+fn element_cell(element: &PgTypeInfo, bytes: &[u8]) -> CellValue {
+    decode_binary(element, bytes)
+        .or_else(|| utf8(bytes).map(CellValue::Text))
+        .unwrap_or_else(|| CellValue::Binary(bytes.to_vec()))
+}
+
+/// A range in PostgreSQL's literal syntax, e.g. `[1,10)` or `empty`.
+fn range(element: &PgTypeInfo, buf: &[u8]) -> Option<String> {
+    const EMPTY: u8 = 0x01;
+    const LOWER_INCLUSIVE: u8 = 0x02;
+    const UPPER_INCLUSIVE: u8 = 0x04;
+    const LOWER_INFINITE: u8 = 0x08;
+    const UPPER_INFINITE: u8 = 0x10;
+
+    let mut r = Reader::new(buf);
+    let flags = r.u8()?;
+    if flags & EMPTY != 0 {
+        return Some("empty".to_string());
+    }
+    let mut bound = |infinite: u8| -> Option<String> {
+        if flags & infinite != 0 {
+            return Some(String::new());
+        }
+        let len = usize::try_from(r.i32()?).ok()?;
+        let cell = element_cell(element, r.take(len)?);
+        Some(range_bound(&cell_text(&cell).unwrap_or_default()))
+    };
+    let lower = bound(LOWER_INFINITE)?;
+    let upper = bound(UPPER_INFINITE)?;
+    Some(format!(
+        "{}{},{}{}",
+        if flags & LOWER_INCLUSIVE != 0 {
+            '['
+        } else {
+            '('
+        },
+        lower,
+        upper,
+        if flags & UPPER_INCLUSIVE != 0 {
+            ']'
+        } else {
+            ')'
+        },
+    ))
+}
+
+/// A range bound, quoted when it contains characters the range syntax uses.
+fn range_bound(text: &str) -> String {
+    let plain = !text.is_empty()
+        && !text
+            .chars()
+            .any(|c| matches!(c, '(' | ')' | '[' | ']' | ',' | '"' | '\\') || c.is_whitespace());
+    if plain {
+        text.to_string()
+    } else {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// BIT and VARBIT: the length in bits, then the bits packed high bit first.
+fn bit_string(buf: &[u8]) -> Option<String> {
+    let mut r = Reader::new(buf);
+    let bits = usize::try_from(r.i32()?).ok()?;
+    let bytes = r.take(bits.div_ceil(8))?;
+    Some(
+        (0..bits)
+            .map(|i| {
+                if bytes[i / 8] & (0x80 >> (i % 8)) != 0 {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect(),
+    )
+}
+
+/// INET and CIDR: family, prefix length, CIDR flag, address length, address.
+/// A host address is written without its full-length prefix, as PostgreSQL
+/// does for INET.
+fn inet(buf: &[u8]) -> Option<String> {
+    let mut r = Reader::new(buf);
+    let family = r.u8()?;
+    let bits = r.u8()?;
+    let is_cidr = r.u8()? != 0;
+    let len = r.u8()? as usize;
+    let addr = r.take(len)?;
+    let (text, max_bits) = match family {
+        2 => (
+            Ipv4Addr::from(<[u8; 4]>::try_from(addr).ok()?).to_string(),
+            32,
+        ),
+        3 => (
+            Ipv6Addr::from(<[u8; 16]>::try_from(addr).ok()?).to_string(),
+            128,
+        ),
+        _ => return None,
+    };
+    Some(if is_cidr || bits != max_bits {
+        format!("{}/{}", text, bits)
+    } else {
+        text
+    })
+}
+
+/// hstore in its input syntax: `"key"=>"value", "other"=>NULL`.
+fn hstore(buf: &[u8]) -> Option<String> {
+    let quote = |s: String| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut r = Reader::new(buf);
+    let count = usize::try_from(r.i32()?).ok()?;
+    let mut pairs = Vec::with_capacity(count);
+    for _ in 0..count {
+        let key = utf8(r.value()??)?;
+        let value = match r.value()? {
+            Some(bytes) => quote(utf8(bytes)?),
+            None => "NULL".to_string(),
+        };
+        pairs.push(format!("{}=>{}", quote(key), value));
+    }
+    Some(pairs.join(", "))
+}
+
+/// PostGIS EWKB as EWKT, e.g. `SRID=4326;POINT(1 2)`, which PostGIS accepts
+/// back as input.
+fn ewkt(buf: &[u8]) -> Option<String> {
+    let mut r = Reader::new(buf);
+    let (text, srid) = wkb(&mut r)?;
+    Some(match srid {
+        Some(srid) => format!("SRID={};{}", srid, text),
+        None => text,
+    })
+}
+
+/// One WKB geometry, with the SRID if the EWKB header carries one. Both the
+/// EWKB dimension flags and ISO WKB type codes (1001, 2001, ...) are read.
+fn wkb(r: &mut Reader<'_>) -> Option<(String, Option<u32>)> {
+    let le = r.u8()? == 1;
+    let raw_type = r.u32_in(le)?;
+    let srid = match raw_type & 0x2000_0000 {
+        0 => None,
+        _ => Some(r.u32_in(le)?),
+    };
+    let code = raw_type & 0x0fff_ffff;
+    let (mut z, mut m) = (raw_type & 0x8000_0000 != 0, raw_type & 0x4000_0000 != 0);
+    match code / 1000 {
+        1 => z = true,
+        2 => m = true,
+        3 => (z, m) = (true, true),
+        _ => {}
+    }
+    let dims = 2 + z as usize + m as usize;
+
+    let (name, body) = match code % 1000 {
+        1 => {
+            let coords: Vec<f64> = (0..dims).map(|_| r.f64_in(le)).collect::<Option<_>>()?;
+            // An empty point is written with NaN coordinates.
+            let body = (!coords.iter().all(|c| c.is_nan())).then(|| format!("({})", join(&coords)));
+            ("POINT", body)
+        }
+        2 => ("LINESTRING", wkb_points(r, le, dims)?),
+        3 => {
+            let rings: Vec<String> = (0..r.u32_in(le)?)
+                .map(|_| wkb_points(r, le, dims).map(Option::unwrap_or_default))
+                .collect::<Option<_>>()?;
+            (
+                "POLYGON",
+                (!rings.is_empty()).then(|| format!("({})", rings.join(","))),
+            )
+        }
+        code @ 4..=7 => {
+            let parts: Vec<String> = (0..r.u32_in(le)?)
+                .map(|_| {
+                    let (text, _) = wkb(r)?;
+                    // Members of a collection keep their type name; points in
+                    // a multipoint also drop their parentheses.
+                    Some(match (code, text.find('(')) {
+                        (7, _) => text,
+                        (_, None) => "EMPTY".to_string(),
+                        (4, Some(i)) => text[i..].trim_matches(['(', ')']).to_string(),
+                        (_, Some(i)) => text[i..].to_string(),
+                    })
+                })
+                .collect::<Option<_>>()?;
+            let name = [
+                "MULTIPOINT",
+                "MULTILINESTRING",
+                "MULTIPOLYGON",
+                "GEOMETRYCOLLECTION",
+            ][code as usize - 4];
+            (
+                name,
+                (!parts.is_empty()).then(|| format!("({})", parts.join(","))),
+            )
+        }
+        _ => return None,
+    };
+    // Like ST_AsEWKT: a Z coordinate is implied by the count, M is marked.
+    let tag = if m && !z {
+        format!("{}M", name)
+    } else {
+        name.to_string()
+    };
+    let text = match body {
+        Some(body) => format!("{}{}", tag, body),
+        None => format!("{} EMPTY", tag),
+    };
+    Some((text, srid))
+}
+
+/// A point count followed by the points, as `(x y,x y)`; `None` inside when
+/// there are no points.
+fn wkb_points(r: &mut Reader<'_>, le: bool, dims: usize) -> Option<Option<String>> {
+    let count = r.u32_in(le)? as usize;
+    let points: Vec<String> = (0..count)
+        .map(|_| {
+            let coords: Vec<f64> = (0..dims).map(|_| r.f64_in(le)).collect::<Option<_>>()?;
+            Some(join(&coords))
+        })
+        .collect::<Option<_>>()?;
+    Some((!points.is_empty()).then(|| format!("({})", points.join(","))))
+}
+
+fn join(coords: &[f64]) -> String {
+    coords
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reads the fixed-width big-endian fields of the binary format.
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.buf.len() < n {
+            return None;
+        }
+        let (head, rest) = self.buf.split_at(n);
+        self.buf = rest;
+        Some(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.array::<1>()?[0])
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        self.array().map(i16::from_be_bytes)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.array().map(i32::from_be_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        self.array().map(i64::from_be_bytes)
+    }
+
+    fn f64(&mut self) -> Option<f64> {
+        self.array().map(f64::from_be_bytes)
+    }
+
+    /// WKB fields, whose byte order is set per geometry.
+    fn u32_in(&mut self, le: bool) -> Option<u32> {
+        self.array().map(if le {
+            u32::from_le_bytes
+        } else {
+            u32::from_be_bytes
+        })
+    }
+
+    fn f64_in(&mut self, le: bool) -> Option<f64> {
+        self.array().map(if le {
+            f64::from_le_bytes
+        } else {
+            f64::from_be_bytes
+        })
+    }
+
+    /// A length-prefixed value; `Some(None)` for NULL.
+    fn value(&mut self) -> Option<Option<&'a [u8]>> {
+        match self.i32()? {
+            len if len < 0 => Some(None),
+            len => self.take(len as usize).map(Some),
+        }
+    }
+
+    /// A built-in geometric point, as `x,y`.
+    fn point(&mut self) -> Option<String> {
+        Some(format!("{},{}", self.f64()?, self.f64()?))
+    }
+
+    /// A point count followed by the points, as `(x,y),(x,y)`.
+    fn points(&mut self) -> Option<String> {
+        let count = usize::try_from(self.i32()?).ok()?;
+        let points: Vec<String> = (0..count)
+            .map(|_| self.point().map(|p| format!("({})", p)))
+            .collect::<Option<_>>()?;
+        Some(points.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::types::PgRange;
+    use sqlx::{Postgres, Type};
+
+    fn be(fields: &[&[u8]]) -> Vec<u8> {
+        fields.concat()
+    }
+
+    fn text(cell: Option<CellValue>) -> Option<String> {
+        cell_text(&cell?)
+    }
+
+    #[test]
+    fn test_numeric() {
+        let encode = |words: &[u16]| {
+            words
+                .iter()
+                .flat_map(|w| w.to_be_bytes())
+                .collect::<Vec<u8>>()
+        };
+        // 12345.678: groups 1|2345|6780, weight 1, scale 3.
+        let buf = encode(&[3, 1, 0x0000, 3, 1, 2345, 6780]);
+        assert_eq!(text(numeric(&buf)).as_deref(), Some("12345.678"));
+        // -0.00001: one group 1000 at weight -2, scale 5.
+        let buf = encode(&[1, (-2i16) as u16, 0x4000, 5, 1000]);
+        assert_eq!(text(numeric(&buf)).as_deref(), Some("-0.00001"));
+        // 20000: group 2 at weight 1, trailing zero group omitted.
+        let buf = encode(&[1, 1, 0x0000, 0, 2]);
+        assert_eq!(text(numeric(&buf)).as_deref(), Some("20000"));
+        let buf = encode(&[0, 0, 0xC000, 0]);
+        assert!(matches!(numeric(&buf), Some(CellValue::Text(t)) if t == "NaN"));
+    }
+
+    #[test]
+    fn test_int_array_with_null() {
+        let buf = be(&[
+            &1i32.to_be_bytes(),
+            &1i32.to_be_bytes(),
+            &23u32.to_be_bytes(),
+            &3i32.to_be_bytes(),
+            &1i32.to_be_bytes(),
+            &4i32.to_be_bytes(),
+            &7i32.to_be_bytes(),
+            &(-1i32).to_be_bytes(),
+            &4i32.to_be_bytes(),
+            &(-2i32).to_be_bytes(),
+        ]);
+        let info = <Vec<i32> as Type<Postgres>>::type_info();
+        assert_eq!(
+            text(decode_binary(&info, &buf)).as_deref(),
+            Some("{7,NULL,-2}")
+        );
+    }
+
+    #[test]
+    fn test_two_dimensional_array() {
+        let mut buf = be(&[
+            &2i32.to_be_bytes(),
+            &0i32.to_be_bytes(),
+            &20u32.to_be_bytes(),
+            &2i32.to_be_bytes(),
+            &1i32.to_be_bytes(),
+            &2i32.to_be_bytes(),
+            &1i32.to_be_bytes(),
+        ]);
+        for v in 1i64..=4 {
+            buf.extend(8i32.to_be_bytes());
+            buf.extend(v.to_be_bytes());
+        }
+        let info = <Vec<i64> as Type<Postgres>>::type_info();
+        assert_eq!(
+            text(decode_binary(&info, &buf)).as_deref(),
+            Some("{{1,2},{3,4}}")
+        );
+    }
+
+    #[test]
+    fn test_ranges() {
+        let info = <PgRange<i32> as Type<Postgres>>::type_info();
+        let buf = be(&[
+            &[0x02],
+            &4i32.to_be_bytes(),
+            &1i32.to_be_bytes(),
+            &4i32.to_be_bytes(),
+            &10i32.to_be_bytes(),
+        ]);
+        assert_eq!(text(decode_binary(&info, &buf)).as_deref(), Some("[1,10)"));
+        let buf = be(&[&[0x12], &4i32.to_be_bytes(), &5i32.to_be_bytes()]);
+        assert_eq!(text(decode_binary(&info, &buf)).as_deref(), Some("[5,)"));
+        assert_eq!(
+            text(decode_binary(&info, &[0x01])).as_deref(),
+            Some("empty")
+        );
+
+        let info = <PgRange<chrono::NaiveDateTime> as Type<Postgres>>::type_info();
+        let buf = be(&[&[0x08], &8i32.to_be_bytes(), &0i64.to_be_bytes()]);
+        assert_eq!(
+            text(decode_binary(&info, &buf)).as_deref(),
+            Some("(,\"2000-01-01 00:00:00\")")
+        );
+    }
+
+    #[test]
+    fn test_inet_and_cidr() {
+        assert_eq!(
+            inet(&[2, 32, 0, 4, 192, 168, 0, 1]).as_deref(),
+            Some("192.168.0.1")
+        );
+        assert_eq!(
+            inet(&[2, 24, 1, 4, 10, 1, 2, 0]).as_deref(),
+            Some("10.1.2.0/24")
+        );
+        let mut v6 = vec![3, 64, 0, 16, 0x20, 0x01, 0x0d, 0xb8];
+        v6.extend([0; 12]);
+        assert_eq!(inet(&v6).as_deref(), Some("2001:db8::/64"));
+    }
+
+    #[test]
+    fn test_hstore() {
+        let buf = be(&[
+            &2i32.to_be_bytes(),
+            &1i32.to_be_bytes(),
+            b"a",
+            &3i32.to_be_bytes(),
+            b"x\"y",
+            &1i32.to_be_bytes(),
+            b"b",
+            &(-1i32).to_be_bytes(),
+        ]);
+        assert_eq!(hstore(&buf).as_deref(), Some(r#""a"=>"x\"y", "b"=>NULL"#));
+    }
+
+    #[test]
+    fn test_ewkt() {
+        // Little-endian point with SRID 4326.
+        let point = be(&[
+            &[1],
+            &0x2000_0001u32.to_le_bytes(),
+            &4326u32.to_le_bytes(),
+            &1.5f64.to_le_bytes(),
+            &(-2f64).to_le_bytes(),
+        ]);
+        assert_eq!(ewkt(&point).as_deref(), Some("SRID=4326;POINT(1.5 -2)"));
+
+        // Big-endian multipoint of two points, one of them 3D (ISO code 1001).
+        let buf = be(&[
+            &[0],
+            &4u32.to_be_bytes(),
+            &2u32.to_be_bytes(),
+            &[0],
+            &1u32.to_be_bytes(),
+            &1f64.to_be_bytes(),
+            &2f64.to_be_bytes(),
+            &[1],
+            &1001u32.to_le_bytes(),
+            &3f64.to_le_bytes(),
+            &4f64.to_le_bytes(),
+            &5f64.to_le_bytes(),
+        ]);
+        assert_eq!(ewkt(&buf).as_deref(), Some("MULTIPOINT(1 2,3 4 5)"));
+
+        let empty = be(&[&[1], &3u32.to_le_bytes(), &0u32.to_le_bytes()]);
+        assert_eq!(ewkt(&empty).as_deref(), Some("POLYGON EMPTY"));
+    }
+
+    #[test]
+    fn test_bit_string() {
+        assert_eq!(
+            bit_string(&[0, 0, 0, 10, 0b1011_0000, 0b0100_0000]).as_deref(),
+            Some("1011000001")
+        );
+        assert_eq!(bit_string(&[0, 0, 0, 0]).as_deref(), Some(""));
+        assert_eq!(bit_string(&[0, 0, 0, 9, 0xff]), None);
+    }
+}
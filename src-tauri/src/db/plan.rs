@@ -0,0 +1,1044 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::models::plan::{PlanNode, QueryPlan};
+use crate::models::query::{CellValue, QueryResponse};
+
+/// `sql` without surrounding whitespace and trailing semicolons, ready to be
+/// prefixed with an EXPLAIN clause.
+pub fn explain_target(sql: &str) -> &str {
+    sql.trim().trim_end_matches(';').trim_end()
+}
+
+/// The plan document an EXPLAIN statement returned: the first column of every
+/// row, joined by newlines (ClickHouse splits long output over several rows).
+pub fn plan_document(response: &QueryResponse) -> Result<String, AppError> {
+    let lines: Vec<&str> = response
+        .rows
+        .iter()
+        .filter_map(|row| match row.first() {
+            Some(CellValue::Text(s)) | Some(CellValue::Json(s)) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect();
+    if lines.is_empty() {
+        return Err(AppError::Database("EXPLAIN returned no plan".to_string()));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// `EXPLAIN (FORMAT JSON[, ANALYZE, BUFFERS])`.
+pub fn parse_postgres(raw: &str, analyzed: bool) -> Result<QueryPlan, AppError> {
+    let doc: Value = serde_json::from_str(raw)?;
+    let top = doc.get(0).ok_or_else(|| invalid("PostgreSQL", "empty document"))?;
+    let plan = top.get("Plan").ok_or_else(|| invalid("PostgreSQL", "missing Plan"))?;
+    Ok(QueryPlan {
+        root: postgres_node(plan),
+        analyzed,
+        planning_time_ms: number(top.get("Planning Time")),
+        execution_time_ms: number(top.get("Execution Time")),
+        raw: raw.to_string(),
+    })
+}
+
+const PG_DETAIL_KEYS: &[&str] = &[
+    "Join Type",
+    "Strategy",
+    "Index Cond",
+    "Recheck Cond",
+    "Hash Cond",
+    "Merge Cond",
+    "Join Filter",
+    "Filter",
+    "Sort Key",
+    "Group Key",
+];
+
+fn postgres_node(node: &Value) -> PlanNode {
+    // Actual rows and times are averages per loop.
+    let loops = number(node.get("Actual Loops")).unwrap_or(1.0);
+    let mut details = Vec::new();
+    if let Some(index) = text(node.get("Index Name")) {
+        details.push(format!("Index: {}", index));
+    }
+    for key in PG_DETAIL_KEYS {
+        match node.get(*key) {
+            Some(Value::String(s)) => details.push(format!("{}: {}", key, s)),
+            Some(Value::Array(items)) => {
+                let items: Vec<&str> = items.iter().filter_map(Value::as_str).collect();
+                details.push(format!("{}: {}", key, items.join(", ")));
+            }
+            _ => {}
+        }
+    }
+    if let (Some(hit), Some(read)) = (
+        number(node.get("Shared Hit Blocks")),
+        number(node.get("Shared Read Blocks")),
+    ) {
+        details.push(format!("Buffers: shared hit={} read={}", hit, read));
+    }
+
+    PlanNode {
+        operation: text(node.get("Node Type")).unwrap_or_default(),
+        object: ["Relation Name", "Function Name", "CTE Name"]
+            .iter()
+            .find_map(|key| text(node.get(*key))),
+        detail: join_details(details),
+        estimated_rows: number(node.get("Plan Rows")),
+        actual_rows: number(node.get("Actual Rows")).map(|rows| rows * loops),
+        cost: number(node.get("Total Cost")),
+        actual_time_ms: number(node.get("Actual Total Time")).map(|ms| ms * loops),
+        children: children_of(node.get("Plans"), postgres_node),
+    }
+}
+
+/// `EXPLAIN FORMAT=JSON` (MySQL and MariaDB). The document nests operations
+/// by key rather than as a list, so each known key becomes a node.
+pub fn parse_mysql(raw: &str) -> Result<QueryPlan, AppError> {
+    let doc: Value = serde_json::from_str(raw)?;
+    let block = doc
+        .get("query_block")
+        .ok_or_else(|| invalid("MySQL", "missing query_block"))?;
+    Ok(QueryPlan {
+        root: mysql_block(block),
+        analyzed: false,
+        planning_time_ms: None,
+        execution_time_ms: None,
+        raw: raw.to_string(),
+    })
+}
+
+fn mysql_block(block: &Value) -> PlanNode {
+    let operation = match number(block.get("select_id")) {
+        Some(id) => format!("Query Block #{}", id),
+        None => "Query Block".to_string(),
+    };
+    PlanNode {
+        operation,
+        cost: number(block.pointer("/cost_info/query_cost")),
+        children: mysql_children(block),
+        ..Default::default()
+    }
+}
+
+fn mysql_children(object: &Value) -> Vec<PlanNode> {
+    let map = match object.as_object() {
+        Some(map) => map,
+        None => return Vec::new(),
+    };
+    let mut children = Vec::new();
+    for (key, value) in map {
+        match key.as_str() {
+            "query_block" => children.push(mysql_block(value)),
+            "table" => children.push(mysql_table(value)),
+            "nested_loop" => children.push(PlanNode {
+                operation: "Nested Loop".to_string(),
+                children: value
+                    .as_array()
+                    .map(|items| items.iter().flat_map(mysql_children).collect())
+                    .unwrap_or_default(),
+                ..Default::default()
+            }),
+            "ordering_operation" | "grouping_operation" | "duplicates_removal" | "windowing"
+            | "union_result" | "filesort" => children.push(mysql_operation(key, value)),
+            "materialized_from_subquery" => children.push(PlanNode {
+                operation: "Materialize".to_string(),
+                children: mysql_children(value),
+                ..Default::default()
+            }),
+            // Wrappers without an operation of their own.
+            "read_sorted_file" | "temporary_table" => children.extend(mysql_children(value)),
+            "query_specifications"
+            | "subqueries"
+            | "attached_subqueries"
+            | "optimized_away_subqueries"
+            | "order_by_subqueries"
+            | "group_by_subqueries"
+            | "having_subqueries"
+            | "select_list_subqueries"
+            | "update_value_subqueries" => {
+                if let Some(items) = value.as_array() {
+                    children.extend(items.iter().flat_map(mysql_children));
+                }
+            }
+            _ => {}
+        }
+    }
+    children
+}
+
+fn mysql_operation(key: &str, value: &Value) -> PlanNode {
+    let flag = |name: &str| value.get(name).and_then(Value::as_bool).unwrap_or(false);
+    let operation = match key {
+        "ordering_operation" if flag("using_filesort") => "Sort",
+        "ordering_operation" => "Order",
+        "filesort" => "Sort",
+        "grouping_operation" => "Group",
+        "duplicates_removal" => "Distinct",
+        "windowing" => "Window",
+        _ => "Union",
+    };
+    let mut details = Vec::new();
+    if flag("using_temporary_table") {
+        details.push("Using temporary table".to_string());
+    }
+    PlanNode {
+        operation: operation.to_string(),
+        object: text(value.get("table_name")),
+        detail: join_details(details),
+        children: mysql_children(value),
+        ..Default::default()
+    }
+}
+
+fn mysql_table(table: &Value) -> PlanNode {
+    let operation = match table.get("access_type").and_then(Value::as_str) {
+        Some("ALL") => "Full Table Scan",
+        Some("index") => "Full Index Scan",
+        Some("range") => "Index Range Scan",
+        Some("ref") | Some("ref_or_null") => "Non-Unique Key Lookup",
+        Some("eq_ref") => "Unique Key Lookup",
+        Some("const") | Some("system") => "Single Row Lookup",
+        Some("fulltext") => "Fulltext Index Search",
+        Some(other) => other,
+        None => "Table",
+    };
+    let mut details = Vec::new();
+    if let Some(key) = text(table.get("key")) {
+        details.push(format!("Key: {}", key));
+    }
+    if let Some(condition) = text(table.get("attached_condition")) {
+        details.push(format!("Condition: {}", condition));
+    }
+    PlanNode {
+        operation: operation.to_string(),
+        object: text(table.get("table_name")),
+        detail: join_details(details),
+        estimated_rows: number(table.get("rows_produced_per_join")),
+        cost: number(table.pointer("/cost_info/prefix_cost")),
+        children: mysql_children(table),
+        ..Default::default()
+    }
+}
+
+/// Text plans laid out like PostgreSQL's: Redshift's `EXPLAIN` and MySQL's
+/// `EXPLAIN ANALYZE` tree. Each operator line carries `(cost=...)` and, when
+/// analyzed, `(actual time=...)`; children start with `->` and are indented
+/// under their parent, and other lines are details of the operator above.
+pub fn parse_text_plan(raw: &str, analyzed: bool) -> QueryPlan {
+    let mut entries: Vec<(usize, PlanNode)> = Vec::new();
+    let mut planning_time_ms = None;
+    let mut execution_time_ms = None;
+
+    for line in raw.lines() {
+        let trimmed = line.trim();
+        // Redshift closes with "----- Tables missing statistics -----" notes.
+        if trimmed.is_empty() || trimmed.starts_with("-----") {
+            continue;
+        }
+        if let Some(ms) = trimmed.strip_prefix("Planning Time: ") {
+            planning_time_ms = ms.trim_end_matches(" ms").parse().ok();
+            continue;
+        }
+        if let Some(ms) = trimmed.strip_prefix("Execution Time: ") {
+            execution_time_ms = ms.trim_end_matches(" ms").parse().ok();
+            continue;
+        }
+        if let Some(operator) = trimmed.strip_prefix("->") {
+            let indent = line.len() - line.trim_start().len() + 1;
+            entries.push((indent, text_plan_node(operator.trim())));
+        } else if let Some((_, node)) = entries.last_mut() {
+            let detail = match node.detail.take() {
+                Some(detail) => format!("{}; {}", detail, trimmed),
+                None => trimmed.to_string(),
+            };
+            node.detail = Some(detail);
+        } else {
+            entries.push((0, text_plan_node(trimmed)));
+        }
+    }
+
+    QueryPlan {
+        root: single_root(nest_by_indent(entries), "Query Plan"),
+        analyzed,
+        planning_time_ms,
+        execution_time_ms,
+        raw: raw.to_string(),
+    }
+}
+
+/// One operator line, e.g. `Index Scan using idx on orders o  (cost=...)` or
+/// `Filter: (t.a > 1)  (cost=...) (actual time=...)`.
+fn text_plan_node(line: &str) -> PlanNode {
+    let (head, stats) = match line.find("  (") {
+        Some(i) => (&line[..i], &line[i..]),
+        None => (line, ""),
+    };
+    let mut details = Vec::new();
+    let (operation, object) = if let Some((operation, condition)) = head.split_once(": ") {
+        details.push(condition.to_string());
+        (operation, None)
+    } else if let Some((operation, target)) = head.split_once(" on ") {
+        // PostgreSQL names the index before the table, MySQL after it.
+        let operation = match operation.split_once(" using ") {
+            Some((operation, index)) => {
+                details.push(format!("Index: {}", index));
+                operation
+            }
+            None => operation,
+        };
+        let object = match target.split_once(" using ") {
+            Some((object, access)) => {
+                details.push(format!("Using {}", access));
+                object
+            }
+            None => target,
+        };
+        (operation, Some(object.to_string()))
+    } else {
+        (head, None)
+    };
+
+    let estimate = paren_fields(stats, "(cost=");
+    let actual = paren_fields(stats, "(actual ");
+    // Actual rows and times are averages per loop.
+    let loops = actual.get("loops").and_then(|v| v.parse().ok()).unwrap_or(1.0);
+    PlanNode {
+        operation: operation.trim().to_string(),
+        object,
+        detail: join_details(details),
+        estimated_rows: estimate.get("rows").and_then(|v| v.parse().ok()),
+        actual_rows: actual
+            .get("rows")
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|rows| rows * loops),
+        cost: estimate.get("cost").and_then(|v| range_end(v)),
+        actual_time_ms: actual.get("time").and_then(|v| range_end(v)).map(|ms| ms * loops),
+        children: Vec::new(),
+    }
+}
+
+/// `key=value` pairs of the parenthesised group that starts with `prefix`.
+fn paren_fields<'a>(stats: &'a str, prefix: &str) -> HashMap<&'a str, &'a str> {
+    let Some(start) = stats.find(prefix) else {
+        return HashMap::new();
+    };
+    let group = &stats[start + 1..];
+    let group = &group[..group.find(')').unwrap_or(group.len())];
+    group
+        .split_whitespace()
+        .filter_map(|field| field.split_once('='))
+        .collect()
+}
+
+/// Upper bound of a `startup..total` pair, or the value itself.
+fn range_end(value: &str) -> Option<f64> {
+    value.rsplit("..").next()?.parse().ok()
+}
+
+/// CockroachDB's `EXPLAIN` and `EXPLAIN ANALYZE` tree: `• operator` lines,
+/// nested by the column of the bullet, each followed by `key: value`
+/// properties. Properties before the first operator describe the whole plan.
+pub fn parse_cockroach(raw: &str, analyzed: bool) -> QueryPlan {
+    let mut entries: Vec<(usize, PlanNode)> = Vec::new();
+    let mut planning_time_ms = None;
+    let mut execution_time_ms = None;
+
+    for line in raw.lines() {
+        let content = line.trim_start_matches([' ', '│', '├', '└', '─']).trim_end();
+        if let Some(operator) = content.strip_prefix('•') {
+            let indent = line.chars().take_while(|c| *c != '•').count();
+            let node = PlanNode {
+                operation: operator.trim().to_string(),
+                ..Default::default()
+            };
+            entries.push((indent, node));
+            continue;
+        }
+        let Some((key, value)) = content.split_once(": ") else {
+            continue;
+        };
+        let Some((_, node)) = entries.last_mut() else {
+            match key {
+                "planning time" => planning_time_ms = duration_ms(value),
+                "execution time" => execution_time_ms = duration_ms(value),
+                _ => {}
+            }
+            continue;
+        };
+        match key {
+            "estimated row count" => node.estimated_rows = leading_count(value),
+            "actual row count" => node.actual_rows = leading_count(value),
+            "execution time" => node.actual_time_ms = duration_ms(value),
+            "table" => node.object = Some(value.to_string()),
+            _ => {
+                let detail = format!("{}: {}", key, value);
+                node.detail = Some(match node.detail.take() {
+                    Some(existing) => format!("{}; {}", existing, detail),
+                    None => detail,
+                });
+            }
+        }
+    }
+
+    QueryPlan {
+        root: single_root(nest_by_indent(entries), "Query Plan"),
+        analyzed,
+        planning_time_ms,
+        execution_time_ms,
+        raw: raw.to_string(),
+    }
+}
+
+/// `1,000 (100% of the table; ...)` -> 1000.
+fn leading_count(value: &str) -> Option<f64> {
+    value.split_whitespace().next()?.replace(',', "").parse().ok()
+}
+
+/// CockroachDB durations: `850µs`, `12ms`, `1.5s`.
+fn duration_ms(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if let Some(us) = value.strip_suffix("µs") {
+        us.parse::<f64>().ok().map(|us| us / 1000.0)
+    } else if let Some(ms) = value.strip_suffix("ms") {
+        ms.parse().ok()
+    } else if let Some(s) = value.strip_suffix('s') {
+        s.parse::<f64>().ok().map(|s| s * 1000.0)
+    } else {
+        None
+    }
+}
+
+/// `EXPLAIN QUERY PLAN` rows as `(id, parent, detail)`. SQLite reports no
+/// row estimates or costs, only the access path of each step.
+pub fn parse_sqlite(rows: &[(i64, i64, String)]) -> QueryPlan {
+    let entries = rows
+        .iter()
+        .map(|(id, parent, detail)| {
+            let parent = (*parent != 0).then(|| parent.to_string());
+            (id.to_string(), parent, sqlite_node(detail))
+        })
+        .collect::<Vec<_>>();
+    let raw = rows
+        .iter()
+        .map(|(id, parent, detail)| format!("{}|{}|{}", id, parent, detail))
+        .collect::<Vec<_>>()
+        .join("\n");
+    QueryPlan {
+        root: single_root(build_tree(entries), "Query Plan"),
+        analyzed: false,
+        planning_time_ms: None,
+        execution_time_ms: None,
+        raw,
+    }
+}
+
+fn sqlite_node(detail: &str) -> PlanNode {
+    // "SCAN t", "SEARCH t USING INDEX i (a=?)"; older versions say "SCAN TABLE t".
+    if let Some((operation @ ("SCAN" | "SEARCH"), rest)) = detail.split_once(' ') {
+        let rest = rest.strip_prefix("TABLE ").unwrap_or(rest);
+        if rest != "CONSTANT ROW" {
+            let (object, extra) = rest.split_once(' ').unwrap_or((rest, ""));
+            return PlanNode {
+                operation: operation.to_string(),
+                object: Some(object.to_string()),
+                detail: (!extra.is_empty()).then(|| extra.to_string()),
+                ..Default::default()
+            };
+        }
+    }
+    PlanNode {
+        operation: detail.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Showplan XML from `SET SHOWPLAN_XML` or `SET STATISTICS XML`, one document
+/// per statement. Each statement's `RelOp` elements form a tree; several
+/// statements hang off a `Batch` node.
+pub fn parse_mssql(documents: &[String], analyzed: bool) -> Result<QueryPlan, AppError> {
+    let statements: Vec<PlanNode> = documents.iter().flat_map(|doc| mssql_relops(doc)).collect();
+    if statements.is_empty() {
+        return Err(invalid("SQL Server", "no RelOp elements"));
+    }
+    Ok(QueryPlan {
+        root: single_root(statements, "Batch"),
+        analyzed,
+        planning_time_ms: None,
+        execution_time_ms: None,
+        raw: documents.join("\n"),
+    })
+}
+
+/// Minimal scan over the showplan tags: only `RelOp`, `Object` and the
+/// per-thread runtime counters matter, so no full XML parser is needed.
+fn mssql_relops(xml: &str) -> Vec<PlanNode> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<PlanNode> = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        let end = match tag_end(&rest[start..]) {
+            Some(len) => start + len,
+            None => break,
+        };
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        match name.trim_end_matches('/') {
+            "RelOp" => {
+                let attrs = xml_attributes(attrs);
+                let operation = attrs.get("PhysicalOp").cloned().unwrap_or_default();
+                let node = PlanNode {
+                    detail: attrs.get("LogicalOp").filter(|op| **op != operation).cloned(),
+                    operation,
+                    estimated_rows: attrs.get("EstimateRows").and_then(|v| v.parse().ok()),
+                    cost: attrs
+                        .get("EstimatedTotalSubtreeCost")
+                        .and_then(|v| v.parse().ok()),
+                    ..Default::default()
+                };
+                if tag.ends_with('/') {
+                    attach(&mut stack, &mut roots, node);
+                } else {
+                    stack.push(node);
+                }
+            }
+            "/RelOp" => {
+                if let Some(node) = stack.pop() {
+                    attach(&mut stack, &mut roots, node);
+                }
+            }
+            "Object" => {
+                if let Some(node) = stack.last_mut().filter(|node| node.object.is_none()) {
+                    let attrs = xml_attributes(attrs);
+                    let parts: Vec<&str> = ["Schema", "Table", "Index"]
+                        .iter()
+                        .filter_map(|key| attrs.get(*key).map(String::as_str))
+                        .collect();
+                    if !parts.is_empty() {
+                        node.object = Some(parts.join("."));
+                    }
+                }
+            }
+            "RunTimeCountersPerThread" => {
+                if let Some(node) = stack.last_mut() {
+                    let attrs = xml_attributes(attrs);
+                    if let Some(rows) = attrs.get("ActualRows").and_then(|v| v.parse::<f64>().ok()) {
+                        node.actual_rows = Some(node.actual_rows.unwrap_or(0.0) + rows);
+                    }
+                    // Threads run in parallel; the slowest one is the node's time.
+                    if let Some(ms) = attrs.get("ActualElapsedms").and_then(|v| v.parse::<f64>().ok()) {
+                        node.actual_time_ms = Some(node.actual_time_ms.map_or(ms, |t| t.max(ms)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    roots
+}
+
+fn attach(stack: &mut [PlanNode], roots: &mut Vec<PlanNode>, node: PlanNode) {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(node),
+        None => roots.push(node),
+    }
+}
+
+/// Offset of the `>` closing the tag that starts `s`, skipping quoted values.
+fn tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn xml_attributes(s: &str) -> HashMap<String, String> {
+    let mut attrs = HashMap::new();
+    let mut rest = s;
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim();
+        let value = rest[eq + 1..].trim_start();
+        let quote = match value.chars().next() {
+            Some(q @ ('"' | '\'')) => q,
+            _ => break,
+        };
+        let close = match value[1..].find(quote) {
+            Some(i) => i + 1,
+            None => break,
+        };
+        attrs.insert(name.to_string(), xml_unescape(&value[1..close]));
+        rest = &value[close + 1..];
+    }
+    attrs
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#xD;", "\r")
+        .replace("&#xA;", "\n")
+        .replace("&amp;", "&")
+}
+
+/// `EXPLAIN json = 1, description = 1`.
+pub fn parse_clickhouse(raw: &str) -> Result<QueryPlan, AppError> {
+    let doc: Value = serde_json::from_str(raw)?;
+    let plan = doc
+        .get(0)
+        .and_then(|top| top.get("Plan"))
+        .ok_or_else(|| invalid("ClickHouse", "missing Plan"))?;
+    Ok(QueryPlan {
+        root: clickhouse_node(plan),
+        analyzed: false,
+        planning_time_ms: None,
+        execution_time_ms: None,
+        raw: raw.to_string(),
+    })
+}
+
+fn clickhouse_node(node: &Value) -> PlanNode {
+    PlanNode {
+        operation: text(node.get("Node Type")).unwrap_or_default(),
+        detail: text(node.get("Description")),
+        children: children_of(node.get("Plans"), clickhouse_node),
+        ..Default::default()
+    }
+}
+
+/// `EXPLAIN USING JSON`. Operations are listed flat per step and point at
+/// their consumers through `parentOperators`. Snowflake gives no row estimates;
+/// the partitions and bytes a scan is assigned go into the detail.
+pub fn parse_snowflake(raw: &str) -> Result<QueryPlan, AppError> {
+    let doc: Value = serde_json::from_str(raw)?;
+    let steps = doc
+        .get("Operations")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("Snowflake", "missing Operations"))?;
+
+    let mut roots = Vec::new();
+    for step in steps {
+        let entries = step
+            .as_array()
+            .map(|ops| ops.iter().map(snowflake_entry).collect())
+            .unwrap_or_default();
+        roots.extend(build_tree(entries));
+    }
+    Ok(QueryPlan {
+        root: single_root(roots, "Query Plan"),
+        analyzed: false,
+        planning_time_ms: None,
+        execution_time_ms: None,
+        raw: raw.to_string(),
+    })
+}
+
+fn snowflake_entry(op: &Value) -> (String, Option<String>, PlanNode) {
+    let mut details = Vec::new();
+    if let Some(Value::Array(expressions)) = op.get("expressions") {
+        let expressions: Vec<&str> = expressions.iter().filter_map(Value::as_str).collect();
+        details.push(expressions.join(", "));
+    }
+    if let (Some(assigned), Some(total)) = (
+        number(op.get("partitionsAssigned")),
+        number(op.get("partitionsTotal")),
+    ) {
+        details.push(format!("Partitions: {} of {}", assigned, total));
+    }
+    if let Some(bytes) = number(op.get("bytesAssigned")) {
+        details.push(format!("Bytes assigned: {}", bytes));
+    }
+    let objects: Vec<&str> = op
+        .get("objects")
+        .and_then(Value::as_array)
+        .map(|objects| objects.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let node = PlanNode {
+        operation: text(op.get("operation")).unwrap_or_default(),
+        object: (!objects.is_empty()).then(|| objects.join(", ")),
+        detail: join_details(details),
+        ..Default::default()
+    };
+    let parent = op.pointer("/parentOperators/0").and_then(id_string);
+    (op.get("id").and_then(id_string).unwrap_or_default(), parent, node)
+}
+
+/// Stages of a finished BigQuery job (`statistics.query.queryPlan`). A stage
+/// reads the output of its `inputStages`, which become its children; the
+/// timings are the stage's wall-clock span.
+pub fn parse_bigquery(stages: &Value) -> Result<QueryPlan, AppError> {
+    let stages = stages
+        .as_array()
+        .filter(|stages| !stages.is_empty())
+        .ok_or_else(|| invalid("BigQuery", "job has no query plan"))?;
+
+    let mut consumers = HashMap::new();
+    for stage in stages {
+        if let (Some(id), Some(Value::Array(inputs))) = (stage.get("id").and_then(id_string), stage.get("inputStages")) {
+            for input in inputs.iter().filter_map(id_string) {
+                consumers.insert(input, id.clone());
+            }
+        }
+    }
+
+    let entries = stages
+        .iter()
+        .map(|stage| {
+            let id = stage.get("id").and_then(id_string).unwrap_or_default();
+            let steps: Vec<String> = stage
+                .get("steps")
+                .and_then(Value::as_array)
+                .map(|steps| {
+                    steps
+                        .iter()
+                        .map(|step| {
+                            let kind = text(step.get("kind")).unwrap_or_default();
+                            let substeps: Vec<&str> = step
+                                .get("substeps")
+                                .and_then(Value::as_array)
+                                .map(|s| s.iter().filter_map(Value::as_str).collect())
+                                .unwrap_or_default();
+                            format!("{}: {}", kind, substeps.join(", "))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let node = PlanNode {
+                operation: text(stage.get("name")).unwrap_or_else(|| format!("Stage {}", id)),
+                detail: join_details(steps),
+                actual_rows: number(stage.get("recordsWritten")),
+                actual_time_ms: number(stage.get("endMs"))
+                    .zip(number(stage.get("startMs")))
+                    .map(|(end, start)| end - start),
+                ..Default::default()
+            };
+            let parent = consumers.get(&id).cloned();
+            (id, parent, node)
+        })
+        .collect();
+
+    Ok(QueryPlan {
+        root: single_root(build_tree(entries), "Query Plan"),
+        analyzed: true,
+        planning_time_ms: None,
+        execution_time_ms: None,
+        raw: serde_json::to_string_pretty(stages)?,
+    })
+}
+
+// === Helpers ===
+
+fn invalid(engine: &str, reason: impl Display) -> AppError {
+    AppError::Serialization(format!("Unexpected {} plan format: {}", engine, reason))
+}
+
+/// Numbers arrive as JSON numbers or, for MySQL costs and BigQuery int64s, as strings.
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    value?.as_str().map(str::to_string)
+}
+
+fn id_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn children_of(plans: Option<&Value>, node: fn(&Value) -> PlanNode) -> Vec<PlanNode> {
+    plans
+        .and_then(Value::as_array)
+        .map(|plans| plans.iter().map(node).collect())
+        .unwrap_or_default()
+}
+
+fn join_details(details: Vec<String>) -> Option<String> {
+    (!details.is_empty()).then(|| details.join("; "))
+}
+
+/// The only top-level node, or a synthetic `operation` node holding several.
+fn single_root(mut nodes: Vec<PlanNode>, operation: &str) -> PlanNode {
+    if nodes.len() == 1 {
+        return nodes.remove(0);
+    }
+    PlanNode {
+        operation: operation.to_string(),
+        children: nodes,
+        ..Default::default()
+    }
+}
+
+/// Nest nodes listed in plan order with their indentation: each node is a
+/// child of the closest earlier node indented less.
+fn nest_by_indent(entries: Vec<(usize, PlanNode)>) -> Vec<PlanNode> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<(usize, PlanNode)> = Vec::new();
+    for (indent, node) in entries {
+        close_nodes(&mut stack, &mut roots, indent);
+        stack.push((indent, node));
+    }
+    close_nodes(&mut stack, &mut roots, 0);
+    roots
+}
+
+/// Pop the nodes indented at least `indent` into their parents.
+fn close_nodes(stack: &mut Vec<(usize, PlanNode)>, roots: &mut Vec<PlanNode>, indent: usize) {
+    while stack.last().is_some_and(|(open, _)| *open >= indent) {
+        if let Some((_, node)) = stack.pop() {
+            match stack.last_mut() {
+                Some((_, parent)) => parent.children.push(node),
+                None => roots.push(node),
+            }
+        }
+    }
+}
+
+/// Assemble `(id, parent, node)` entries into trees, keeping listing order.
+/// Entries whose parent is not listed become roots.
+fn build_tree(entries: Vec<(String, Option<String>, PlanNode)>) -> Vec<PlanNode> {
+    let ids: HashSet<String> = entries.iter().map(|(id, _, _)| id.clone()).collect();
+    let entries: Vec<(String, Option<String>, PlanNode)> = entries
+        .into_iter()
+        .map(|(id, parent, node)| {
+            let parent = parent.filter(|p| ids.contains(p) && *p != id);
+            (id, parent, node)
+        })
+        .collect();
+    children_in(&entries, None, 0)
+}
+
+fn children_in(
+    entries: &[(String, Option<String>, PlanNode)],
+    parent: Option<&str>,
+    depth: usize,
+) -> Vec<PlanNode> {
+    // A parent cycle can never be deeper than the number of entries.
+    if depth > entries.len() {
+        return Vec::new();
+    }
+    entries
+        .iter()
+        .filter(|(_, p, _)| p.as_deref() == parent)
+        .map(|(id, _, node)| {
+            let mut node = node.clone();
+            node.children = children_in(entries, Some(id), depth + 1);
+            node
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_postgres_analyze_scales_by_loops() {
+        let raw = r#"[{"Plan": {"Node Type": "Nested Loop", "Join Type": "Inner", "Plan Rows": 10,
+            "Total Cost": 42.5, "Actual Rows": 8, "Actual Loops": 1, "Actual Total Time": 1.5,
+            "Plans": [
+              {"Node Type": "Seq Scan", "Relation Name": "users", "Plan Rows": 10, "Total Cost": 1.1,
+               "Actual Rows": 10, "Actual Loops": 1, "Filter": "(active)"},
+              {"Node Type": "Index Scan", "Relation Name": "orders", "Index Name": "orders_user_idx",
+               "Plan Rows": 1, "Actual Rows": 2, "Actual Loops": 4, "Actual Total Time": 0.25,
+               "Shared Hit Blocks": 12, "Shared Read Blocks": 3}
+            ]},
+            "Planning Time": 0.2, "Execution Time": 1.7}]"#;
+        let plan = parse_postgres(raw, true).unwrap();
+        assert_eq!(plan.root.operation, "Nested Loop");
+        assert_eq!(plan.root.detail.as_deref(), Some("Join Type: Inner"));
+        assert_eq!(plan.execution_time_ms, Some(1.7));
+
+        let index_scan = &plan.root.children[1];
+        assert_eq!(index_scan.object.as_deref(), Some("orders"));
+        assert_eq!(index_scan.actual_rows, Some(8.0));
+        assert_eq!(index_scan.actual_time_ms, Some(1.0));
+        assert_eq!(
+            index_scan.detail.as_deref(),
+            Some("Index: orders_user_idx; Buffers: shared hit=12 read=3")
+        );
+    }
+
+    #[test]
+    fn test_mysql_nested_loop() {
+        let raw = r#"{"query_block": {"select_id": 1, "cost_info": {"query_cost": "12.50"},
+            "ordering_operation": {"using_filesort": true,
+              "nested_loop": [
+                {"table": {"table_name": "u", "access_type": "ALL", "rows_produced_per_join": 100,
+                           "cost_info": {"prefix_cost": "10.25"}}},
+                {"table": {"table_name": "o", "access_type": "ref", "key": "user_id",
+                           "attached_condition": "(o.total > 5)"}}
+              ]}}}"#;
+        let plan = parse_mysql(raw).unwrap();
+        assert_eq!(plan.root.operation, "Query Block #1");
+        assert_eq!(plan.root.cost, Some(12.5));
+
+        let sort = &plan.root.children[0];
+        assert_eq!(sort.operation, "Sort");
+        let tables = &sort.children[0].children;
+        assert_eq!(tables[0].operation, "Full Table Scan");
+        assert_eq!(tables[0].estimated_rows, Some(100.0));
+        assert_eq!(tables[0].cost, Some(10.25));
+        assert_eq!(tables[1].detail.as_deref(), Some("Key: user_id; Condition: (o.total > 5)"));
+    }
+
+    #[test]
+    fn test_text_plan_nests_by_arrow() {
+        let raw = "XN Hash Join DS_DIST_NONE  (cost=1.25..9.50 rows=10 width=8)
+  Hash Cond: (\"outer\".id = \"inner\".user_id)
+  ->  XN Seq Scan on users u  (cost=0.00..1.00 rows=100 width=4)
+        Filter: (active)
+  ->  XN Hash  (cost=0.50..0.50 rows=50 width=4)
+        ->  XN Seq Scan on orders o  (cost=0.00..0.50 rows=50 width=4)
+----- Tables missing statistics: orders -----";
+        let plan = parse_text_plan(raw, false);
+        assert_eq!(plan.root.operation, "XN Hash Join DS_DIST_NONE");
+        assert_eq!(plan.root.cost, Some(9.5));
+        assert_eq!(plan.root.detail.as_deref(), Some("Hash Cond: (\"outer\".id = \"inner\".user_id)"));
+        assert_eq!(plan.root.children.len(), 2);
+        assert_eq!(plan.root.children[0].object.as_deref(), Some("users u"));
+        assert_eq!(plan.root.children[0].detail.as_deref(), Some("Filter: (active)"));
+        assert_eq!(plan.root.children[1].children[0].estimated_rows, Some(50.0));
+
+        let raw = "-> Nested loop inner join  (cost=4.95 rows=9) (actual time=0.15..0.20 rows=9 loops=1)
+    -> Filter: (t1.a > 1)  (cost=1.20 rows=3) (actual time=0.05..0.08 rows=3 loops=1)
+        -> Table scan on t1  (cost=1.20 rows=10) (actual time=0.02..0.03 rows=10 loops=1)
+    -> Index lookup on t2 using idx_a (a=t1.a)  (cost=0.9 rows=3) (actual time=0.01..0.25 rows=3 loops=2)";
+        let plan = parse_text_plan(raw, true);
+        assert_eq!(plan.root.operation, "Nested loop inner join");
+        assert_eq!(plan.root.actual_rows, Some(9.0));
+        let filter = &plan.root.children[0];
+        assert_eq!(filter.operation, "Filter");
+        assert_eq!(filter.detail.as_deref(), Some("(t1.a > 1)"));
+        assert_eq!(filter.children[0].object.as_deref(), Some("t1"));
+        let lookup = &plan.root.children[1];
+        assert_eq!(lookup.object.as_deref(), Some("t2"));
+        assert_eq!(lookup.detail.as_deref(), Some("Using idx_a (a=t1.a)"));
+        assert_eq!(lookup.actual_rows, Some(6.0));
+        assert_eq!(lookup.actual_time_ms, Some(0.5));
+    }
+
+    #[test]
+    fn test_cockroach_tree() {
+        let raw = "planning time: 850µs
+execution time: 3ms
+distribution: local
+
+• filter
+│ estimated row count: 1,000
+│ actual row count: 3
+│ filter: a > 1
+│
+└── • scan
+      estimated row count: 3 (100% of the table; stats collected 1 minute ago)
+      actual row count: 10
+      table: t@t_pkey
+      spans: FULL SCAN";
+        let plan = parse_cockroach(raw, true);
+        assert_eq!(plan.planning_time_ms, Some(0.85));
+        assert_eq!(plan.execution_time_ms, Some(3.0));
+        assert_eq!(plan.root.operation, "filter");
+        assert_eq!(plan.root.estimated_rows, Some(1000.0));
+        assert_eq!(plan.root.detail.as_deref(), Some("filter: a > 1"));
+        let scan = &plan.root.children[0];
+        assert_eq!(scan.object.as_deref(), Some("t@t_pkey"));
+        assert_eq!(scan.actual_rows, Some(10.0));
+        assert_eq!(scan.detail.as_deref(), Some("spans: FULL SCAN"));
+    }
+
+    #[test]
+    fn test_sqlite_rows_form_tree() {
+        let rows = vec![
+            (2, 0, "SEARCH t USING INDEX t_a (a=?)".to_string()),
+            (5, 0, "SCALAR SUBQUERY 1".to_string()),
+            (7, 5, "SCAN TABLE u".to_string()),
+        ];
+        let plan = parse_sqlite(&rows);
+        assert_eq!(plan.root.operation, "Query Plan");
+        assert_eq!(plan.root.children[0].operation, "SEARCH");
+        assert_eq!(plan.root.children[0].object.as_deref(), Some("t"));
+        assert_eq!(plan.root.children[0].detail.as_deref(), Some("USING INDEX t_a (a=?)"));
+        assert_eq!(plan.root.children[1].children[0].object.as_deref(), Some("u"));
+    }
+
+    #[test]
+    fn test_mssql_showplan_xml() {
+        let xml = r#"<?xml version="1.0" encoding="utf-16"?>
+<ShowPlanXML xmlns="http://schemas.microsoft.com/sqlserver/2004/07/showplan">
+<StmtSimple StatementText="SELECT * FROM t WHERE a > 1">
+<RelOp NodeId="0" PhysicalOp="Nested Loops" LogicalOp="Inner Join" EstimateRows="4" EstimatedTotalSubtreeCost="0.5">
+<RunTimeInformation><RunTimeCountersPerThread Thread="0" ActualRows="3" ActualElapsedms="2" /></RunTimeInformation>
+<NestedLoops>
+<RelOp NodeId="1" PhysicalOp="Index Seek" LogicalOp="Index Seek" EstimateRows="4" EstimatedTotalSubtreeCost="0.1">
+<IndexScan><Object Database="[db]" Schema="[dbo]" Table="[t]" Index="[ix_a]" /></IndexScan>
+</RelOp>
+<RelOp NodeId="2" PhysicalOp="Clustered Index Seek" LogicalOp="Clustered Index Seek" EstimateRows="1" EstimatedTotalSubtreeCost="0.2" />
+</NestedLoops>
+</RelOp>
+</StmtSimple></ShowPlanXML>"#;
+        let plan = parse_mssql(&[xml.to_string()], true).unwrap();
+        assert_eq!(plan.root.operation, "Nested Loops");
+        assert_eq!(plan.root.detail.as_deref(), Some("Inner Join"));
+        assert_eq!(plan.root.actual_rows, Some(3.0));
+        assert_eq!(plan.root.children.len(), 2);
+        assert_eq!(plan.root.children[0].object.as_deref(), Some("[dbo].[t].[ix_a]"));
+        assert_eq!(plan.root.children[0].detail, None);
+        assert_eq!(plan.root.children[1].cost, Some(0.2));
+    }
+
+    #[test]
+    fn test_clickhouse_and_snowflake() {
+        let raw = r#"[{"Plan": {"Node Type": "Expression", "Description": "(Projection)",
+            "Plans": [{"Node Type": "ReadFromMergeTree", "Description": "default.hits"}]}}]"#;
+        let plan = parse_clickhouse(raw).unwrap();
+        assert_eq!(plan.root.children[0].operation, "ReadFromMergeTree");
+        assert_eq!(plan.root.children[0].detail.as_deref(), Some("default.hits"));
+
+        let raw = r#"{"GlobalStats": {"partitionsTotal": 4},
+            "Operations": [[
+              {"id": 0, "operation": "Result", "expressions": ["T.A"]},
+              {"id": 1, "parentOperators": [0], "operation": "TableScan", "objects": ["DB.PUBLIC.T"],
+               "partitionsAssigned": 2, "partitionsTotal": 4}
+            ]]}"#;
+        let plan = parse_snowflake(raw).unwrap();
+        assert_eq!(plan.root.operation, "Result");
+        assert_eq!(plan.root.children[0].object.as_deref(), Some("DB.PUBLIC.T"));
+        assert_eq!(plan.root.children[0].detail.as_deref(), Some("Partitions: 2 of 4"));
+    }
+
+    #[test]
+    fn test_bigquery_stages_link_inputs() {
+        let stages = serde_json::json!([
+            {"id": "0", "name": "S00: Input", "recordsWritten": "120", "startMs": "1000", "endMs": "1250",
+             "steps": [{"kind": "READ", "substeps": ["$1:a", "FROM t"]}]},
+            {"id": "1", "name": "S01: Output", "inputStages": ["0"], "recordsWritten": "1"}
+        ]);
+        let plan = parse_bigquery(&stages).unwrap();
+        assert_eq!(plan.root.operation, "S01: Output");
+        let input = &plan.root.children[0];
+        assert_eq!(input.actual_rows, Some(120.0));
+        assert_eq!(input.actual_time_ms, Some(250.0));
+        assert_eq!(input.detail.as_deref(), Some("READ: $1:a, FROM t"));
+    }
+}
@@ -0,0 +1,362 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::db::statement::{analyze, split_script, tokenize, TokenKind};
+use crate::db::stream::{BatchSink, StatementSink};
+use crate::db::traits::{DbDriver, DocumentDriver, GraphDriver, KeyValueDriver, SqlDriver};
+use crate::error::AppError;
+use crate::models::connection::DatabaseCategory;
+use crate::models::plan::QueryPlan;
+use crate::models::query::{CellValue, QueryResponse, StreamSummary};
+use crate::models::schema::{
+    ColumnInfo, ContainerInfo, EnumInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo,
+    KeyColumn, RoutineInfo, SchemaInfo, SequenceInfo, TableInfo, TableStats,
+};
+
+/// Redis commands that never modify the keyspace.
+const REDIS_READ_COMMANDS: &[&str] = &[
+    "BITCOUNT", "BITPOS", "DBSIZE", "DUMP", "ECHO", "EXISTS", "GET", "GETBIT", "GETRANGE",
+    "HEXISTS", "HGET", "HGETALL", "HKEYS", "HLEN", "HMGET", "HSCAN", "HSTRLEN", "HVALS", "INFO",
+    "KEYS", "LINDEX", "LLEN", "LPOS", "LRANGE", "MGET", "OBJECT", "PFCOUNT", "PING", "PTTL",
+    "RANDOMKEY", "SCAN", "SCARD", "SISMEMBER", "SMEMBERS", "SMISMEMBER", "SRANDMEMBER", "SSCAN",
+    "STRLEN", "TIME", "TTL", "TYPE", "XINFO", "XLEN", "XRANGE", "XREAD", "XREVRANGE", "ZCARD",
+    "ZCOUNT", "ZLEXCOUNT", "ZMSCORE", "ZRANGE", "ZRANGEBYLEX", "ZRANGEBYSCORE", "ZRANK",
+    "ZREVRANGE", "ZREVRANGEBYSCORE", "ZREVRANK", "ZSCAN", "ZSCORE",
+];
+
+/// Cypher clauses that create, change or remove graph data or schema.
+const CYPHER_WRITE_CLAUSES: &[&str] = &[
+    "CREATE", "MERGE", "DELETE", "DETACH", "SET", "REMOVE", "DROP", "FOREACH", "LOAD",
+];
+
+/// Whether `query` could modify data or schema when run through `execute_raw`
+/// on a driver with the given `dialect_hint`.
+pub fn is_write(query: &str, dialect: &str) -> bool {
+    match dialect {
+        "redis" => query
+            .split_whitespace()
+            .next()
+            .is_some_and(|command| !REDIS_READ_COMMANDS.contains(&command.to_uppercase().as_str())),
+        "mongodb" => {
+            // Queries are JSON commands; invalid JSON is rejected by the driver.
+            let command: serde_json::Value = match serde_json::from_str(query) {
+                Ok(command) => command,
+                Err(_) => return false,
+            };
+            match command.get("operation").and_then(|op| op.as_str()).unwrap_or("find") {
+                "find" | "count" | "countDocuments" | "distinct" => false,
+                "aggregate" => query.contains("\"$out\"") || query.contains("\"$merge\""),
+                _ => true,
+            }
+        }
+        // `execute_raw` only scans.
+        "dynamodb" => false,
+        "neo4j" => {
+            let tokens = tokenize(query, dialect);
+            tokens.iter().enumerate().any(|(i, t)| {
+                t.kind == TokenKind::Word
+                    && CYPHER_WRITE_CLAUSES.iter().any(|clause| t.is_keyword(clause))
+                    // `n.set` is a property, not a clause.
+                    && !(i > 0 && tokens[i - 1].is_punct('.'))
+            })
+        }
+        _ => split_script(query, dialect)
+            .into_iter()
+            .any(|range| !analyze(&query[range], dialect).read_only),
+    }
+}
+
+fn rejected(what: &str) -> AppError {
+    AppError::ReadOnly(format!("{} is not allowed", what))
+}
+
+/// Driver wrapper for connections configured as read-only. Anything that could
+/// write is rejected before it reaches the driver. Sessions are also opened
+/// read-only where the engine supports it; this check covers the engines and
+/// statements the server would not stop.
+pub struct ReadOnly<T: ?Sized> {
+    inner: Arc<T>,
+}
+
+impl<T: DbDriver + ?Sized> ReadOnly<T> {
+    pub fn new(inner: Arc<T>) -> Self {
+        Self { inner }
+    }
+
+    fn check(&self, query: &str) -> Result<(), AppError> {
+        if is_write(query, self.inner.dialect_hint()) {
+            return Err(rejected("A statement that modifies data"));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T: DbDriver + ?Sized> DbDriver for ReadOnly<T> {
+    fn category(&self) -> DatabaseCategory {
+        self.inner.category()
+    }
+
+    fn dialect_hint(&self) -> &'static str {
+        self.inner.dialect_hint()
+    }
+
+    async fn execute_raw(&self, query: &str) -> Result<QueryResponse, AppError> {
+        self.check(query)?;
+        self.inner.execute_raw(query).await
+    }
+
+    async fn execute_params(
+        &self,
+        query: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        self.check(query)?;
+        self.inner.execute_params(query, params).await
+    }
+
+    async fn execute_with_messages(
+        &self,
+        query: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        self.check(query)?;
+        self.inner.execute_with_messages(query, params).await
+    }
+
+    async fn execute_result_sets(&self, query: &str) -> Result<Vec<QueryResponse>, AppError> {
+        self.check(query)?;
+        self.inner.execute_result_sets(query).await
+    }
+
+    async fn explain(&self, query: &str, analyze: bool) -> Result<QueryPlan, AppError> {
+        if analyze {
+            self.check(query)?;
+        }
+        self.inner.explain(query, analyze).await
+    }
+
+    async fn execute_stream(
+        &self,
+        query: &str,
+        batch_size: usize,
+        max_rows: usize,
+        sink: BatchSink<'_>,
+    ) -> Result<StreamSummary, AppError> {
+        self.check(query)?;
+        self.inner.execute_stream(query, batch_size, max_rows, sink).await
+    }
+
+    async fn execute_script(
+        &self,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) -> Result<(), AppError> {
+        for statement in statements {
+            self.check(statement)?;
+        }
+        self.inner.execute_script(statements, sink).await
+    }
+
+    async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError> {
+        self.inner.get_containers().await
+    }
+
+    async fn get_items(&self, container: &str) -> Result<Vec<ItemInfo>, AppError> {
+        self.inner.get_items(container).await
+    }
+
+    async fn get_item_fields(&self, container: &str, item: &str) -> Result<Vec<FieldInfo>, AppError> {
+        self.inner.get_item_fields(container, item).await
+    }
+
+    async fn get_item_data(&self, container: &str, item: &str, limit: i64, offset: i64) -> Result<QueryResponse, AppError> {
+        self.inner.get_item_data(container, item, limit, offset).await
+    }
+
+    async fn get_item_count(&self, container: &str, item: &str) -> Result<i64, AppError> {
+        self.inner.get_item_count(container, item).await
+    }
+
+    async fn cancel(&self, query_id: &str) -> Result<bool, AppError> {
+        self.inner.cancel(query_id).await
+    }
+
+    async fn health_check(&self) -> Result<(), AppError> {
+        self.inner.health_check().await
+    }
+}
+
+#[async_trait]
+impl SqlDriver for ReadOnly<dyn SqlDriver> {
+    async fn get_schemas(&self) -> Result<Vec<SchemaInfo>, AppError> {
+        self.inner.get_schemas().await
+    }
+
+    async fn get_tables(&self, schema: &str) -> Result<Vec<TableInfo>, AppError> {
+        self.inner.get_tables(schema).await
+    }
+
+    async fn get_columns(&self, schema: &str, table: &str) -> Result<Vec<ColumnInfo>, AppError> {
+        self.inner.get_columns(schema, table).await
+    }
+
+    async fn get_indexes(&self, schema: &str, table: &str) -> Result<Vec<IndexInfo>, AppError> {
+        self.inner.get_indexes(schema, table).await
+    }
+
+    async fn get_foreign_keys(&self, schema: &str, table: &str) -> Result<Vec<ForeignKeyInfo>, AppError> {
+        self.inner.get_foreign_keys(schema, table).await
+    }
+
+    async fn get_table_data(&self, schema: &str, table: &str, limit: i64, offset: i64) -> Result<QueryResponse, AppError> {
+        self.inner.get_table_data(schema, table, limit, offset).await
+    }
+
+    async fn browse_columns(&self, schema: &str, table: &str) -> Result<String, AppError> {
+        self.inner.browse_columns(schema, table).await
+    }
+
+    async fn get_table_data_after(
+        &self,
+        schema: &str,
+        table: &str,
+        columns: &str,
+        key: &[KeyColumn],
+        after: Option<&[CellValue]>,
+        limit: i64,
+    ) -> Result<QueryResponse, AppError> {
+        self.inner.get_table_data_after(schema, table, columns, key, after, limit).await
+    }
+
+    async fn get_row_count(&self, schema: &str, table: &str) -> Result<i64, AppError> {
+        self.inner.get_row_count(schema, table).await
+    }
+
+    async fn update_cell(&self, _schema: &str, _table: &str, _column: &str, _value: &str, _pk_columns: Vec<String>, _pk_values: Vec<String>) -> Result<(), AppError> {
+        Err(rejected("Editing cells"))
+    }
+
+    async fn insert_row(&self, _schema: &str, _table: &str, _columns: Vec<String>, _values: Vec<String>) -> Result<(), AppError> {
+        Err(rejected("Inserting rows"))
+    }
+
+    async fn delete_rows(&self, _schema: &str, _table: &str, _pk_columns: Vec<String>, _pk_values_list: Vec<Vec<String>>) -> Result<u64, AppError> {
+        Err(rejected("Deleting rows"))
+    }
+
+    async fn get_table_stats(&self, schema: &str, table: &str) -> Result<TableStats, AppError> {
+        self.inner.get_table_stats(schema, table).await
+    }
+
+    async fn get_routines(&self, schema: &str) -> Result<Vec<RoutineInfo>, AppError> {
+        self.inner.get_routines(schema).await
+    }
+
+    async fn get_sequences(&self, schema: &str) -> Result<Vec<SequenceInfo>, AppError> {
+        self.inner.get_sequences(schema).await
+    }
+
+    async fn get_enums(&self, schema: &str) -> Result<Vec<EnumInfo>, AppError> {
+        self.inner.get_enums(schema).await
+    }
+
+    async fn begin_transaction(&self) -> Result<(), AppError> {
+        self.inner.begin_transaction().await
+    }
+
+    async fn commit_transaction(&self) -> Result<(), AppError> {
+        self.inner.commit_transaction().await
+    }
+
+    async fn rollback_transaction(&self) -> Result<(), AppError> {
+        self.inner.rollback_transaction().await
+    }
+
+    async fn in_transaction(&self) -> Result<bool, AppError> {
+        self.inner.in_transaction().await
+    }
+}
+
+#[async_trait]
+impl DocumentDriver for ReadOnly<dyn DocumentDriver> {
+    async fn insert_document(&self, _container: &str, _collection: &str, _document: serde_json::Value) -> Result<String, AppError> {
+        Err(rejected("Inserting documents"))
+    }
+
+    async fn update_document(&self, _container: &str, _collection: &str, _filter: serde_json::Value, _update: serde_json::Value) -> Result<u64, AppError> {
+        Err(rejected("Updating documents"))
+    }
+
+    async fn delete_documents(&self, _container: &str, _collection: &str, _filter: serde_json::Value) -> Result<u64, AppError> {
+        Err(rejected("Deleting documents"))
+    }
+}
+
+#[async_trait]
+impl KeyValueDriver for ReadOnly<dyn KeyValueDriver> {
+    async fn get_value(&self, key: &str) -> Result<serde_json::Value, AppError> {
+        self.inner.get_value(key).await
+    }
+
+    async fn set_value(&self, _key: &str, _value: &str, _ttl: Option<u64>) -> Result<(), AppError> {
+        Err(rejected("Setting keys"))
+    }
+
+    async fn delete_keys(&self, _keys: Vec<String>) -> Result<u64, AppError> {
+        Err(rejected("Deleting keys"))
+    }
+
+    async fn get_key_type(&self, key: &str) -> Result<String, AppError> {
+        self.inner.get_key_type(key).await
+    }
+
+    async fn scan_keys(&self, pattern: &str, count: i64) -> Result<Vec<String>, AppError> {
+        self.inner.scan_keys(pattern, count).await
+    }
+}
+
+#[async_trait]
+impl GraphDriver for ReadOnly<dyn GraphDriver> {
+    async fn get_labels(&self) -> Result<Vec<String>, AppError> {
+        self.inner.get_labels().await
+    }
+
+    async fn get_relationship_types(&self) -> Result<Vec<String>, AppError> {
+        self.inner.get_relationship_types().await
+    }
+
+    async fn get_node_properties(&self, label: &str) -> Result<Vec<String>, AppError> {
+        self.inner.get_node_properties(label).await
+    }
+
+    async fn get_nodes(&self, label: &str, limit: i64, offset: i64) -> Result<QueryResponse, AppError> {
+        self.inner.get_nodes(label, limit, offset).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sql_writes_are_detected_in_any_statement() {
+        assert!(!is_write("SELECT * FROM t", "postgres"));
+        assert!(!is_write("SET search_path = app; SELECT 1", "postgres"));
+        assert!(is_write("SELECT 1; DELETE FROM t", "postgres"));
+        assert!(is_write("WITH x AS (DELETE FROM t RETURNING *) SELECT * FROM x", "postgres"));
+        assert!(is_write("EXPLAIN ANALYZE UPDATE t SET a = 1", "postgres"));
+        assert!(!is_write("EXPLAIN UPDATE t SET a = 1", "postgres"));
+    }
+
+    #[test]
+    fn test_non_sql_writes() {
+        assert!(!is_write("hgetall user:1", "redis"));
+        assert!(is_write("FLUSHALL", "redis"));
+        assert!(!is_write(r#"{"collection": "users", "filter": {}}"#, "mongodb"));
+        assert!(is_write(r#"{"collection": "users", "operation": "deleteMany"}"#, "mongodb"));
+        assert!(!is_write("MATCH (n) WHERE n.set = 1 RETURN n", "neo4j"));
+        assert!(is_write("MATCH (n) DETACH DELETE n", "neo4j"));
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+
+use log::info;
+use sqlx::sqlite::{Sqlite, SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::{Executor, QueryBuilder};
+
+use crate::db::drivers::sqlite::SqliteDriver;
+use crate::db::keyset::quote_ident;
+use crate::db::statement::{analyze, split_script};
+use crate::db::types::cell_text;
+use crate::error::AppError;
+use crate::models::query::{CellValue, ColumnDef, QueryResponse};
+
+/// SQLite's limit on bound parameters in one statement.
+const MAX_BINDS: usize = 32766;
+
+/// Fetched results loaded into an in-memory SQLite database, one table per
+/// result named after its result id, so they can be filtered, grouped and
+/// joined without going back to the server.
+pub struct ResultSetStore {
+    /// A single connection that is never closed: each in-memory connection
+    /// has its own database, which is dropped with it.
+    pool: SqlitePool,
+}
+
+impl ResultSetStore {
+    pub async fn open() -> Result<Self, AppError> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(SqliteConnectOptions::new().in_memory(true))
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to open result set store: {}", e)))?;
+        Ok(Self { pool })
+    }
+
+    /// Load `response` as the table `result_id`, replacing any result stored
+    /// under the same id. Cells cut down to a preview are stored as the
+    /// preview text; truncated binary values are stored as NULL.
+    pub async fn store(&self, result_id: &str, response: &QueryResponse) -> Result<(), AppError> {
+        if response.columns.is_empty() {
+            return Err(AppError::InvalidConfig(
+                "Only results with columns can be stored".to_string(),
+            ));
+        }
+        let table = quote_ident("sqlite", result_id);
+        let columns: Vec<String> = column_names(&response.columns)
+            .iter()
+            .map(|name| quote_ident("sqlite", name))
+            .collect();
+        let definitions: Vec<String> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| format!("{} {}", column, column_type(&response.rows, i)))
+            .collect();
+
+        let mut tx = self.pool.begin().await?;
+        tx.execute(format!("DROP TABLE IF EXISTS {}", table).as_str())
+            .await?;
+        tx.execute(format!("CREATE TABLE {} ({})", table, definitions.join(", ")).as_str())
+            .await?;
+
+        let insert = format!("INSERT INTO {} ({}) ", table, columns.join(", "));
+        for chunk in response.rows.chunks((MAX_BINDS / columns.len()).max(1)) {
+            let mut query = QueryBuilder::<Sqlite>::new(&insert);
+            query.push_values(chunk, |mut values, row| {
+                for i in 0..columns.len() {
+                    match row.get(i).unwrap_or(&CellValue::Null) {
+                        CellValue::Null | CellValue::LargeBinary { .. } => {
+                            values.push_bind(None::<String>)
+                        }
+                        CellValue::Bool(v) => values.push_bind(*v),
+                        CellValue::Int(v) => values.push_bind(*v),
+                        CellValue::Float(v) => values.push_bind(*v),
+                        CellValue::Text(v) | CellValue::Timestamp(v) | CellValue::Json(v) => {
+                            values.push_bind(v.clone())
+                        }
+                        CellValue::LargeText { preview, .. }
+                        | CellValue::LargeJson { preview, .. } => values.push_bind(preview.clone()),
+                        CellValue::Binary(v) => values.push_bind(v.clone()),
+                        // Stored as text so decimals keep every digit.
+                        cell => values.push_bind(cell_text(cell)),
+                    };
+                }
+            });
+            query.build().execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        info!(
+            "Stored result set '{}' ({} columns, {} rows)",
+            result_id,
+            columns.len(),
+            response.rows.len()
+        );
+        Ok(())
+    }
+
+    /// Forget a stored result. Unknown ids are ignored.
+    pub async fn remove(&self, result_id: &str) -> Result<(), AppError> {
+        self.pool
+            .execute(format!("DROP TABLE IF EXISTS {}", quote_ident("sqlite", result_id)).as_str())
+            .await?;
+        Ok(())
+    }
+
+    /// Run one read-only statement over the stored results.
+    pub async fn query(&self, sql: &str) -> Result<QueryResponse, AppError> {
+        let statements = split_script(sql, "sqlite");
+        if statements.len() != 1 {
+            return Err(AppError::InvalidConfig(
+                "Result sets are queried one statement at a time".to_string(),
+            ));
+        }
+        let info = analyze(&sql[statements[0].clone()], "sqlite");
+        if !info.returns_rows || !info.read_only {
+            return Err(AppError::ReadOnly(
+                "Stored result sets can only be queried".to_string(),
+            ));
+        }
+        SqliteDriver::execute_on(&self.pool, sql, &[]).await
+    }
+}
+
+/// Column names made unique, since joins often return the same name twice
+/// and SQLite rejects duplicate columns.
+fn column_names(columns: &[ColumnDef]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    columns
+        .iter()
+        .map(|column| {
+            let mut name = column.name.clone();
+            let mut n = 1;
+            while !seen.insert(name.to_lowercase()) {
+                n += 1;
+                name = format!("{}_{}", column.name, n);
+            }
+            name
+        })
+        .collect()
+}
+
+/// Declared type for column `index`, from its first non-null value. The
+/// declared type is what the SQLite driver maps read values back by.
+fn column_type(rows: &[Vec<CellValue>], index: usize) -> &'static str {
+    let first = rows
+        .iter()
+        .filter_map(|row| row.get(index))
+        .find(|cell| !matches!(cell, CellValue::Null | CellValue::LargeBinary { .. }));
+    match first {
+        Some(CellValue::Bool(_)) => "BOOLEAN",
+        Some(CellValue::Int(_)) => "INTEGER",
+        Some(CellValue::Float(_)) => "REAL",
+        Some(CellValue::Binary(_)) => "BLOB",
+        // Read back as dates by the SQLite driver.
+        Some(CellValue::Date(_)) => "DATE",
+        Some(CellValue::DateTime { tz: None, .. }) => "DATETIME",
+        _ => "TEXT",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str) -> ColumnDef {
+        ColumnDef {
+            name: name.to_string(),
+            data_type: "text".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_column_names_are_made_unique() {
+        let names = column_names(&[column("id"), column("ID"), column("id"), column("name")]);
+        assert_eq!(names, vec!["id", "ID_2", "id_3", "name"]);
+    }
+
+    #[test]
+    fn test_column_type_skips_nulls() {
+        let rows = vec![
+            vec![CellValue::Null, CellValue::Text("a".into())],
+            vec![CellValue::Float(1.5), CellValue::Null],
+        ];
+        assert_eq!(column_type(&rows, 0), "REAL");
+        assert_eq!(column_type(&rows, 1), "TEXT");
+        assert_eq!(column_type(&[], 0), "TEXT");
+    }
+}
@@ -0,0 +1,844 @@
+use std::ops::Range;
+
+/// Lexical rules that differ between SQL dialects.
+#[derive(Debug, Clone, Copy, Default)]
+struct DialectRules {
+    /// `# comment` to end of line (MySQL, ClickHouse, BigQuery).
+    hash_comments: bool,
+    /// `/* /* */ */` nests (PostgreSQL).
+    nested_comments: bool,
+    /// Backslash escapes inside quoted strings.
+    backslash_escapes: bool,
+    /// `"..."` is a string literal rather than a quoted identifier.
+    double_quoted_strings: bool,
+    /// `` `name` `` quoted identifiers.
+    backticks: bool,
+    /// `[name]` quoted identifiers (SQL Server, SQLite).
+    brackets: bool,
+    /// `$$ ... $$` / `$tag$ ... $tag$` string bodies.
+    dollar_quotes: bool,
+    /// `'''...'''` / `"""..."""` string literals (BigQuery).
+    triple_quotes: bool,
+}
+
+impl DialectRules {
+    fn for_dialect(dialect: &str) -> Self {
+        match dialect {
+            "postgres" => Self {
+                nested_comments: true,
+                dollar_quotes: true,
+                ..Self::default()
+            },
+            "mysql" => Self {
+                hash_comments: true,
+                backslash_escapes: true,
+                double_quoted_strings: true,
+                backticks: true,
+                ..Self::default()
+            },
+            "sqlite" => Self {
+                backticks: true,
+                brackets: true,
+                ..Self::default()
+            },
+            "mssql" => Self {
+                brackets: true,
+                ..Self::default()
+            },
+            "clickhouse" => Self {
+                hash_comments: true,
+                backslash_escapes: true,
+                backticks: true,
+                ..Self::default()
+            },
+            "bigquery" => Self {
+                hash_comments: true,
+                backslash_escapes: true,
+                double_quoted_strings: true,
+                backticks: true,
+                triple_quotes: true,
+                ..Self::default()
+            },
+            "snowflake" => Self {
+                backslash_escapes: true,
+                dollar_quotes: true,
+                ..Self::default()
+            },
+            "cassandra" => Self {
+                dollar_quotes: true,
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Keyword or unquoted identifier.
+    Word,
+    QuotedIdent,
+    String,
+    Number,
+    Comment,
+    /// Any other single character (operators, parentheses, `;`, ...).
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// Byte offset of the token in the source text.
+    pub start: usize,
+}
+
+impl Token<'_> {
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    /// Whether this is an unquoted word equal (case-insensitively) to `keyword`.
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(keyword)
+    }
+
+    pub fn is_punct(&self, c: char) -> bool {
+        self.kind == TokenKind::Punct && self.text.len() == 1 && self.text.starts_with(c)
+    }
+}
+
+/// Split SQL text into tokens using the lexical rules of `dialect` (a driver's
+/// `dialect_hint`). Whitespace is dropped; comments are kept as tokens so callers
+/// can decide whether they matter. Unterminated strings and comments run to the
+/// end of the input.
+pub fn tokenize<'a>(sql: &'a str, dialect: &str) -> Vec<Token<'a>> {
+    let rules = DialectRules::for_dialect(dialect);
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        let start = i;
+        let next = bytes.get(i + 1).copied();
+
+        let kind = if b.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        } else if (b == b'-' && next == Some(b'-')) || (b == b'#' && rules.hash_comments) {
+            i = line_end(bytes, i);
+            TokenKind::Comment
+        } else if b == b'/' && next == Some(b'*') {
+            i = block_comment_end(bytes, i, rules.nested_comments);
+            TokenKind::Comment
+        } else if rules.triple_quotes
+            && (b == b'\'' || b == b'"')
+            && bytes[i..].starts_with(&[b, b, b])
+        {
+            i = triple_quoted_end(bytes, i, b);
+            TokenKind::String
+        } else if b == b'\'' {
+            i = quoted_end(bytes, i, b'\'', rules.backslash_escapes);
+            TokenKind::String
+        } else if b == b'"' {
+            if rules.double_quoted_strings {
+                i = quoted_end(bytes, i, b'"', rules.backslash_escapes);
+                TokenKind::String
+            } else {
+                i = quoted_end(bytes, i, b'"', false);
+                TokenKind::QuotedIdent
+            }
+        } else if b == b'`' && rules.backticks {
+            i = quoted_end(bytes, i, b'`', false);
+            TokenKind::QuotedIdent
+        } else if b == b'[' && rules.brackets {
+            i = quoted_end(bytes, i, b']', false);
+            TokenKind::QuotedIdent
+        } else if b == b'$' && rules.dollar_quotes {
+            match dollar_tag_len(bytes, i) {
+                Some(tag_len) => {
+                    i = dollar_quoted_end(sql, i, tag_len);
+                    TokenKind::String
+                }
+                None => {
+                    i += 1;
+                    TokenKind::Punct
+                }
+            }
+        } else if is_word_start(b) {
+            // PostgreSQL escape strings: E'...' with backslash escapes.
+            if (b == b'E' || b == b'e') && next == Some(b'\'') && dialect == "postgres" {
+                i = quoted_end(bytes, i + 1, b'\'', true);
+                TokenKind::String
+            } else {
+                i += 1;
+                while i < bytes.len()
+                    && (is_word_start(bytes[i])
+                        || bytes[i].is_ascii_digit()
+                        || (bytes[i] == b'$' && !rules.dollar_quotes))
+                {
+                    i += 1;
+                }
+                TokenKind::Word
+            }
+        } else if b.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                i += 1;
+            }
+            TokenKind::Number
+        } else {
+            i += 1;
+            TokenKind::Punct
+        };
+
+        tokens.push(Token {
+            kind,
+            text: &sql[start..i],
+            start,
+        });
+    }
+
+    tokens
+}
+
+/// Non-ASCII bytes are treated as identifier characters so that slicing only
+/// ever happens on ASCII boundaries.
+fn is_word_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b >= 0x80
+}
+
+fn line_end(bytes: &[u8], from: usize) -> usize {
+    bytes[from..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(bytes.len(), |p| from + p)
+}
+
+fn block_comment_end(bytes: &[u8], from: usize, nested: bool) -> usize {
+    let mut depth = 0;
+    let mut i = from;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'/' && bytes[i + 1] == b'*' {
+            if depth == 0 || nested {
+                depth += 1;
+            }
+            i += 2;
+        } else if bytes[i] == b'*' && bytes[i + 1] == b'/' {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+/// End of a quoted run starting at `from` (the opening quote). A doubled closing
+/// quote is an escaped quote.
+fn quoted_end(bytes: &[u8], from: usize, close: u8, backslash: bool) -> usize {
+    let mut i = from + 1;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if backslash && b == b'\\' {
+            i += 2;
+        } else if b == close {
+            if bytes.get(i + 1) == Some(&close) {
+                i += 2;
+            } else {
+                return i + 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+fn triple_quoted_end(bytes: &[u8], from: usize, quote: u8) -> usize {
+    let mut i = from + 3;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            i += 2;
+        } else if bytes[i..].starts_with(&[quote, quote, quote]) {
+            return i + 3;
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+/// Length of a `$tag$` opener at `from`, if there is one. Positional
+/// parameters such as `$1` are not tags.
+fn dollar_tag_len(bytes: &[u8], from: usize) -> Option<usize> {
+    let mut i = from + 1;
+    if i < bytes.len() && bytes[i].is_ascii_digit() {
+        return None;
+    }
+    while i < bytes.len() && (is_word_start(bytes[i]) || bytes[i].is_ascii_digit()) {
+        i += 1;
+    }
+    if bytes.get(i) == Some(&b'$') {
+        Some(i + 1 - from)
+    } else {
+        None
+    }
+}
+
+fn dollar_quoted_end(sql: &str, from: usize, tag_len: usize) -> usize {
+    let tag = &sql[from..from + tag_len];
+    let body = from + tag_len;
+    sql[body..]
+        .find(tag)
+        .map_or(sql.len(), |p| body + p + tag_len)
+}
+
+/// Byte offset just past the last meaningful token of `sql`, ignoring trailing
+/// comments and statement terminators. `&sql[..end]` is safe to embed in a
+/// subquery.
+pub fn statement_end(sql: &str, dialect: &str) -> usize {
+    tokenize(sql, dialect)
+        .iter()
+        .rev()
+        .find(|t| t.kind != TokenKind::Comment && !t.is_punct(';'))
+        .map_or(0, |t| t.end())
+}
+
+/// Words after BEGIN that make it transaction control rather than a block.
+const TRANSACTION_WORDS: &[&str] = &[
+    "TRANSACTION", "TRAN", "WORK", "DEFERRED", "IMMEDIATE", "EXCLUSIVE", "ISOLATION", "READ",
+    "DISTRIBUTED", "NAME",
+];
+
+/// Split a script into statements, returning the byte range of each without
+/// its terminator, leading comments or surrounding whitespace.
+///
+/// Statements end at `;` outside `BEGIN ... END` blocks (and Cassandra's
+/// `BEGIN BATCH ... APPLY BATCH`). MySQL scripts may switch the terminator with
+/// `DELIMITER`. SQL Server scripts are split into `GO` batches instead, since
+/// variables are scoped to a batch; `GO <n>` repeats the batch `n` times.
+pub fn split_script(sql: &str, dialect: &str) -> Vec<Range<usize>> {
+    let tokens = tokenize(sql, dialect);
+    let mut statements = Vec::new();
+    let mut current: Option<Range<usize>> = None;
+    let mut delimiter = String::from(";");
+    let mut block_depth = 0usize;
+    let mut skip_until = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.start < skip_until || token.kind == TokenKind::Comment {
+            continue;
+        }
+
+        if dialect == "mssql" {
+            if let Some(count) = go_separator(sql, token) {
+                if let Some(range) = current.take() {
+                    for _ in 0..count.max(1) {
+                        statements.push(range.clone());
+                    }
+                }
+                skip_until = line_end(sql.as_bytes(), token.end());
+            } else {
+                current = Some(current.map_or(token.start, |r| r.start)..token.end());
+            }
+            continue;
+        }
+
+        if dialect == "mysql" && current.is_none() && token.is_keyword("DELIMITER") {
+            let end = line_end(sql.as_bytes(), token.end());
+            if let Some(new) = sql[token.end()..end].split_whitespace().next() {
+                delimiter = new.to_string();
+            }
+            skip_until = end;
+            continue;
+        }
+
+        // A custom delimiter can be glued to the preceding word (`END$$`).
+        let delimiter_at = match token.kind {
+            TokenKind::Word | TokenKind::Number | TokenKind::Punct
+                if sql[token.start..].starts_with(delimiter.as_str()) =>
+            {
+                Some(token.start)
+            }
+            TokenKind::Word => token.text.find(delimiter.as_str()).map(|p| token.start + p),
+            _ => None,
+        };
+        if let Some(at) = delimiter_at.filter(|_| block_depth == 0 || delimiter != ";") {
+            if at > token.start {
+                current = Some(current.map_or(token.start, |r| r.start)..at);
+            }
+            if let Some(range) = current.take() {
+                statements.push(range);
+            }
+            block_depth = 0;
+            skip_until = at + delimiter.len();
+            continue;
+        }
+
+        // Blocks only need tracking while `;` is the terminator.
+        if delimiter == ";" && token.kind == TokenKind::Word {
+            let next = tokens[i + 1..].iter().find(|t| t.kind != TokenKind::Comment);
+            let next_word = next
+                .filter(|t| t.kind == TokenKind::Word)
+                .map(|t| t.text.to_ascii_uppercase())
+                .unwrap_or_default();
+            // The CASE of `END CASE` closes a block rather than opening one.
+            let ends_case = tokens[..i]
+                .iter()
+                .rev()
+                .find(|t| t.kind != TokenKind::Comment)
+                .is_some_and(|t| t.is_keyword("END"));
+            match token.text.to_ascii_uppercase().as_str() {
+                "BEGIN" if opens_block(dialect, current.is_none(), next, &next_word) => {
+                    block_depth += 1
+                }
+                "CASE" if block_depth > 0 && !ends_case => block_depth += 1,
+                "END" if !matches!(next_word.as_str(), "IF" | "LOOP" | "WHILE" | "REPEAT" | "FOR") => {
+                    block_depth = block_depth.saturating_sub(1)
+                }
+                "APPLY" if dialect == "cassandra" && next_word == "BATCH" => {
+                    block_depth = block_depth.saturating_sub(1)
+                }
+                _ => {}
+            }
+        }
+
+        current = Some(current.map_or(token.start, |r| r.start)..token.end());
+    }
+
+    if let Some(range) = current {
+        statements.push(range);
+    }
+    statements
+}
+
+/// Whether a BEGIN starts a block (procedure body, trigger, anonymous block)
+/// rather than a transaction.
+fn opens_block(dialect: &str, starts_statement: bool, next: Option<&Token>, next_word: &str) -> bool {
+    match next {
+        None => return false,
+        Some(t) if t.is_punct(';') => return false,
+        _ => {}
+    }
+    if TRANSACTION_WORDS.contains(&next_word) {
+        return false;
+    }
+    match dialect {
+        "cassandra" => matches!(next_word, "BATCH" | "UNLOGGED" | "COUNTER"),
+        // A leading BEGIN is always a transaction here (MariaDB's BEGIN NOT ATOMIC aside).
+        "postgres" | "mysql" | "sqlite" if starts_statement => next_word == "NOT",
+        _ => true,
+    }
+}
+
+/// `GO` or `GO <count>` alone on its line ends an SQL Server batch. Returns the
+/// repeat count.
+fn go_separator(sql: &str, token: &Token) -> Option<usize> {
+    if !token.is_keyword("GO") {
+        return None;
+    }
+    let line_start = sql[..token.start].rfind('\n').map_or(0, |p| p + 1);
+    if !sql[line_start..token.start].trim().is_empty() {
+        return None;
+    }
+    let rest = sql[token.end()..line_end(sql.as_bytes(), token.end())].trim();
+    if rest.is_empty() {
+        Some(1)
+    } else {
+        rest.parse().ok()
+    }
+}
+
+/// What a statement does, derived from its leading (or, after `WITH`, main) keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementKind {
+    /// SELECT, VALUES, TABLE.
+    Select,
+    /// INSERT, REPLACE, UPSERT.
+    Insert,
+    Update,
+    Delete,
+    Merge,
+    Create,
+    /// ALTER, RENAME, COMMENT ON.
+    Alter,
+    Drop,
+    Truncate,
+    /// SHOW, DESCRIBE, and Snowflake LIST.
+    Show,
+    Explain,
+    Pragma,
+    /// CALL, EXEC, and bare `sp_`/`xp_` procedure calls on SQL Server.
+    Call,
+    Set,
+    Use,
+    /// BEGIN, COMMIT, ROLLBACK, SAVEPOINT, ...
+    Transaction,
+    /// GRANT, REVOKE.
+    Grant,
+    /// Only whitespace and comments.
+    Empty,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatementInfo {
+    pub kind: StatementKind,
+    /// Whether executing the statement produces a result set.
+    pub returns_rows: bool,
+    /// Whether the statement leaves data and schema untouched. Session-level
+    /// statements (SET, USE, transaction control) count as read-only.
+    pub read_only: bool,
+    /// Statement starts with a `WITH` clause.
+    pub has_cte: bool,
+    /// ORDER BY outside any parentheses.
+    pub has_order_by: bool,
+    /// LIMIT, OFFSET, FETCH FIRST/NEXT or TOP outside any parentheses.
+    pub has_limit: bool,
+    /// Byte offset of the top-level ORDER BY, if any.
+    pub order_by_start: Option<usize>,
+    /// Byte offset of the main statement, after any `WITH` clause.
+    pub main_start: usize,
+}
+
+impl StatementInfo {
+    /// A plain row-returning query that can be wrapped in a subquery for
+    /// paging, counting or re-sorting.
+    pub fn is_query(&self) -> bool {
+        self.kind == StatementKind::Select && self.returns_rows
+    }
+}
+
+const MAIN_KEYWORDS: &[&str] = &[
+    "SELECT", "INSERT", "UPDATE", "DELETE", "MERGE", "VALUES", "TABLE", "REPLACE",
+];
+
+const WRITE_KEYWORDS: &[&str] = &["INSERT", "UPDATE", "DELETE", "MERGE"];
+
+/// Classify a single SQL statement.
+pub fn analyze(sql: &str, dialect: &str) -> StatementInfo {
+    let tokens: Vec<Token> = tokenize(sql, dialect)
+        .into_iter()
+        .filter(|t| t.kind != TokenKind::Comment)
+        .collect();
+    analyze_tokens(&tokens, dialect)
+}
+
+fn analyze_tokens(tokens: &[Token], dialect: &str) -> StatementInfo {
+    let mut info = StatementInfo {
+        kind: StatementKind::Empty,
+        returns_rows: false,
+        read_only: true,
+        has_cte: false,
+        has_order_by: false,
+        has_limit: false,
+        order_by_start: None,
+        main_start: 0,
+    };
+
+    // Parenthesis depth before each token.
+    let mut depths = Vec::with_capacity(tokens.len());
+    let mut depth: i32 = 0;
+    for t in tokens {
+        if t.is_punct(')') {
+            depth -= 1;
+        }
+        depths.push(depth);
+        if t.is_punct('(') {
+            depth += 1;
+        }
+    }
+
+    let lead = match tokens.iter().position(|t| t.kind == TokenKind::Word) {
+        Some(i) => i,
+        None => return info,
+    };
+    // `(SELECT ...) UNION (SELECT ...)` puts the top level outside the parens;
+    // a fully parenthesized statement has its top level inside them.
+    let top = tokens
+        .iter()
+        .zip(&depths)
+        .filter(|(t, _)| t.kind == TokenKind::Word)
+        .map(|(_, d)| *d)
+        .min()
+        .unwrap_or(0);
+    let is_top = |i: usize| depths[i] == top;
+    let keyword = |i: usize| -> String {
+        match tokens.get(i) {
+            Some(t) if t.kind == TokenKind::Word => t.text.to_ascii_uppercase(),
+            _ => String::new(),
+        }
+    };
+
+    let first = keyword(lead);
+    let mut main = lead;
+    let mut cte_writes = false;
+
+    if first == "WITH" {
+        info.has_cte = true;
+        match (lead + 1..tokens.len())
+            .find(|&i| is_top(i) && MAIN_KEYWORDS.contains(&keyword(i).as_str()))
+        {
+            Some(i) => main = i,
+            None => {
+                info.kind = StatementKind::Other;
+                info.read_only = false;
+                return info;
+            }
+        }
+        // Data-modifying CTE bodies: `name AS ( DELETE ... )`.
+        cte_writes = (lead + 1..main).any(|i| {
+            tokens[i].is_punct('(')
+                && is_top(i)
+                && matches!(keyword(i.wrapping_sub(1)).as_str(), "AS" | "MATERIALIZED")
+                && WRITE_KEYWORDS.contains(&keyword(i + 1).as_str())
+        });
+    }
+
+    info.main_start = tokens[main].start;
+
+    let mut returning = false;
+    let mut into = false;
+    let mut locking = false;
+    for (i, token) in tokens.iter().enumerate().skip(main + 1) {
+        if !is_top(i) || token.kind != TokenKind::Word {
+            continue;
+        }
+        match keyword(i).as_str() {
+            "ORDER" if keyword(i + 1) == "BY" => {
+                info.has_order_by = true;
+                info.order_by_start.get_or_insert(token.start);
+            }
+            "LIMIT" | "OFFSET" => info.has_limit = true,
+            "FETCH" if matches!(keyword(i + 1).as_str(), "FIRST" | "NEXT") => info.has_limit = true,
+            "TOP" if dialect == "mssql" => info.has_limit = true,
+            "RETURNING" => returning = true,
+            "OUTPUT" if dialect == "mssql" => returning = true,
+            "INTO" => into = true,
+            "FOR" if matches!(keyword(i + 1).as_str(), "UPDATE" | "SHARE" | "NO" | "KEY") => {
+                locking = true
+            }
+            _ => {}
+        }
+    }
+
+    let main_kw = keyword(main);
+    let (kind, returns_rows, read_only) = match main_kw.as_str() {
+        "SELECT" | "VALUES" | "TABLE" => (
+            StatementKind::Select,
+            !into,
+            !into && !locking && !cte_writes,
+        ),
+        "INSERT" | "REPLACE" | "UPSERT" => (StatementKind::Insert, returning, false),
+        "UPDATE" => (StatementKind::Update, returning, false),
+        "DELETE" => (StatementKind::Delete, returning, false),
+        "MERGE" => (StatementKind::Merge, returning, false),
+        "CREATE" => (StatementKind::Create, false, false),
+        "ALTER" | "RENAME" | "COMMENT" => (StatementKind::Alter, false, false),
+        "DROP" => (StatementKind::Drop, false, false),
+        "TRUNCATE" => (StatementKind::Truncate, false, false),
+        "SHOW" | "DESCRIBE" | "DESC" => (StatementKind::Show, true, true),
+        "LIST" | "LS" if dialect == "snowflake" => (StatementKind::Show, true, true),
+        "EXPLAIN" => {
+            // EXPLAIN ANALYZE runs the statement it explains.
+            let analyzes = tokens[main..].iter().any(|t| t.is_keyword("ANALYZE"));
+            let inner_read_only = (main + 1..tokens.len())
+                .find(|&i| MAIN_KEYWORDS.contains(&keyword(i).as_str()) || keyword(i) == "WITH")
+                .is_none_or(|i| analyze_tokens(&tokens[i..], dialect).read_only);
+            (StatementKind::Explain, true, !analyzes || inner_read_only)
+        }
+        "PRAGMA" => {
+            let assigns = tokens[main..].iter().any(|t| t.is_punct('='));
+            (StatementKind::Pragma, true, !assigns)
+        }
+        "CALL" | "EXEC" | "EXECUTE" => (StatementKind::Call, true, false),
+        "SET" => (StatementKind::Set, false, true),
+        "USE" => (StatementKind::Use, false, true),
+        "BEGIN" if dialect == "mssql"
+            && !matches!(keyword(main + 1).as_str(), "TRAN" | "TRANSACTION" | "DISTRIBUTED") =>
+        {
+            (StatementKind::Other, false, false)
+        }
+        "BEGIN" | "START" | "COMMIT" | "ROLLBACK" | "SAVEPOINT" | "RELEASE" | "END" | "ABORT" => {
+            (StatementKind::Transaction, false, true)
+        }
+        "GRANT" | "REVOKE" => (StatementKind::Grant, false, false),
+        kw if dialect == "mssql" && (kw.starts_with("SP_") || kw.starts_with("XP_")) => {
+            (StatementKind::Call, true, false)
+        }
+        _ => (StatementKind::Other, false, false),
+    };
+
+    info.kind = kind;
+    info.returns_rows = returns_rows;
+    info.read_only = read_only;
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(sql: &str, dialect: &str) -> Vec<TokenKind> {
+        tokenize(sql, dialect).iter().map(|t| t.kind).collect()
+    }
+
+    #[test]
+    fn test_tokenize_dialect_quoting() {
+        use TokenKind::*;
+        assert_eq!(kinds("SELECT $$a;b$$", "postgres"), vec![Word, String]);
+        assert_eq!(kinds("SELECT $1", "postgres"), vec![Word, Punct, Number]);
+        assert_eq!(kinds("SELECT [a b]", "mssql"), vec![Word, QuotedIdent]);
+        assert_eq!(kinds("SELECT arr[1]", "postgres"), vec![Word, Word, Punct, Number, Punct]);
+        assert_eq!(kinds("SELECT 'a\\'b'", "mysql"), vec![Word, String]);
+        assert_eq!(kinds("SELECT 1 # note", "mysql"), vec![Word, Number, Comment]);
+        assert_eq!(kinds("SELECT \"a\"", "mysql"), vec![Word, String]);
+        assert_eq!(kinds("SELECT \"a\"", "postgres"), vec![Word, QuotedIdent]);
+        assert_eq!(kinds("/* a /* b */ c */ SELECT", "postgres"), vec![Comment, Word]);
+    }
+
+    #[test]
+    fn test_leading_comments() {
+        let info = analyze("-- load users\n/* v2 */ SELECT * FROM users", "postgres");
+        assert_eq!(info.kind, StatementKind::Select);
+        assert!(info.returns_rows);
+        assert!(info.read_only);
+    }
+
+    #[test]
+    fn test_cte_with_delete_returning() {
+        let info = analyze(
+            "WITH old AS (SELECT id FROM t WHERE ts < now()) DELETE FROM t USING old WHERE t.id = old.id RETURNING t.id",
+            "postgres",
+        );
+        assert_eq!(info.kind, StatementKind::Delete);
+        assert!(info.has_cte);
+        assert!(info.returns_rows);
+        assert!(!info.read_only);
+    }
+
+    #[test]
+    fn test_cte_with_data_modifying_body() {
+        let sql = "WITH d AS (DELETE FROM t RETURNING *) SELECT * FROM d";
+        let info = analyze(sql, "postgres");
+        assert_eq!(info.kind, StatementKind::Select);
+        assert!(!info.read_only);
+        assert_eq!(&sql[info.main_start..], "SELECT * FROM d");
+    }
+
+    #[test]
+    fn test_insert_returning() {
+        let info = analyze("INSERT INTO t (a) VALUES (1) RETURNING id", "sqlite");
+        assert_eq!(info.kind, StatementKind::Insert);
+        assert!(info.returns_rows);
+        assert!(!analyze("INSERT INTO t (a) VALUES (1)", "sqlite").returns_rows);
+        assert!(analyze("INSERT INTO t OUTPUT inserted.id VALUES (1)", "mssql").returns_rows);
+    }
+
+    #[test]
+    fn test_order_by_only_at_top_level() {
+        let sub = analyze("SELECT * FROM (SELECT a FROM t ORDER BY a) x", "mssql");
+        assert!(!sub.has_order_by);
+        let window = analyze("SELECT row_number() OVER (ORDER BY a) FROM t", "mssql");
+        assert!(!window.has_order_by);
+
+        let sql = "SELECT a FROM t ORDER BY a -- 'ORDER BY' in a comment";
+        let top = analyze(sql, "mssql");
+        assert!(top.has_order_by);
+        assert_eq!(top.order_by_start, sql.find("ORDER"));
+        assert!(!analyze("SELECT 'ORDER BY' FROM t", "mssql").has_order_by);
+    }
+
+    #[test]
+    fn test_limit_detection() {
+        assert!(analyze("SELECT * FROM t LIMIT 10", "postgres").has_limit);
+        assert!(analyze("SELECT TOP 5 * FROM t", "mssql").has_limit);
+        assert!(analyze("SELECT * FROM t ORDER BY a OFFSET 0 ROWS FETCH NEXT 5 ROWS ONLY", "mssql").has_limit);
+        assert!(!analyze("SELECT * FROM t WHERE id IN (SELECT id FROM u LIMIT 5)", "postgres").has_limit);
+    }
+
+    #[test]
+    fn test_read_only_classification() {
+        assert!(!analyze("SELECT * INTO backup FROM t", "mssql").read_only);
+        assert!(!analyze("SELECT * FROM t FOR UPDATE", "postgres").read_only);
+        assert!(analyze("EXPLAIN SELECT 1", "postgres").read_only);
+        assert!(!analyze("EXPLAIN (ANALYZE, BUFFERS) DELETE FROM t", "postgres").read_only);
+        assert!(analyze("PRAGMA table_info(t)", "sqlite").read_only);
+        assert!(!analyze("PRAGMA foreign_keys = ON", "sqlite").read_only);
+        assert_eq!(analyze("DROP TABLE t", "mysql").kind, StatementKind::Drop);
+    }
+
+    #[test]
+    fn test_mssql_procedure_calls() {
+        assert_eq!(analyze("EXEC sp_who", "mssql").kind, StatementKind::Call);
+        assert_eq!(analyze("sp_helpdb", "mssql").kind, StatementKind::Call);
+        assert_eq!(analyze("BEGIN TRAN", "mssql").kind, StatementKind::Transaction);
+    }
+
+    #[test]
+    fn test_statement_end_skips_trailing_noise() {
+        let sql = "SELECT 1; -- done\n";
+        assert_eq!(&sql[..statement_end(sql, "postgres")], "SELECT 1");
+        assert_eq!(statement_end("-- nothing", "postgres"), 0);
+    }
+
+    fn split<'a>(sql: &'a str, dialect: &str) -> Vec<&'a str> {
+        split_script(sql, dialect).into_iter().map(|r| &sql[r]).collect()
+    }
+
+    #[test]
+    fn test_split_quotes_and_dollar_bodies() {
+        let sql = "SELECT ';' AS a; -- x; y\nCREATE FUNCTION f() RETURNS int AS $$ BEGIN RETURN 1; END; $$ LANGUAGE plpgsql;\n\nSELECT 2";
+        assert_eq!(
+            split(sql, "postgres"),
+            vec![
+                "SELECT ';' AS a",
+                "CREATE FUNCTION f() RETURNS int AS $$ BEGIN RETURN 1; END; $$ LANGUAGE plpgsql",
+                "SELECT 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_transaction_begin_is_not_a_block() {
+        assert_eq!(
+            split("BEGIN; UPDATE t SET a = 1; COMMIT;", "postgres"),
+            vec!["BEGIN", "UPDATE t SET a = 1", "COMMIT"]
+        );
+        assert_eq!(split("-- nothing here\n;\n", "postgres"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_split_begin_end_blocks() {
+        let sql = "CREATE PROCEDURE p() BEGIN IF x THEN SELECT 1; END IF; CASE WHEN y THEN SELECT 2; END CASE; END; SELECT 3";
+        assert_eq!(split(sql, "mysql").len(), 2);
+
+        let trigger = "CREATE TRIGGER tr AFTER INSERT ON t BEGIN UPDATE u SET n = n + 1; END;\nSELECT 1;";
+        assert_eq!(split(trigger, "sqlite").len(), 2);
+
+        let batch = "BEGIN BATCH INSERT INTO t (a) VALUES (1); INSERT INTO t (a) VALUES (2); APPLY BATCH; SELECT * FROM t";
+        assert_eq!(split(batch, "cassandra").len(), 2);
+    }
+
+    #[test]
+    fn test_split_mysql_delimiter() {
+        let sql = "DELIMITER $$\nCREATE PROCEDURE p() BEGIN SELECT 1; END$$\nDELIMITER ;\nCALL p();";
+        assert_eq!(
+            split(sql, "mysql"),
+            vec!["CREATE PROCEDURE p() BEGIN SELECT 1; END", "CALL p()"]
+        );
+    }
+
+    #[test]
+    fn test_split_mssql_go_batches() {
+        let sql = "DECLARE @x int = 1; SELECT @x\nGO\nPRINT 'GO'\n  go 2\n";
+        assert_eq!(
+            split(sql, "mssql"),
+            vec!["DECLARE @x int = 1; SELECT @x", "PRINT 'GO'", "PRINT 'GO'"]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::Instant;
+
+use crate::error::AppError;
+use crate::models::query::{CellValue, ColumnDef, QueryBatch, QueryResponse, StreamSummary};
+
+/// Callback that receives row batches produced by `DbDriver::execute_stream`.
+pub type BatchSink<'a> = &'a (dyn Fn(QueryBatch) -> Result<(), AppError> + Send + Sync);
+
+/// Callback that receives each statement's outcome from `DbDriver::execute_script`,
+/// by statement index. Returning `false` stops the script.
+pub type StatementSink<'a> =
+    &'a (dyn Fn(usize, Result<QueryResponse, AppError>) -> bool + Send + Sync);
+
+/// Accumulates rows into fixed-size batches and hands them to a sink,
+/// enforcing the row cap so drivers can stop fetching early.
+pub struct BatchCollector<'a> {
+    sink: BatchSink<'a>,
+    batch_size: usize,
+    max_rows: usize,
+    start: Instant,
+    columns: Option<Vec<ColumnDef>>,
+    pending: Vec<Vec<CellValue>>,
+    batch_index: usize,
+    total: usize,
+    truncated: bool,
+}
+
+impl<'a> BatchCollector<'a> {
+    pub fn new(sink: BatchSink<'a>, batch_size: usize, max_rows: usize) -> Self {
+        let batch_size = batch_size.max(1);
+        Self {
+            sink,
+            batch_size,
+            max_rows,
+            start: Instant::now(),
+            columns: None,
+            pending: Vec::with_capacity(batch_size),
+            batch_index: 0,
+            total: 0,
+            truncated: false,
+        }
+    }
+
+    /// Whether column definitions still need to be supplied (before the first row).
+    pub fn needs_columns(&self) -> bool {
+        self.columns.is_none() && self.batch_index == 0
+    }
+
+    pub fn set_columns(&mut self, columns: Vec<ColumnDef>) {
+        self.columns = Some(columns);
+    }
+
+    /// Add a row. Returns `Ok(false)` once the row cap has been reached, in which
+    /// case the row is discarded and the caller should stop fetching.
+    pub fn push(&mut self, row: Vec<CellValue>) -> Result<bool, AppError> {
+        if self.total >= self.max_rows {
+            self.truncated = true;
+            return Ok(false);
+        }
+        self.pending.push(row);
+        self.total += 1;
+        if self.pending.len() >= self.batch_size {
+            self.flush()?;
+        }
+        Ok(true)
+    }
+
+    fn flush(&mut self) -> Result<(), AppError> {
+        if self.pending.is_empty() && self.batch_index > 0 {
+            return Ok(());
+        }
+        let rows = std::mem::replace(&mut self.pending, Vec::with_capacity(self.batch_size));
+        let batch = QueryBatch {
+            batch_index: self.batch_index,
+            columns: self.columns.take(),
+            rows,
+        };
+        self.batch_index += 1;
+        (self.sink)(batch)
+    }
+
+    /// Flush any remaining rows and build the final summary.
+    pub fn finish(mut self) -> Result<StreamSummary, AppError> {
+        self.flush()?;
+        Ok(StreamSummary {
+            row_count: self.total,
+            execution_time_ms: self.start.elapsed().as_millis() as u64,
+            affected_rows: None,
+            truncated: self.truncated,
+            max_rows_limit: if self.truncated { Some(self.max_rows) } else { None },
+        })
+    }
+}
@@ -1,11 +1,15 @@
 use async_trait::async_trait;
 
+use crate::db::keyset::select_page;
+use crate::db::params::ParamBuilder;
+use crate::db::stream::{BatchCollector, BatchSink, StatementSink};
 use crate::error::AppError;
 use crate::models::connection::DatabaseCategory;
-use crate::models::query::QueryResponse;
+use crate::models::plan::QueryPlan;
+use crate::models::query::{CellValue, QueryResponse, StreamSummary};
 use crate::models::schema::{
     ColumnInfo, ContainerInfo, EnumInfo, FieldInfo, ForeignKeyInfo, IndexInfo, ItemInfo,
-    RoutineInfo, SchemaInfo, SequenceInfo, TableInfo, TableStats,
+    KeyColumn, RoutineInfo, SchemaInfo, SequenceInfo, TableInfo, TableStats,
 };
 
 /// Base trait implemented by all 17 database drivers.
@@ -16,6 +20,53 @@ pub trait DbDriver: Send + Sync {
 
     async fn execute_raw(&self, query: &str) -> Result<QueryResponse, AppError>;
 
+    /// Execute a query with bind parameters using the driver's native binding.
+    /// Placeholders follow the driver dialect (see `db::params::placeholder`).
+    async fn execute_params(
+        &self,
+        query: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        if params.is_empty() {
+            return self.execute_raw(query).await;
+        }
+        Err(AppError::UnsupportedOperation(
+            "Parameterized queries not supported by this driver".to_string(),
+        ))
+    }
+
+    /// `execute_params` for a statement the user ran. Drivers that have to ask
+    /// the server for its warnings separately (MySQL's `SHOW WARNINGS`) do it
+    /// here, so internal reads skip the extra round trip. Without `params` it
+    /// runs through `execute_raw`.
+    async fn execute_with_messages(
+        &self,
+        query: &str,
+        params: Vec<CellValue>,
+    ) -> Result<QueryResponse, AppError> {
+        if params.is_empty() {
+            self.execute_raw(query).await
+        } else {
+            self.execute_params(query, params).await
+        }
+    }
+
+    /// Execute a query that may produce several result sets (stored procedure
+    /// calls, multi-statement batches) and return all of them in order. The
+    /// default returns the single `execute_raw` result.
+    async fn execute_result_sets(&self, query: &str) -> Result<Vec<QueryResponse>, AppError> {
+        Ok(vec![self.execute_raw(query).await?])
+    }
+
+    /// Run the engine's EXPLAIN for `query` and parse it into a plan tree.
+    /// With `analyze` the statement is executed to collect actual rows and
+    /// timings where the engine supports it; its changes are rolled back.
+    async fn explain(&self, _query: &str, _analyze: bool) -> Result<QueryPlan, AppError> {
+        Err(AppError::UnsupportedOperation(
+            "EXPLAIN is not supported by this driver".to_string(),
+        ))
+    }
+
     async fn get_containers(&self) -> Result<Vec<ContainerInfo>, AppError>;
 
     async fn get_items(&self, container: &str) -> Result<Vec<ItemInfo>, AppError>;
@@ -36,6 +87,54 @@ pub trait DbDriver: Send + Sync {
 
     async fn get_item_count(&self, container: &str, item: &str) -> Result<i64, AppError>;
 
+    /// Execute a query and deliver its rows to `sink` in batches of `batch_size`,
+    /// stopping once `max_rows` rows have been delivered. The default buffers the
+    /// full result via `execute_raw`; drivers with cursor support override it.
+    async fn execute_stream(
+        &self,
+        query: &str,
+        batch_size: usize,
+        max_rows: usize,
+        sink: BatchSink<'_>,
+    ) -> Result<StreamSummary, AppError> {
+        let response = self.execute_raw(query).await?;
+        let mut collector = BatchCollector::new(sink, batch_size, max_rows);
+        collector.set_columns(response.columns);
+        for row in response.rows {
+            if !collector.push(row)? {
+                break;
+            }
+        }
+        let mut summary = collector.finish()?;
+        summary.execution_time_ms += response.execution_time_ms;
+        summary.affected_rows = response.affected_rows;
+        Ok(summary)
+    }
+
+    /// Run script statements in order, handing each outcome to `sink`. Drivers
+    /// with pooled connections override this to keep every statement on one
+    /// session; the default goes through `execute_raw`.
+    async fn execute_script(
+        &self,
+        statements: &[String],
+        sink: StatementSink<'_>,
+    ) -> Result<(), AppError> {
+        for (index, statement) in statements.iter().enumerate() {
+            let result = self.execute_raw(statement).await;
+            if !sink(index, result) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Abort the statement running under `query_id` on the server, using the
+    /// handle the driver recorded when it started (see `db::cancel`).
+    /// Returns `Ok(false)` when the driver has nothing to cancel.
+    async fn cancel(&self, _query_id: &str) -> Result<bool, AppError> {
+        Ok(false)
+    }
+
     /// Return the SQL dialect hint for pagination wrapping.
     fn dialect_hint(&self) -> &'static str {
         "generic"
@@ -72,6 +171,30 @@ pub trait SqlDriver: DbDriver {
         offset: i64,
     ) -> Result<QueryResponse, AppError>;
 
+    /// Select list used to browse `schema.table`. `*` unless the driver has to
+    /// convert columns it cannot read.
+    async fn browse_columns(&self, _schema: &str, _table: &str) -> Result<String, AppError> {
+        Ok("*".to_string())
+    }
+
+    /// Fetch up to `limit` rows ordered by `key`, starting after the row whose
+    /// key values are `after` (keyset pagination, see `db::keyset`). `columns`
+    /// is the select list from `browse_columns`.
+    async fn get_table_data_after(
+        &self,
+        schema: &str,
+        table: &str,
+        columns: &str,
+        key: &[KeyColumn],
+        after: Option<&[CellValue]>,
+        limit: i64,
+    ) -> Result<QueryResponse, AppError> {
+        let dialect = self.dialect_hint();
+        let mut params = ParamBuilder::new(dialect);
+        let sql = select_page(dialect, columns, schema, table, "", key, after, limit, 0, &mut params);
+        self.execute_params(&sql, params.into_params()).await
+    }
+
     async fn get_row_count(&self, schema: &str, table: &str) -> Result<i64, AppError>;
 
     async fn update_cell(
@@ -1,9 +1,371 @@
-use sqlx::mysql::MySqlRow;
-use sqlx::postgres::PgRow;
+use sqlx::mysql::{MySql, MySqlArguments, MySqlRow};
+use sqlx::postgres::types::{PgInterval, PgMoney, PgTimeTz};
+use sqlx::postgres::{PgArguments, PgRow, PgValueFormat, PgValueRef, Postgres};
+use sqlx::query::Query;
 use sqlx::{Column, Row, TypeInfo, ValueRef};
 
+use crate::db::pg_types;
+use crate::error::AppError;
 use crate::models::query::{CellValue, ColumnDef};
 
+/// Error for values that only exist as truncated previews and cannot be bound.
+pub fn unbindable_param() -> AppError {
+    AppError::InvalidConfig("Truncated values cannot be used as query parameters".to_string())
+}
+
+/// Parse a decimal rendered as text (`-12.50`, `1.5E+3`) without losing digits.
+pub fn decimal_cell(text: &str) -> Option<CellValue> {
+    let text = text.trim();
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(pos) => (&text[..pos], text[pos + 1..].parse::<i64>().ok()?),
+        None => (text, 0),
+    };
+    let (negative, mantissa) = match mantissa.as_bytes().first()? {
+        b'-' => (true, &mantissa[1..]),
+        b'+' => (false, &mantissa[1..]),
+        _ => (false, mantissa),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if int_part.is_empty() && frac_part.is_empty()
+        || !int_part.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let mut digits = format!("{}{}", int_part, frac_part);
+    let mut scale = frac_part.len() as i64 - exponent;
+    if scale < 0 {
+        digits.push_str(&"0".repeat((-scale) as usize));
+        scale = 0;
+    }
+    let digits = match digits.trim_start_matches('0') {
+        "" => "0".to_string(),
+        d if negative => format!("-{}", d),
+        d => d.to_string(),
+    };
+    Some(CellValue::Decimal {
+        digits,
+        scale: u32::try_from(scale).ok()?,
+    })
+}
+
+/// Decimal digits of a two's-complement big-endian integer of any width.
+pub fn signed_be_digits(bytes: &[u8]) -> String {
+    let negative = bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let mut magnitude: Vec<u8> = bytes.to_vec();
+    if negative {
+        // Negate: invert and add one.
+        let mut carry = true;
+        for b in magnitude.iter_mut().rev() {
+            *b = !*b;
+            if carry {
+                let (sum, overflow) = b.overflowing_add(1);
+                *b = sum;
+                carry = overflow;
+            }
+        }
+    }
+
+    let mut digits = Vec::new();
+    while magnitude.iter().any(|&b| b != 0) {
+        let mut remainder = 0u32;
+        for b in magnitude.iter_mut() {
+            let value = (remainder << 8) | *b as u32;
+            *b = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+    }
+    if digits.is_empty() {
+        digits.push(b'0');
+    } else if negative {
+        digits.push(b'-');
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap_or_default()
+}
+
+/// Inverse of `signed_be_digits`: a two's-complement big-endian
+/// encoding of an integer written in decimal.
+pub fn digits_to_signed_be(digits: &str) -> Option<Vec<u8>> {
+    let (negative, digits) = match digits.strip_prefix('-') {
+        Some(d) => (true, d),
+        None => (false, digits),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    // Little-endian magnitude while accumulating.
+    let mut bytes: Vec<u8> = vec![0];
+    for d in digits.bytes() {
+        let mut carry = (d - b'0') as u32;
+        for b in bytes.iter_mut() {
+            let value = *b as u32 * 10 + carry;
+            *b = value as u8;
+            carry = value >> 8;
+        }
+        if carry > 0 {
+            bytes.push(carry as u8);
+        }
+    }
+    // Room for the sign bit.
+    if bytes.last().is_some_and(|b| b & 0x80 != 0) {
+        bytes.push(0);
+    }
+    if negative {
+        let mut carry = true;
+        for b in bytes.iter_mut() {
+            *b = !*b;
+            if carry {
+                let (sum, overflow) = b.overflowing_add(1);
+                *b = sum;
+                carry = overflow;
+            }
+        }
+    }
+    bytes.reverse();
+    Some(bytes)
+}
+
+/// `digits * 10^-scale` written out as a plain decimal.
+pub fn decimal_text(digits: &str, scale: u32) -> String {
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(d) => ("-", d),
+        None => ("", digits),
+    };
+    let scale = scale as usize;
+    if scale == 0 {
+        return format!("{}{}", sign, digits);
+    }
+    let padded = format!("{:0>width$}", digits, width = scale + 1);
+    let (int_part, frac_part) = padded.split_at(padded.len() - scale);
+    format!("{}{}.{}", sign, int_part, frac_part)
+}
+
+pub fn date_cell(date: chrono::NaiveDate) -> CellValue {
+    CellValue::Date(date.format("%Y-%m-%d").to_string())
+}
+
+pub fn time_cell(time: chrono::NaiveTime) -> CellValue {
+    CellValue::Time(time.format("%H:%M:%S%.f").to_string())
+}
+
+/// A timestamp without time zone.
+pub fn datetime_cell(ts: chrono::NaiveDateTime) -> CellValue {
+    CellValue::DateTime {
+        tz: None,
+        micros: ts.and_utc().timestamp_micros(),
+    }
+}
+
+/// An instant, shown in the offset it was read with.
+pub fn datetime_tz_cell<Tz: chrono::TimeZone>(ts: chrono::DateTime<Tz>) -> CellValue {
+    CellValue::DateTime {
+        tz: Some(ts.fixed_offset().offset().to_string()),
+        micros: ts.timestamp_micros(),
+    }
+}
+
+/// Wall-clock time of a `CellValue::DateTime` without time zone.
+pub fn naive_datetime(micros: i64) -> Option<chrono::NaiveDateTime> {
+    chrono::DateTime::from_timestamp_micros(micros).map(|ts| ts.naive_utc())
+}
+
+/// Instant of a `CellValue::DateTime`, in the offset it should be shown in.
+pub fn zoned_datetime(
+    micros: i64,
+    tz: &str,
+) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    let offset: chrono::FixedOffset = tz.parse().ok()?;
+    chrono::DateTime::from_timestamp_micros(micros).map(|ts| ts.with_timezone(&offset))
+}
+
+/// `YYYY-MM-DD HH:MM:SS[.ffffff][+HH:MM]`.
+pub fn datetime_text(micros: i64, tz: Option<&str>) -> String {
+    let text = match tz {
+        Some(tz) => zoned_datetime(micros, tz).map(|ts| ts.format("%Y-%m-%d %H:%M:%S%.f%:z")),
+        None => naive_datetime(micros).map(|ts| ts.format("%Y-%m-%d %H:%M:%S%.f")),
+    };
+    text.map(|t| t.to_string())
+        .unwrap_or_else(|| micros.to_string())
+}
+
+/// ISO 8601 duration, e.g. `P1Y2M3DT4H5M6.5S`. Each part carries its own
+/// sign, as PostgreSQL writes and accepts them.
+pub fn interval_text(months: i32, days: i32, micros: i64) -> String {
+    let mut out = String::from("P");
+    for (value, unit) in [(months / 12, 'Y'), (months % 12, 'M'), (days, 'D')] {
+        if value != 0 {
+            out.push_str(&format!("{}{}", value, unit));
+        }
+    }
+    if micros != 0 {
+        out.push('T');
+        let (hours, minutes) = (micros / 3_600_000_000, micros / 60_000_000 % 60);
+        let seconds = micros % 60_000_000;
+        for (value, unit) in [(hours, 'H'), (minutes, 'M')] {
+            if value != 0 {
+                out.push_str(&format!("{}{}", value, unit));
+            }
+        }
+        if seconds != 0 {
+            let sign = if seconds < 0 { "-" } else { "" };
+            let (whole, frac) = (seconds.abs() / 1_000_000, seconds.abs() % 1_000_000);
+            let frac = format!(".{:06}", frac);
+            out.push_str(&format!("{}{}{}S", sign, whole, frac.trim_end_matches(['0', '.'])));
+        }
+    }
+    if out == "P" {
+        out.push_str("T0S");
+    }
+    out
+}
+
+/// PostgreSQL array literal, e.g. `{1,NULL,"a b"}`.
+pub fn array_text(items: &[CellValue]) -> String {
+    let elements: Vec<String> = items
+        .iter()
+        .map(|item| match item {
+            CellValue::Null => "NULL".to_string(),
+            CellValue::Array(inner) => array_text(inner),
+            _ => {
+                let text = cell_text(item).unwrap_or_default();
+                let plain = !text.is_empty()
+                    && !text.eq_ignore_ascii_case("NULL")
+                    && !text
+                        .chars()
+                        .any(|c| matches!(c, '{' | '}' | ',' | '"' | '\\') || c.is_whitespace());
+                if plain {
+                    text
+                } else {
+                    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+                }
+            }
+        })
+        .collect();
+    format!("{{{}}}", elements.join(","))
+}
+
+/// Text form of a value that the database accepts back as a literal of its
+/// type. `None` for NULL and for truncated previews.
+pub fn cell_text(cell: &CellValue) -> Option<String> {
+    Some(match cell {
+        CellValue::Null
+        | CellValue::LargeText { .. }
+        | CellValue::LargeJson { .. }
+        | CellValue::LargeBinary { .. } => return None,
+        CellValue::Bool(v) => v.to_string(),
+        CellValue::Int(v) => v.to_string(),
+        CellValue::Float(v) => v.to_string(),
+        CellValue::Text(v)
+        | CellValue::Timestamp(v)
+        | CellValue::Json(v)
+        | CellValue::Uuid(v)
+        | CellValue::Date(v)
+        | CellValue::Time(v)
+        | CellValue::Enum(v) => v.clone(),
+        CellValue::Binary(v) => format!(
+            "\\x{}",
+            v.iter().map(|b| format!("{:02x}", b)).collect::<String>()
+        ),
+        CellValue::Decimal { digits, scale } => decimal_text(digits, *scale),
+        CellValue::DateTime { tz, micros } => datetime_text(*micros, tz.as_deref()),
+        CellValue::Interval {
+            months,
+            days,
+            micros,
+        } => interval_text(*months, *days, *micros),
+        CellValue::Array(items) => array_text(items),
+    })
+}
+
+/// Bind a CellValue to a PostgreSQL query using the closest native type.
+pub fn pg_bind_cell<'q>(
+    query: Query<'q, Postgres, PgArguments>,
+    cell: &CellValue,
+) -> Result<Query<'q, Postgres, PgArguments>, AppError> {
+    Ok(match cell {
+        CellValue::Null => query.bind(None::<String>),
+        CellValue::Bool(v) => query.bind(*v),
+        CellValue::Int(v) => query.bind(*v),
+        CellValue::Float(v) => query.bind(*v),
+        CellValue::Text(v) => query.bind(v.clone()),
+        CellValue::Timestamp(v) => {
+            match chrono::NaiveDateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f") {
+                Ok(ts) => query.bind(ts),
+                Err(_) => query.bind(v.clone()),
+            }
+        }
+        CellValue::Binary(v) => query.bind(v.clone()),
+        CellValue::Json(v) => query.bind(
+            serde_json::from_str::<serde_json::Value>(v)
+                .unwrap_or_else(|_| serde_json::Value::String(v.clone())),
+        ),
+        CellValue::Date(v) => match chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d") {
+            Ok(date) => query.bind(date),
+            Err(_) => query.bind(v.clone()),
+        },
+        CellValue::Time(v) => match chrono::NaiveTime::parse_from_str(v, "%H:%M:%S%.f") {
+            Ok(time) => query.bind(time),
+            Err(_) => query.bind(v.clone()),
+        },
+        CellValue::DateTime { tz: None, micros } => match naive_datetime(*micros) {
+            Some(ts) => query.bind(ts),
+            None => query.bind(micros.to_string()),
+        },
+        CellValue::DateTime { tz: Some(tz), micros } => match zoned_datetime(*micros, tz) {
+            Some(ts) => query.bind(ts.with_timezone(&chrono::Utc)),
+            None => query.bind(micros.to_string()),
+        },
+        CellValue::Interval {
+            months,
+            days,
+            micros,
+        } => query.bind(PgInterval {
+            months: *months,
+            days: *days,
+            microseconds: *micros,
+        }),
+        CellValue::Decimal { .. } | CellValue::Uuid(_) | CellValue::Array(_) | CellValue::Enum(_) => {
+            query.bind(cell_text(cell))
+        }
+        CellValue::LargeText { .. } | CellValue::LargeJson { .. } | CellValue::LargeBinary { .. } => {
+            return Err(unbindable_param());
+        }
+    })
+}
+
+/// Bind a CellValue to a MySQL query using the closest native type.
+pub fn mysql_bind_cell<'q>(
+    query: Query<'q, MySql, MySqlArguments>,
+    cell: &CellValue,
+) -> Result<Query<'q, MySql, MySqlArguments>, AppError> {
+    Ok(match cell {
+        CellValue::Null => query.bind(None::<String>),
+        CellValue::Bool(v) => query.bind(*v),
+        CellValue::Int(v) => query.bind(*v),
+        CellValue::Float(v) => query.bind(*v),
+        CellValue::Text(v) | CellValue::Timestamp(v) | CellValue::Json(v) => query.bind(v.clone()),
+        CellValue::Binary(v) => query.bind(v.clone()),
+        // MySQL has no zoned timestamps; TIMESTAMP columns are read back in UTC.
+        CellValue::DateTime { micros, .. } => match naive_datetime(*micros) {
+            Some(ts) => query.bind(ts),
+            None => query.bind(micros.to_string()),
+        },
+        CellValue::Decimal { .. }
+        | CellValue::Uuid(_)
+        | CellValue::Date(_)
+        | CellValue::Time(_)
+        | CellValue::Interval { .. }
+        | CellValue::Array(_)
+        | CellValue::Enum(_) => query.bind(cell_text(cell)),
+        CellValue::LargeText { .. } | CellValue::LargeJson { .. } | CellValue::LargeBinary { .. } => {
+            return Err(unbindable_param());
+        }
+    })
+}
+
 pub fn pg_columns_to_defs(row: &PgRow) -> Vec<ColumnDef> {
     row.columns()
         .iter()
@@ -19,7 +381,8 @@ pub fn pg_row_to_cells(row: &PgRow) -> Vec<CellValue> {
     let mut cells = Vec::with_capacity(columns);
 
     for i in 0..columns {
-        let type_name = row.columns()[i].type_info().name().to_uppercase();
+        let type_info = row.columns()[i].type_info();
+        let type_name = type_info.name().to_uppercase();
 
         // Check for null first
         let raw = row.try_get_raw(i);
@@ -59,53 +422,53 @@ pub fn pg_row_to_cells(row: &PgRow) -> Vec<CellValue> {
                 Err(_) => CellValue::Null,
             },
             "TEXT" | "VARCHAR" | "CHAR" | "NAME" | "BPCHAR" | "CHAR(N)" | "CHARACTER VARYING"
-            | "CHARACTER" | "CITEXT" | "UUID" | "OID" => {
+            | "CHARACTER" | "CITEXT" => {
                 match row.try_get::<String, _>(i) {
                     Ok(v) => CellValue::Text(v),
                     Err(_) => CellValue::Null,
                 }
             }
+            "UUID" => match raw.as_ref().ok().and_then(pg_uuid) {
+                Some(v) => CellValue::Uuid(v),
+                None => CellValue::Null,
+            },
             "TIMESTAMP" | "TIMESTAMP WITHOUT TIME ZONE" => {
                 match row.try_get::<chrono::NaiveDateTime, _>(i) {
-                    Ok(v) => CellValue::Timestamp(v.format("%Y-%m-%d %H:%M:%S").to_string()),
-                    Err(_) => match row.try_get::<String, _>(i) {
-                        Ok(v) => CellValue::Timestamp(v),
-                        Err(_) => CellValue::Null,
-                    },
+                    Ok(v) => datetime_cell(v),
+                    Err(_) => pg_text(&raw, CellValue::Timestamp),
                 }
             }
             "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => {
                 match row.try_get::<chrono::DateTime<chrono::Utc>, _>(i) {
-                    Ok(v) => {
-                        CellValue::Timestamp(v.format("%Y-%m-%d %H:%M:%S %Z").to_string())
-                    }
-                    Err(_) => match row.try_get::<chrono::NaiveDateTime, _>(i) {
-                        Ok(v) => {
-                            CellValue::Timestamp(v.format("%Y-%m-%d %H:%M:%S").to_string())
-                        }
-                        Err(_) => match row.try_get::<String, _>(i) {
-                            Ok(v) => CellValue::Timestamp(v),
-                            Err(_) => CellValue::Null,
-                        },
-                    },
+                    Ok(v) => datetime_tz_cell(v),
+                    Err(_) => pg_text(&raw, CellValue::Timestamp),
                 }
             }
             "DATE" => match row.try_get::<chrono::NaiveDate, _>(i) {
-                Ok(v) => CellValue::Timestamp(v.format("%Y-%m-%d").to_string()),
-                Err(_) => match row.try_get::<String, _>(i) {
-                    Ok(v) => CellValue::Timestamp(v),
-                    Err(_) => CellValue::Null,
-                },
+                Ok(v) => date_cell(v),
+                Err(_) => pg_text(&raw, CellValue::Timestamp),
             },
             "TIME" | "TIME WITHOUT TIME ZONE" => {
                 match row.try_get::<chrono::NaiveTime, _>(i) {
-                    Ok(v) => CellValue::Timestamp(v.format("%H:%M:%S").to_string()),
-                    Err(_) => match row.try_get::<String, _>(i) {
-                        Ok(v) => CellValue::Timestamp(v),
-                        Err(_) => CellValue::Null,
-                    },
+                    Ok(v) => time_cell(v),
+                    Err(_) => pg_text(&raw, CellValue::Timestamp),
                 }
             }
+            "TIMETZ" | "TIME WITH TIME ZONE" => {
+                match row.try_get::<PgTimeTz<chrono::NaiveTime, chrono::FixedOffset>, _>(i) {
+                    Ok(v) => CellValue::Time(format!("{}{}", v.time.format("%H:%M:%S%.f"), v.offset)),
+                    Err(_) => pg_text(&raw, CellValue::Timestamp),
+                }
+            }
+            "INTERVAL" => match row.try_get::<PgInterval, _>(i) {
+                Ok(v) => CellValue::Interval {
+                    months: v.months,
+                    days: v.days,
+                    micros: v.microseconds,
+                },
+                // Unprepared queries return intervals as text only.
+                Err(_) => pg_text(&raw, CellValue::Text),
+            },
             "JSON" | "JSONB" => match row.try_get::<serde_json::Value, _>(i) {
                 Ok(v) => CellValue::Json(v.to_string()),
                 Err(_) => match row.try_get::<String, _>(i) {
@@ -117,16 +480,22 @@ pub fn pg_row_to_cells(row: &PgRow) -> Vec<CellValue> {
                 Ok(v) => CellValue::Binary(v),
                 Err(_) => CellValue::Null,
             },
-            "NUMERIC" | "DECIMAL" | "MONEY" => match row.try_get::<String, _>(i) {
-                Ok(v) => CellValue::Text(v),
-                Err(_) => match row.try_get::<f64, _>(i) {
-                    Ok(v) => CellValue::Float(v),
-                    Err(_) => CellValue::Null,
+            "NUMERIC" | "DECIMAL" => match raw.as_ref().ok().and_then(pg_numeric) {
+                Some(v) => v,
+                None => pg_text(&raw, CellValue::Text),
+            },
+            // MONEY is sent as an integer count of cents.
+            "MONEY" => match row.try_get::<PgMoney, _>(i) {
+                Ok(v) => CellValue::Decimal {
+                    digits: v.0.to_string(),
+                    scale: 2,
                 },
+                Err(_) => pg_text(&raw, CellValue::Text),
             },
-            _ => {
+            _ => match raw.as_ref().ok().and_then(|v| pg_types::decode(type_info, v)) {
+                Some(v) => v,
                 // Fallback: try to get as string
-                match row.try_get::<String, _>(i) {
+                None => match row.try_get::<String, _>(i) {
                     Ok(v) => CellValue::Text(v),
                     Err(_) => match row.try_get::<i64, _>(i) {
                         Ok(v) => CellValue::Int(v),
@@ -138,8 +507,8 @@ pub fn pg_row_to_cells(row: &PgRow) -> Vec<CellValue> {
                             },
                         },
                     },
-                }
-            }
+                },
+            },
         };
 
         cells.push(cell);
@@ -148,6 +517,38 @@ pub fn pg_row_to_cells(row: &PgRow) -> Vec<CellValue> {
     cells
 }
 
+/// The value as the server rendered it, for results fetched in text format.
+/// Binary values that could not be decoded become NULL.
+fn pg_text(
+    raw: &Result<PgValueRef<'_>, sqlx::Error>,
+    wrap: fn(String) -> CellValue,
+) -> CellValue {
+    match raw {
+        Ok(v) if v.format() == PgValueFormat::Text => match v.as_str() {
+            Ok(text) => wrap(text.to_string()),
+            Err(_) => CellValue::Null,
+        },
+        _ => CellValue::Null,
+    }
+}
+
+fn pg_uuid(value: &PgValueRef<'_>) -> Option<String> {
+    match value.format() {
+        PgValueFormat::Text => value.as_str().ok().map(str::to_string),
+        PgValueFormat::Binary => uuid::Uuid::from_slice(value.as_bytes().ok()?)
+            .ok()
+            .map(|u| u.to_string()),
+    }
+}
+
+/// NUMERIC without going through a float. NaN and infinities become text.
+fn pg_numeric(value: &PgValueRef<'_>) -> Option<CellValue> {
+    match value.format() {
+        PgValueFormat::Text => decimal_cell(value.as_str().ok()?),
+        PgValueFormat::Binary => pg_types::numeric(value.as_bytes().ok()?),
+    }
+}
+
 pub fn mysql_columns_to_defs(row: &MySqlRow) -> Vec<ColumnDef> {
     row.columns()
         .iter()
@@ -223,41 +624,51 @@ pub fn mysql_row_to_cells(row: &MySqlRow) -> Vec<CellValue> {
                 Ok(v) => CellValue::Float(v),
                 Err(_) => CellValue::Null,
             },
-            "DECIMAL" | "NUMERIC" | "DEC" | "FIXED" => match row.try_get::<String, _>(i) {
-                Ok(v) => CellValue::Text(v),
-                Err(_) => match row.try_get::<f64, _>(i) {
-                    Ok(v) => CellValue::Float(v),
+            // DECIMAL is sent as text in both protocols, but sqlx only
+            // decodes it to a `String` unchecked.
+            "DECIMAL" | "NUMERIC" | "DEC" | "FIXED" => {
+                match row.try_get_unchecked::<String, _>(i) {
+                    Ok(v) => decimal_cell(&v).unwrap_or(CellValue::Text(v)),
                     Err(_) => CellValue::Null,
-                },
-            },
-            "VARCHAR" | "TEXT" | "CHAR" | "TINYTEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM"
-            | "SET" => match row.try_get::<String, _>(i) {
-                Ok(v) => CellValue::Text(v),
+                }
+            }
+            "ENUM" => match row.try_get::<String, _>(i) {
+                Ok(v) => CellValue::Enum(v),
                 Err(_) => CellValue::Null,
             },
+            "VARCHAR" | "TEXT" | "CHAR" | "TINYTEXT" | "MEDIUMTEXT" | "LONGTEXT" | "SET" => {
+                match row.try_get::<String, _>(i) {
+                    Ok(v) => CellValue::Text(v),
+                    Err(_) => CellValue::Null,
+                }
+            }
             "DATETIME" => match row.try_get::<chrono::NaiveDateTime, _>(i) {
-                Ok(v) => CellValue::Timestamp(v.format("%Y-%m-%d %H:%M:%S").to_string()),
+                Ok(v) => datetime_cell(v),
                 Err(_) => match row.try_get::<String, _>(i) {
                     Ok(v) => CellValue::Timestamp(v),
                     Err(_) => CellValue::Null,
                 },
             },
-            "TIMESTAMP" => match row.try_get::<chrono::NaiveDateTime, _>(i) {
-                Ok(v) => CellValue::Timestamp(v.format("%Y-%m-%d %H:%M:%S").to_string()),
+            // TIMESTAMP is stored in UTC and converted to the session time zone,
+            // which the driver sets to UTC.
+            "TIMESTAMP" => match row.try_get::<chrono::DateTime<chrono::Utc>, _>(i) {
+                Ok(v) => datetime_tz_cell(v),
                 Err(_) => match row.try_get::<String, _>(i) {
                     Ok(v) => CellValue::Timestamp(v),
                     Err(_) => CellValue::Null,
                 },
             },
             "DATE" => match row.try_get::<chrono::NaiveDate, _>(i) {
-                Ok(v) => CellValue::Timestamp(v.format("%Y-%m-%d").to_string()),
+                Ok(v) => date_cell(v),
                 Err(_) => match row.try_get::<String, _>(i) {
                     Ok(v) => CellValue::Timestamp(v),
                     Err(_) => CellValue::Null,
                 },
             },
+            // TIME is a duration that can exceed a day or be negative; only
+            // times of day map to `NaiveTime`.
             "TIME" => match row.try_get::<chrono::NaiveTime, _>(i) {
-                Ok(v) => CellValue::Timestamp(v.format("%H:%M:%S").to_string()),
+                Ok(v) => time_cell(v),
                 Err(_) => match row.try_get::<String, _>(i) {
                     Ok(v) => CellValue::Timestamp(v),
                     Err(_) => CellValue::Null,
@@ -299,3 +710,78 @@ pub fn mysql_row_to_cells(row: &MySqlRow) -> Vec<CellValue> {
 
     cells
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(digits: &str, scale: u32) -> Option<(String, u32)> {
+        Some((digits.to_string(), scale))
+    }
+
+    fn parts(cell: Option<CellValue>) -> Option<(String, u32)> {
+        match cell? {
+            CellValue::Decimal { digits, scale } => Some((digits, scale)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_decimal_cell_keeps_digits() {
+        assert_eq!(parts(decimal_cell("-12.50")), decimal("-1250", 2));
+        assert_eq!(parts(decimal_cell("12345678901234567890.123")), decimal("12345678901234567890123", 3));
+        assert_eq!(parts(decimal_cell("1.5E+3")), decimal("1500", 0));
+        assert_eq!(parts(decimal_cell("15e-3")), decimal("15", 3));
+        assert_eq!(parts(decimal_cell("0.00")), decimal("0", 2));
+        assert_eq!(parts(decimal_cell("abc")), None);
+        assert_eq!(parts(decimal_cell("-")), None);
+    }
+
+    #[test]
+    fn test_decimal_text() {
+        assert_eq!(decimal_text("-1250", 2), "-12.50");
+        assert_eq!(decimal_text("15", 3), "0.015");
+        assert_eq!(decimal_text("42", 0), "42");
+    }
+
+    #[test]
+    fn test_signed_be_digits_round_trip() {
+        assert_eq!(signed_be_digits(&[0xff]), "-1");
+        assert_eq!(signed_be_digits(&[0x00, 0x80]), "128");
+        assert_eq!(signed_be_digits(&[0xff, 0x7f]), "-129");
+        assert_eq!(signed_be_digits(&[]), "0");
+        for digits in ["0", "127", "128", "-128", "-129", "170141183460469231731687303715884105728"] {
+            let bytes = digits_to_signed_be(digits).unwrap();
+            assert_eq!(signed_be_digits(&bytes), digits);
+        }
+        assert_eq!(digits_to_signed_be("12a"), None);
+    }
+
+    #[test]
+    fn test_datetime_text() {
+        let micros = 1_700_000_000_123_456;
+        assert_eq!(datetime_text(micros, None), "2023-11-14 22:13:20.123456");
+        assert_eq!(datetime_text(micros, Some("+02:00")), "2023-11-15 00:13:20.123456+02:00");
+        assert_eq!(datetime_text(0, Some("+00:00")), "1970-01-01 00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_interval_text() {
+        assert_eq!(interval_text(14, 3, 14_706_500_000), "P1Y2M3DT4H5M6.5S");
+        assert_eq!(interval_text(0, -1, -90_000_000), "P-1DT-1M-30S");
+        assert_eq!(interval_text(0, 0, 0), "PT0S");
+    }
+
+    #[test]
+    fn test_array_text_quotes_when_needed() {
+        let items = vec![
+            CellValue::Int(1),
+            CellValue::Null,
+            CellValue::Text("a b".into()),
+            CellValue::Text("NULL".into()),
+            CellValue::Text("say \"hi\"".into()),
+            CellValue::Array(vec![CellValue::Bool(true)]),
+        ];
+        assert_eq!(array_text(&items), r#"{1,NULL,"a b","NULL","say \"hi\"",{true}}"#);
+    }
+}
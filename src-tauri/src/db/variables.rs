@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::db::keyset::quote_ident;
+use crate::db::params::{param_literal, ParamBuilder};
+use crate::db::statement::{tokenize, Token, TokenKind};
+use crate::error::AppError;
+use crate::models::query::{CellValue, ParameterKind, QueryParameter};
+
+/// Dialects whose drivers have no native bind support; values are inlined as
+/// escaped literals for them instead.
+const INLINE_DIALECTS: &[&str] = &["generic", "redis", "dynamodb"];
+
+struct Placeholder<'a> {
+    name: &'a str,
+    kind: ParameterKind,
+    range: Range<usize>,
+}
+
+/// Find `:name` parameters and `${name}` variables in `sql`, skipping strings,
+/// quoted identifiers and comments. A `:` directly after a word, number, `]`,
+/// `)` or another `:` is not a parameter: that covers `::` casts, Snowflake
+/// `src:customer` paths and PostgreSQL `arr[lo:hi]` slices.
+fn placeholders<'a>(sql: &'a str, dialect: &str) -> Vec<Placeholder<'a>> {
+    let tokens = tokenize(sql, dialect);
+    let joined = |a: &Token, b: &Token| a.end() == b.start;
+    let mut found = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let t = &tokens[i];
+        if t.is_punct(':') {
+            let attached = i > 0 && joined(&tokens[i - 1], t) && {
+                let prev = &tokens[i - 1];
+                matches!(prev.kind, TokenKind::Word | TokenKind::Number)
+                    || prev.is_punct(']')
+                    || prev.is_punct(')')
+                    || prev.is_punct(':')
+            };
+            if let Some(name) = tokens.get(i + 1) {
+                if !attached && name.kind == TokenKind::Word && joined(t, name) {
+                    found.push(Placeholder {
+                        name: name.text,
+                        kind: ParameterKind::Value,
+                        range: t.start..name.end(),
+                    });
+                    i += 2;
+                    continue;
+                }
+            }
+        } else if t.is_punct('$') {
+            if let Some([open, name, close]) = tokens.get(i + 1..i + 4) {
+                if open.is_punct('{')
+                    && name.kind == TokenKind::Word
+                    && close.is_punct('}')
+                    && joined(t, open)
+                    && joined(open, name)
+                    && joined(name, close)
+                {
+                    found.push(Placeholder {
+                        name: name.text,
+                        kind: ParameterKind::Identifier,
+                        range: t.start..close.end(),
+                    });
+                    i += 4;
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    found
+}
+
+/// Named placeholders in `sql`, in order of first appearance.
+pub fn describe(sql: &str, dialect: &str) -> Vec<QueryParameter> {
+    let mut params: Vec<QueryParameter> = Vec::new();
+    for p in placeholders(sql, dialect) {
+        match params.iter_mut().find(|q| q.name == p.name && q.kind == p.kind) {
+            Some(existing) => existing.occurrences += 1,
+            None => params.push(QueryParameter {
+                name: p.name.to_string(),
+                kind: p.kind,
+                occurrences: 1,
+            }),
+        }
+    }
+    params
+}
+
+/// Rewrite `sql` for execution: `:name` becomes a native placeholder with its
+/// value in the returned list (or an inlined literal on drivers that cannot
+/// bind), and `${name}` is replaced by the value quoted as an identifier.
+pub fn bind(
+    sql: &str,
+    dialect: &'static str,
+    values: &HashMap<String, CellValue>,
+) -> Result<(String, Vec<CellValue>), AppError> {
+    let inline = INLINE_DIALECTS.contains(&dialect);
+    let mut params = ParamBuilder::new(dialect);
+    let mut out = String::with_capacity(sql.len());
+    let mut last = 0;
+
+    for p in placeholders(sql, dialect) {
+        let value = values.get(p.name).ok_or_else(|| {
+            AppError::InvalidConfig(format!("No value supplied for parameter '{}'", p.name))
+        })?;
+        out.push_str(&sql[last..p.range.start]);
+        match p.kind {
+            ParameterKind::Value if inline => out.push_str(&param_literal(value, dialect)?),
+            ParameterKind::Value => out.push_str(&params.push(value.clone())),
+            ParameterKind::Identifier => match value {
+                CellValue::Text(name) => out.push_str(&quote_ident(dialect, name)),
+                _ => {
+                    return Err(AppError::InvalidConfig(format!(
+                        "Variable '{}' must be a text value",
+                        p.name
+                    )));
+                }
+            },
+        }
+        last = p.range.end;
+    }
+
+    out.push_str(&sql[last..]);
+    Ok((out, params.into_params()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, CellValue)]) -> HashMap<String, CellValue> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    #[test]
+    fn test_describe_skips_strings_comments_and_casts() {
+        let sql = "SELECT ':a', id::text FROM ${tenant}.users -- :b\n\
+                   WHERE id = :user_id OR parent = :user_id /* ${c} */";
+        let params = describe(sql, "postgres");
+        assert_eq!(
+            params,
+            vec![
+                QueryParameter {
+                    name: "tenant".into(),
+                    kind: ParameterKind::Identifier,
+                    occurrences: 1,
+                },
+                QueryParameter {
+                    name: "user_id".into(),
+                    kind: ParameterKind::Value,
+                    occurrences: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_describe_skips_paths_and_slices() {
+        let sql = "SELECT src:customer, (v):name FROM t WHERE id = :id";
+        let params = describe(sql, "snowflake");
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].name, "id");
+
+        let sql = "SELECT arr[lo:hi], arr[1:n], arr[f(x):hi] FROM t WHERE lo = :lo";
+        let params = describe(sql, "postgres");
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].name, "lo");
+    }
+
+    #[test]
+    fn test_bind_uses_native_placeholders() {
+        let vals = values(&[
+            ("tenant", CellValue::Text("acme".into())),
+            ("id", CellValue::Int(7)),
+        ]);
+        let sql = "SELECT * FROM ${tenant}.users WHERE id = :id OR owner = :id";
+
+        let (pg, params) = bind(sql, "postgres", &vals).unwrap();
+        assert_eq!(pg, "SELECT * FROM \"acme\".users WHERE id = $1 OR owner = $2");
+        assert_eq!(params.len(), 2);
+
+        let (my, _) = bind(sql, "mysql", &vals).unwrap();
+        assert_eq!(my, "SELECT * FROM `acme`.users WHERE id = ? OR owner = ?");
+    }
+
+    #[test]
+    fn test_bind_inlines_without_native_binds() {
+        let vals = values(&[("name", CellValue::Text("O'Brien".into()))]);
+        let (sql, params) = bind("SELECT * FROM t WHERE name = :name", "generic", &vals).unwrap();
+        assert_eq!(sql, "SELECT * FROM t WHERE name = 'O''Brien'");
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_bind_rejects_missing_and_non_text_identifiers() {
+        assert!(bind("SELECT :missing", "postgres", &HashMap::new()).is_err());
+        let vals = values(&[("t", CellValue::Int(1))]);
+        assert!(bind("SELECT * FROM ${t}", "postgres", &vals).is_err());
+    }
+}
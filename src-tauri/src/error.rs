@@ -23,6 +23,18 @@ pub enum AppError {
     #[error("Query cancelled")]
     QueryCancelled,
 
+    #[error("Read-only connection: {0}")]
+    ReadOnly(String),
+
+    /// A destructive statement was held back; re-submitting it with `token`
+    /// runs it (see `db::guardrails`).
+    #[error("Confirmation required: {summary}")]
+    ConfirmationRequired {
+        summary: String,
+        estimated_rows: Option<u64>,
+        token: String,
+    },
+
     #[allow(dead_code)]
     #[error("Failed to connect to {db_type} at {host}: {cause}")]
     ConnectionFailed {
@@ -52,6 +64,8 @@ impl AppError {
             AppError::UnsupportedOperation(_) => "UNSUPPORTED_OPERATION",
             AppError::QueryTimeout(_) => "QUERY_TIMEOUT",
             AppError::QueryCancelled => "QUERY_CANCELLED",
+            AppError::ReadOnly(_) => "READ_ONLY",
+            AppError::ConfirmationRequired { .. } => "CONFIRMATION_REQUIRED",
             AppError::ConnectionFailed { .. } => "CONNECTION_FAILED",
             AppError::SshTunnel(_) => "SSH_TUNNEL_ERROR",
             AppError::Keychain(_) => "KEYCHAIN_ERROR",
@@ -66,9 +80,19 @@ impl Serialize for AppError {
         S: serde::Serializer,
     {
         use serde::ser::SerializeMap;
-        let mut map = serializer.serialize_map(Some(2))?;
+        let mut map = serializer.serialize_map(None)?;
         map.serialize_entry("code", self.error_code())?;
         map.serialize_entry("message", &self.to_string())?;
+        if let AppError::ConfirmationRequired {
+            summary,
+            estimated_rows,
+            token,
+        } = self
+        {
+            map.serialize_entry("summary", summary)?;
+            map.serialize_entry("estimated_rows", estimated_rows)?;
+            map.serialize_entry("token", token)?;
+        }
         map.end()
     }
 }
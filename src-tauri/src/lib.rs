@@ -3,12 +3,20 @@ mod db;
 mod error;
 mod models;
 
+use db::audit::AuditLog;
 use db::cancel::CancellationRegistry;
+use db::guardrails::Guardrails;
+use db::history::QueryHistory;
+use db::jobs::JobManager;
 use db::pool::PoolManager;
+use db::result_sets::ResultSetStore;
 use db::tunnel::TunnelManager;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    db::messages::install(None);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
@@ -17,8 +25,20 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .manage(PoolManager::new())
+        .manage(JobManager::new())
         .manage(CancellationRegistry::new())
         .manage(TunnelManager::new())
+        .manage(Guardrails::new())
+        .setup(|app| {
+            let data_dir = app.path().app_data_dir()?;
+            std::fs::create_dir_all(&data_dir)?;
+            let history =
+                tauri::async_runtime::block_on(QueryHistory::open(&data_dir.join("history.db")))?;
+            app.manage(history);
+            app.manage(tauri::async_runtime::block_on(ResultSetStore::open())?);
+            app.manage(AuditLog::new(data_dir.join("audit")));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Connection management
             commands::connection::connect_db,
@@ -27,10 +47,34 @@ pub fn run() {
             commands::connection::ping_connection,
             // Query execution
             commands::query::execute_query,
+            commands::query::execute_query_stream,
+            commands::query::execute_query_multi,
+            commands::query::execute_query_result_sets,
+            commands::query::explain_query,
+            commands::query::execute_query_params,
+            commands::query::describe_parameters,
+            commands::query::execute_query_named,
+            commands::query::execute_script,
             commands::query::execute_query_page,
             commands::query::count_query_rows,
             commands::query::fetch_full_cell,
             commands::query::cancel_query,
+            commands::benchmark::benchmark_query,
+            // Local SQL over fetched results
+            commands::result_sets::store_result_set,
+            commands::result_sets::query_result_set,
+            commands::result_sets::drop_result_set,
+            // Query history
+            commands::history::search_history,
+            commands::history::pin_history_entry,
+            commands::history::purge_history,
+            // Audit log
+            commands::audit::export_audit_log,
+            // Background query jobs
+            commands::jobs::submit_query_job,
+            commands::jobs::list_jobs,
+            commands::jobs::get_job_result,
+            commands::jobs::cancel_job,
             // Generic schema browsing (all databases)
             commands::schema::get_database_category,
             commands::schema::get_containers,
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::query::CellValue;
+
+/// One line of the audit log: a write made through the app.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// RFC 3339 UTC timestamp with milliseconds.
+    pub timestamp: String,
+    pub connection_id: String,
+    pub connection_name: String,
+    /// Command that made the write, e.g. `update_cell` or `execute_query`.
+    pub operation: String,
+    /// Table, collection or key the write targeted, when known.
+    pub target: Option<String>,
+    /// SQL statement, or the filter/update document for non-SQL stores.
+    pub statement: Option<String>,
+    pub affected_rows: Option<u64>,
+    /// Previous value of an edited cell or key.
+    pub old_value: Option<CellValue>,
+    pub new_value: Option<CellValue>,
+    /// Set when the write failed; failed attempts are logged too.
+    pub error: Option<String>,
+}
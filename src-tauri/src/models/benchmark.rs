@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// Latency distribution of the measured runs, in milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// Outcome of `benchmark_query`. Warm-up runs are excluded from every figure.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub iterations: usize,
+    pub warmup: usize,
+    pub concurrency: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Latencies of the successful runs; absent when none succeeded.
+    pub latency: Option<LatencyStats>,
+    /// Rows returned (or affected) by the last successful run.
+    pub rows_returned: Option<u64>,
+    /// Distinct error messages, at most ten.
+    pub errors: Vec<String>,
+    pub total_time_ms: u64,
+    /// The run was stopped through `cancel_query` before all iterations ran.
+    pub cancelled: bool,
+}
+
+/// Payload of `benchmark-progress` events, emitted while a benchmark runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkProgress {
+    pub connection_id: String,
+    pub query_id: Option<String>,
+    /// Runs finished so far, warm-up included.
+    pub completed: usize,
+    pub total: usize,
+    pub failed: usize,
+    pub warming_up: bool,
+}
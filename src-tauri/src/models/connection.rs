@@ -37,6 +37,17 @@ pub enum CloudAuth {
     AwsCredentials { access_key: String, secret_key: String, region: String },
 }
 
+/// Which statements are held back until the user confirms them.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum GuardrailLevel {
+    Off,
+    /// Unfiltered UPDATE/DELETE, DROP, TRUNCATE, ALTER and bulk key/document deletes.
+    #[default]
+    Standard,
+    /// Everything in `Standard` plus any other statement that writes.
+    Strict,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionConfig {
     pub id: String,
@@ -110,6 +121,24 @@ pub struct ConnectionConfig {
     pub pool_idle_timeout_secs: u64,
     #[serde(default = "default_acquire_timeout")]
     pub pool_acquire_timeout_secs: u64,
+    // Reject writes and open sessions read-only where the engine supports it
+    #[serde(default)]
+    pub read_only: bool,
+    // Server-side limits set on every new session; None keeps the server default
+    #[serde(default)]
+    pub statement_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub lock_timeout_ms: Option<u64>,
+    // Session setup run on every new physical connection, after the default schema is selected
+    #[serde(default)]
+    pub init_sql: Vec<String>,
+    #[serde(default)]
+    pub default_schema: Option<String>,
+    // Free-form labels; "production" tightens the default guardrails
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub guardrails: Option<GuardrailLevel>,
 }
 
 fn default_pool_size() -> u32 {
@@ -162,6 +191,21 @@ impl ConnectionConfig {
         self.database.as_deref().unwrap_or("")
     }
 
+    /// The configured guardrail level, or `Strict` for connections tagged
+    /// "production". Read-only connections reject writes outright instead.
+    pub fn guardrail_level(&self) -> GuardrailLevel {
+        if self.read_only {
+            return GuardrailLevel::Off;
+        }
+        self.guardrails.unwrap_or_else(|| {
+            if self.tags.iter().any(|t| t.eq_ignore_ascii_case("production")) {
+                GuardrailLevel::Strict
+            } else {
+                GuardrailLevel::Standard
+            }
+        })
+    }
+
     pub fn to_connection_url(&self) -> String {
         match self.db_type {
             DatabaseType::PostgreSQL | DatabaseType::CockroachDB | DatabaseType::Redshift => {
@@ -184,6 +228,11 @@ impl ConnectionConfig {
                 if let Some(ref key) = self.ssl_client_key {
                     url.push_str(&format!("&sslkey={}", key));
                 }
+                // Redshift rejects the startup option; its writes are only
+                // blocked by the statement check.
+                if self.read_only && self.db_type != DatabaseType::Redshift {
+                    url.push_str("&options=-c%20default_transaction_read_only%3Don");
+                }
                 url
             }
             DatabaseType::MySQL | DatabaseType::MariaDB => {
@@ -216,7 +265,11 @@ impl ConnectionConfig {
             }
             DatabaseType::SQLite => {
                 if let Some(ref path) = self.file_path {
-                    format!("sqlite:{}", path)
+                    if self.read_only {
+                        format!("sqlite:{}?mode=ro", path)
+                    } else {
+                        format!("sqlite:{}", path)
+                    }
                 } else {
                     "sqlite::memory:".to_string()
                 }
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// One statement recorded in the local query history database.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub connection_id: String,
+    pub sql: String,
+    pub duration_ms: u64,
+    /// Rows returned, or rows affected for statements without a result set.
+    pub row_count: Option<u64>,
+    /// `AppError::error_code` of a failed statement.
+    pub error_code: Option<String>,
+    pub error_message: Option<String>,
+    /// Unix timestamp in milliseconds.
+    pub executed_at: i64,
+    pub pinned: bool,
+}
+
+/// Criteria for `search_history`. Every field is optional; an empty filter
+/// returns the most recent entries with pinned ones first.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryFilter {
+    /// Full-text search over the SQL; each word matches as a prefix.
+    pub text: Option<String>,
+    pub connection_id: Option<String>,
+    pub pinned_only: bool,
+    pub errors_only: bool,
+    /// Inclusive lower bound on `executed_at`, in Unix milliseconds.
+    pub since: Option<i64>,
+    /// Exclusive upper bound on `executed_at`, in Unix milliseconds.
+    pub until: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
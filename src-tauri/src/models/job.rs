@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Snapshot of a background query job, returned by `list_jobs` and emitted as
+/// `query-job` events whenever the job changes state and periodically while
+/// it runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub id: String,
+    pub connection_id: String,
+    pub sql: String,
+    pub status: JobStatus,
+    /// Unix timestamp in milliseconds.
+    pub submitted_at: i64,
+    pub elapsed_ms: u64,
+    /// Rows in the stored result, once the job has completed.
+    pub row_count: Option<usize>,
+    pub error: Option<String>,
+}
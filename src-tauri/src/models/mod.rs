@@ -1,6 +1,11 @@
+pub mod audit;
 pub mod backup;
+pub mod benchmark;
 pub mod connection;
 pub mod dump;
 pub mod export;
+pub mod history;
+pub mod job;
+pub mod plan;
 pub mod query;
 pub mod schema;
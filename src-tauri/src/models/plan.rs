@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// One operator of an execution plan. Every engine's EXPLAIN output is mapped
+/// onto this shape so the frontend can render a single plan tree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanNode {
+    /// Operator name as the engine reports it, e.g. `Seq Scan`, `Hash Match`.
+    pub operation: String,
+    /// Table, index or CTE the operator reads, when there is one.
+    pub object: Option<String>,
+    /// Conditions, access paths and other engine-specific details.
+    pub detail: Option<String>,
+    pub estimated_rows: Option<f64>,
+    /// Rows produced across all loops; only set for analyzed plans.
+    pub actual_rows: Option<f64>,
+    /// Planner cost of the subtree, in the engine's own units.
+    pub cost: Option<f64>,
+    /// Time spent in the subtree, in milliseconds; only set for analyzed plans.
+    pub actual_time_ms: Option<f64>,
+    pub children: Vec<PlanNode>,
+}
+
+/// Parsed result of `explain_query`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryPlan {
+    pub root: PlanNode,
+    /// Whether the statement was executed to collect actual rows and timings.
+    pub analyzed: bool,
+    pub planning_time_ms: Option<f64>,
+    pub execution_time_ms: Option<f64>,
+    /// Plan as returned by the server (JSON, XML or text rows).
+    pub raw: String,
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::models::schema::KeyColumn;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "value")]
 pub enum CellValue {
@@ -8,9 +10,27 @@ pub enum CellValue {
     Int(i64),
     Float(f64),
     Text(String),
+    /// Date or time only available as text, in whatever form the server
+    /// rendered it.
     Timestamp(String),
     Binary(Vec<u8>),
     Json(String),
+    /// Exact decimal: `digits` is the unscaled integer (with a leading `-`
+    /// when negative), so the value is `digits * 10^-scale`.
+    Decimal { digits: String, scale: u32 },
+    Uuid(String),
+    /// `YYYY-MM-DD`.
+    Date(String),
+    /// `HH:MM:SS[.ffffff]`, with a `+HH:MM` suffix for zoned times.
+    Time(String),
+    /// Microseconds since the Unix epoch. With `tz` (a `+HH:MM` offset) the
+    /// instant is in UTC and is shown in that offset; without it the value is
+    /// a wall-clock time read as if it were UTC.
+    DateTime { tz: Option<String>, micros: i64 },
+    Interval { months: i32, days: i32, micros: i64 },
+    Array(Vec<CellValue>),
+    /// Label of an enumerated type.
+    Enum(String),
     LargeText { preview: String, full_length: usize },
     LargeJson { preview: String, full_length: usize },
     LargeBinary { preview_length: usize, full_length: usize },
@@ -33,6 +53,146 @@ pub struct QueryResponse {
     pub truncated: bool,
     #[serde(default)]
     pub max_rows_limit: Option<usize>,
+    /// Notices, warnings and PRINT output raised while the statement ran.
+    #[serde(default)]
+    pub messages: Vec<ServerMessage>,
+}
+
+/// A non-row message reported by the server for a statement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerMessage {
+    /// "error", "warning", "notice", "info" or "debug".
+    pub severity: String,
+    /// Vendor error/warning code, when the driver reports one.
+    pub code: Option<String>,
+    pub text: String,
+}
+
+/// One page of table data. `key` is the key the table is paged by, resolved
+/// on the first page; the frontend sends it back with later pages so they
+/// skip the catalogue lookup. Empty when the table has no usable key, absent
+/// when the page was not read by key. `select_list` is the driver's
+/// `browse_columns` for keyset pages and is sent back the same way.
+#[derive(Debug, Clone, Serialize)]
+pub struct TablePage {
+    #[serde(flatten)]
+    pub response: QueryResponse,
+    pub key: Option<Vec<KeyColumn>>,
+    pub select_list: Option<String>,
+}
+
+/// A slice of rows pushed to the frontend while a query is still streaming.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryBatch {
+    pub batch_index: usize,
+    /// Column definitions, only present on the first batch.
+    pub columns: Option<Vec<ColumnDef>>,
+    pub rows: Vec<Vec<CellValue>>,
+}
+
+/// Totals reported once a streamed query has finished.
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamSummary {
+    pub row_count: usize,
+    pub execution_time_ms: u64,
+    pub affected_rows: Option<u64>,
+    pub truncated: bool,
+    pub max_rows_limit: Option<usize>,
+}
+
+/// Events sent over the channel passed to `execute_query_stream`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum QueryStreamEvent {
+    Batch(QueryBatch),
+    Finished(StreamSummary),
+}
+
+/// Location of a script statement in the submitted text. Offsets are in UTF-16
+/// code units so they map directly onto editor positions; `line` is 1-based.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceSpan {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementError {
+    pub code: String,
+    pub message: String,
+}
+
+/// Outcome of one statement of a script run by `execute_script`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptStatementResult {
+    pub index: usize,
+    pub span: SourceSpan,
+    pub response: Option<QueryResponse>,
+    pub error: Option<StatementError>,
+}
+
+/// Totals reported once a script has finished.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptSummary {
+    pub statement_count: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Statements not run because the script stopped at an error.
+    pub skipped: usize,
+    pub execution_time_ms: u64,
+}
+
+/// Events sent over the channel passed to `execute_script`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum ScriptEvent {
+    Statement(ScriptStatementResult),
+    Finished(ScriptSummary),
+}
+
+/// Outcome of `execute_query_multi` on one connection.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionQueryResult {
+    pub connection_id: String,
+    /// Omitted when the rows were merged into `MultiQueryResponse::merged`.
+    pub response: Option<QueryResponse>,
+    pub error: Option<StatementError>,
+    /// Set when guardrails held the statement back on this connection;
+    /// re-submit it under this connection id in `confirm_tokens` to run it.
+    pub confirm_token: Option<String>,
+    pub execution_time_ms: u64,
+}
+
+/// Result of running one query across several connections.
+#[derive(Debug, Clone, Serialize)]
+pub struct MultiQueryResponse {
+    /// Rows of every successful connection behind a leading `_connection`
+    /// column, when merging was requested.
+    pub merged: Option<QueryResponse>,
+    /// One entry per connection, in the order the ids were given.
+    pub results: Vec<ConnectionQueryResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub execution_time_ms: u64,
+}
+
+/// How a named placeholder in a query is filled in at execution time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ParameterKind {
+    /// `:name`, bound as a parameter.
+    Value,
+    /// `${name}`, substituted as a quoted identifier.
+    Identifier,
+}
+
+/// A named placeholder found in a query by `describe_parameters`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryParameter {
+    pub name: String,
+    pub kind: ParameterKind,
+    /// Times the placeholder appears in the query.
+    pub occurrences: usize,
 }
 
 #[derive(Debug, Clone, Deserialize)]
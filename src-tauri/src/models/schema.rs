@@ -51,6 +51,14 @@ pub struct ColumnInfo {
     pub ordinal_position: i32,
 }
 
+/// A column of the key a table is paged by, with its declared type (used for
+/// the casts PostgreSQL needs on text binds).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyColumn {
+    pub name: String,
+    pub data_type: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexInfo {
     pub name: String,
@@ -0,0 +1,67 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { captureError } from '$lib/services/sentryService';
+
+export interface LatencyStats {
+  min_ms: number;
+  max_ms: number;
+  mean_ms: number;
+  p50_ms: number;
+  p95_ms: number;
+  p99_ms: number;
+}
+
+export interface BenchmarkResult {
+  iterations: number;
+  warmup: number;
+  concurrency: number;
+  succeeded: number;
+  failed: number;
+  latency: LatencyStats | null;
+  rows_returned: number | null;
+  errors: string[];
+  total_time_ms: number;
+  cancelled: boolean;
+}
+
+export interface BenchmarkProgress {
+  connection_id: string;
+  query_id: string | null;
+  completed: number;
+  total: number;
+  failed: number;
+  warming_up: boolean;
+}
+
+export async function benchmarkQuery(
+  connectionId: string,
+  sql: string,
+  iterations: number,
+  warmup?: number,
+  concurrency?: number,
+  timeoutSecs?: number,
+  queryId?: string,
+  confirmToken?: string,
+): Promise<BenchmarkResult> {
+  try {
+    return await invoke<BenchmarkResult>('benchmark_query', {
+      connectionId,
+      sql,
+      iterations,
+      warmup: warmup ?? null,
+      concurrency: concurrency ?? null,
+      timeoutSecs: timeoutSecs ?? null,
+      queryId: queryId ?? null,
+      confirmToken: confirmToken ?? null,
+    });
+  } catch (error) {
+    captureError(error, { command: 'benchmark_query' });
+    throw error;
+  }
+}
+
+export function onBenchmarkProgress(callback: (progress: BenchmarkProgress) => void): Promise<() => void> {
+  return listen<BenchmarkProgress>('benchmark-progress', (event) => {
+    callback(event.payload);
+  });
+}
@@ -0,0 +1,63 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { captureError } from '$lib/services/sentryService';
+import type { QueryResponse } from '$lib/types/query';
+
+export type JobStatus = 'Running' | 'Completed' | 'Failed' | 'Cancelled';
+
+export interface JobInfo {
+  id: string;
+  connection_id: string;
+  sql: string;
+  status: JobStatus;
+  submitted_at: number;
+  elapsed_ms: number;
+  row_count: number | null;
+  error: string | null;
+}
+
+export async function submitQueryJob(
+  connectionId: string,
+  sql: string,
+  timeoutSecs?: number,
+  maxRows?: number,
+  maxCellSize?: number,
+  confirmToken?: string,
+): Promise<JobInfo> {
+  try {
+    return await invoke<JobInfo>('submit_query_job', {
+      connectionId,
+      sql,
+      timeoutSecs: timeoutSecs ?? null,
+      maxRows: maxRows ?? null,
+      maxCellSize: maxCellSize ?? null,
+      confirmToken: confirmToken ?? null,
+    });
+  } catch (error) {
+    captureError(error, { command: 'submit_query_job' });
+    throw error;
+  }
+}
+
+export async function listJobs(): Promise<JobInfo[]> {
+  return invoke<JobInfo[]>('list_jobs');
+}
+
+export async function getJobResult(jobId: string): Promise<QueryResponse> {
+  try {
+    return await invoke<QueryResponse>('get_job_result', { jobId });
+  } catch (error) {
+    captureError(error, { command: 'get_job_result' });
+    throw error;
+  }
+}
+
+export async function cancelJob(jobId: string): Promise<boolean> {
+  return invoke<boolean>('cancel_job', { jobId });
+}
+
+export function onJobUpdate(callback: (job: JobInfo) => void): Promise<() => void> {
+  return listen<JobInfo>('query-job', (event) => {
+    callback(event.payload);
+  });
+}
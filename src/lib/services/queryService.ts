@@ -1,40 +1,51 @@
 import * as tauri from '$lib/services/tauri';
-import type { QueryResponse, MultiStatementResult, SortColumn, CellValue } from '$lib/types/query';
+import type { QueryResponse, MultiStatementResult, SortColumn, CellValue, ConfirmationRequired } from '$lib/types/query';
 import { uiStore } from '$lib/stores/ui.svelte';
 import { queryHistoryStore } from '$lib/stores/queryHistory.svelte';
 import { settingsStore } from '$lib/stores/settings.svelte';
 import { errorMessage } from '$lib/utils/formatters';
 
+export function isConfirmationRequired(err: unknown): err is ConfirmationRequired {
+  return !!err && typeof err === 'object' && (err as { code?: unknown }).code === 'CONFIRMATION_REQUIRED';
+}
+
+/**
+ * Run `run`; if the backend guardrails hold the statement back, ask the user
+ * and re-run it with the confirmation token. Declining rethrows the error.
+ * Callers that already asked pass `confirmed`; with "Confirm Before Delete"
+ * turned off the statement is re-run without asking.
+ */
+export async function withConfirmation<T>(run: (confirmToken?: string) => Promise<T>, confirmed = false): Promise<T> {
+  try {
+    return await run();
+  } catch (err) {
+    if (!isConfirmationRequired(err)) throw err;
+    if (!confirmed && settingsStore.confirmBeforeDelete) {
+      const rows = err.estimated_rows !== null
+        ? ` Estimated rows affected: ${err.estimated_rows.toLocaleString()}.`
+        : '';
+      if (!(await uiStore.ask(`${err.summary}.${rows} Run it anyway?`))) throw err;
+    }
+    return run(err.token);
+  }
+}
+
 export async function executeQuery(connectionId: string, sql: string, queryId?: string): Promise<QueryResponse | null> {
   uiStore.setLoading(true, 'Executing query...');
-  const startTime = performance.now();
   try {
-    const result = await tauri.executeQuery(connectionId, sql.trim(), undefined, queryId, settingsStore.maxQueryRows, settingsStore.maxCellSize);
-
-    queryHistoryStore.addEntry({
-      connectionId,
-      sql: sql.trim(),
-      executionTimeMs: result.execution_time_ms,
-      rowCount: result.row_count,
-    });
-
-    return result;
+    return await withConfirmation((token) =>
+      tauri.executeQuery(connectionId, sql.trim(), undefined, queryId, settingsStore.maxQueryRows, settingsStore.maxCellSize, token)
+    );
   } catch (err) {
-    const message = errorMessage(err);
-    const elapsed = Math.round(performance.now() - startTime);
+    // Declined at the confirmation prompt; nothing ran.
+    if (isConfirmationRequired(err)) return null;
 
-    queryHistoryStore.addEntry({
-      connectionId,
-      sql: sql.trim(),
-      executionTimeMs: elapsed,
-      rowCount: 0,
-      error: message,
-    });
-
-    uiStore.showError(`Query error: ${message}`);
+    uiStore.showError(`Query error: ${errorMessage(err)}`);
     return null;
   } finally {
     uiStore.setLoading(false);
+    // The backend records history; pick up the new entry.
+    queryHistoryStore.refresh();
   }
 }
 
@@ -52,37 +63,21 @@ export async function executeStatements(
       if (!stmt) continue;
 
       uiStore.setLoading(true, `Executing statement ${i + 1} of ${statements.length}...`);
-      const startTime = performance.now();
 
       try {
-        const result = await tauri.executeQuery(connectionId, stmt, undefined, queryId, settingsStore.maxQueryRows, settingsStore.maxCellSize);
+        const result = await withConfirmation((token) =>
+          tauri.executeQuery(connectionId, stmt, undefined, queryId, settingsStore.maxQueryRows, settingsStore.maxCellSize, token)
+        );
         results.push(result);
-
-        queryHistoryStore.addEntry({
-          connectionId,
-          sql: stmt,
-          executionTimeMs: result.execution_time_ms,
-          rowCount: result.row_count,
-        });
       } catch (err) {
-        const message = errorMessage(err);
-        const elapsed = Math.round(performance.now() - startTime);
-
-        queryHistoryStore.addEntry({
-          connectionId,
-          sql: stmt,
-          executionTimeMs: elapsed,
-          rowCount: 0,
-          error: message,
-        });
-
-        return { results, error: { index: i, message } };
+        return { results, error: { index: i, message: errorMessage(err) } };
       }
     }
 
     return { results };
   } finally {
     uiStore.setLoading(false);
+    queryHistoryStore.refresh();
   }
 }
 
@@ -95,10 +90,16 @@ export async function executeQueryPage(
   sortColumns?: SortColumn[]
 ): Promise<QueryResponse | null> {
   try {
-    return await tauri.executeQueryPage(connectionId, sql, limit, offset, undefined, queryId, settingsStore.maxCellSize, sortColumns);
+    return await withConfirmation((token) =>
+      tauri.executeQueryPage(connectionId, sql, limit, offset, undefined, queryId, settingsStore.maxCellSize, sortColumns, token)
+    );
   } catch (err) {
+    if (isConfirmationRequired(err)) return null;
     uiStore.showError(`Page fetch error: ${errorMessage(err)}`);
     return null;
+  } finally {
+    // Only the first page is recorded in history.
+    if (offset === 0) queryHistoryStore.refresh();
   }
 }
 
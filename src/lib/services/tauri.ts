@@ -1,11 +1,11 @@
-import { invoke as tauriInvoke } from '@tauri-apps/api/core';
+import { invoke as tauriInvoke, Channel } from '@tauri-apps/api/core';
 import { captureError } from '$lib/services/sentryService';
 import type { ConnectionConfig, DatabaseCategory } from '$lib/types/connection';
-import type { QueryResponse, SortColumn, FilterCondition, CellValue, ColumnDef } from '$lib/types/query';
+import type { QueryResponse, SortColumn, FilterCondition, CellValue, ColumnDef, QueryStreamEvent, StreamSummary, ScriptEvent, ScriptSummary, QueryPlan, QueryHistoryEntry, HistoryFilter, QueryParameter, MultiQueryResponse, TablePage } from '$lib/types/query';
 import type {
   SchemaInfo, TableInfo, ColumnInfo, IndexInfo, ForeignKeyInfo,
   ContainerInfo, ItemInfo, FieldInfo,
-  TableStats, RoutineInfo, SequenceInfo, EnumInfo
+  TableStats, RoutineInfo, SequenceInfo, EnumInfo, KeyColumn
 } from '$lib/types/schema';
 import type { ImportResult } from '$lib/types/export';
 
@@ -37,17 +37,107 @@ export async function pingConnection(connectionId: string): Promise<boolean> {
 }
 
 // Query execution
-export async function executeQuery(connectionId: string, sql: string, timeoutSecs?: number, queryId?: string, maxRows?: number, maxCellSize?: number): Promise<QueryResponse> {
-  return invoke<QueryResponse>('execute_query', { connectionId, sql, timeoutSecs: timeoutSecs ?? null, queryId: queryId ?? null, maxRows: maxRows ?? null, maxCellSize: maxCellSize ?? null });
+export async function executeQuery(connectionId: string, sql: string, timeoutSecs?: number, queryId?: string, maxRows?: number, maxCellSize?: number, confirmToken?: string): Promise<QueryResponse> {
+  return invoke<QueryResponse>('execute_query', { connectionId, sql, timeoutSecs: timeoutSecs ?? null, queryId: queryId ?? null, maxRows: maxRows ?? null, maxCellSize: maxCellSize ?? null, confirmToken: confirmToken ?? null });
 }
 
-export async function executeQueryPage(connectionId: string, sql: string, limit: number, offset: number, timeoutSecs?: number, queryId?: string, maxCellSize?: number, sortColumns?: SortColumn[]): Promise<QueryResponse> {
+export async function executeQueryMulti(connectionIds: string[], sql: string, merge?: boolean, maxConcurrency?: number, timeoutSecs?: number, maxRows?: number, maxCellSize?: number, confirmTokens?: Record<string, string>): Promise<MultiQueryResponse> {
+  return invoke<MultiQueryResponse>('execute_query_multi', {
+    connectionIds, sql,
+    merge: merge ?? null,
+    maxConcurrency: maxConcurrency ?? null,
+    timeoutSecs: timeoutSecs ?? null,
+    maxRows: maxRows ?? null,
+    maxCellSize: maxCellSize ?? null,
+    confirmTokens: confirmTokens ?? null,
+  });
+}
+
+export async function executeQueryStream(connectionId: string, sql: string, onEvent: (event: QueryStreamEvent) => void, timeoutSecs?: number, queryId?: string, maxRows?: number, batchSize?: number, maxCellSize?: number, confirmToken?: string): Promise<StreamSummary> {
+  const channel = new Channel<QueryStreamEvent>();
+  channel.onmessage = onEvent;
+  return invoke<StreamSummary>('execute_query_stream', {
+    connectionId, sql,
+    timeoutSecs: timeoutSecs ?? null,
+    queryId: queryId ?? null,
+    maxRows: maxRows ?? null,
+    batchSize: batchSize ?? null,
+    maxCellSize: maxCellSize ?? null,
+    confirmToken: confirmToken ?? null,
+    onEvent: channel,
+  });
+}
+
+export async function executeQueryResultSets(connectionId: string, sql: string, timeoutSecs?: number, queryId?: string, maxRows?: number, maxCellSize?: number, confirmToken?: string): Promise<QueryResponse[]> {
+  return invoke<QueryResponse[]>('execute_query_result_sets', {
+    connectionId, sql,
+    timeoutSecs: timeoutSecs ?? null,
+    queryId: queryId ?? null,
+    maxRows: maxRows ?? null,
+    maxCellSize: maxCellSize ?? null,
+    confirmToken: confirmToken ?? null,
+  });
+}
+
+export async function explainQuery(connectionId: string, sql: string, analyze?: boolean, timeoutSecs?: number, queryId?: string, confirmToken?: string): Promise<QueryPlan> {
+  return invoke<QueryPlan>('explain_query', {
+    connectionId, sql,
+    analyze: analyze ?? null,
+    timeoutSecs: timeoutSecs ?? null,
+    queryId: queryId ?? null,
+    confirmToken: confirmToken ?? null,
+  });
+}
+
+export async function executeQueryParams(connectionId: string, sql: string, params: CellValue[], timeoutSecs?: number, queryId?: string, maxRows?: number, maxCellSize?: number, confirmToken?: string): Promise<QueryResponse> {
+  return invoke<QueryResponse>('execute_query_params', {
+    connectionId, sql, params,
+    timeoutSecs: timeoutSecs ?? null,
+    queryId: queryId ?? null,
+    maxRows: maxRows ?? null,
+    maxCellSize: maxCellSize ?? null,
+    confirmToken: confirmToken ?? null,
+  });
+}
+
+export async function describeParameters(connectionId: string, sql: string): Promise<QueryParameter[]> {
+  return invoke<QueryParameter[]>('describe_parameters', { connectionId, sql });
+}
+
+export async function executeQueryNamed(connectionId: string, sql: string, values: Record<string, CellValue>, timeoutSecs?: number, queryId?: string, maxRows?: number, maxCellSize?: number, confirmToken?: string): Promise<QueryResponse> {
+  return invoke<QueryResponse>('execute_query_named', {
+    connectionId, sql, values,
+    timeoutSecs: timeoutSecs ?? null,
+    queryId: queryId ?? null,
+    maxRows: maxRows ?? null,
+    maxCellSize: maxCellSize ?? null,
+    confirmToken: confirmToken ?? null,
+  });
+}
+
+export async function executeScript(connectionId: string, sql: string, onEvent: (event: ScriptEvent) => void, continueOnError?: boolean, timeoutSecs?: number, queryId?: string, maxRows?: number, maxCellSize?: number, confirmToken?: string): Promise<ScriptSummary> {
+  const channel = new Channel<ScriptEvent>();
+  channel.onmessage = onEvent;
+  return invoke<ScriptSummary>('execute_script', {
+    connectionId, sql,
+    continueOnError: continueOnError ?? null,
+    timeoutSecs: timeoutSecs ?? null,
+    queryId: queryId ?? null,
+    maxRows: maxRows ?? null,
+    maxCellSize: maxCellSize ?? null,
+    confirmToken: confirmToken ?? null,
+    onEvent: channel,
+  });
+}
+
+export async function executeQueryPage(connectionId: string, sql: string, limit: number, offset: number, timeoutSecs?: number, queryId?: string, maxCellSize?: number, sortColumns?: SortColumn[], confirmToken?: string): Promise<QueryResponse> {
   return invoke<QueryResponse>('execute_query_page', {
     connectionId, sql, limit, offset,
     timeoutSecs: timeoutSecs ?? null,
     queryId: queryId ?? null,
     maxCellSize: maxCellSize ?? null,
     sortColumns: sortColumns && sortColumns.length > 0 ? sortColumns : null,
+    confirmToken: confirmToken ?? null,
   });
 }
 
@@ -63,6 +153,37 @@ export async function cancelQuery(queryId: string): Promise<boolean> {
   return invoke<boolean>('cancel_query', { queryId });
 }
 
+// Local SQL over fetched results
+export async function storeResultSet(resultId: string, response: QueryResponse): Promise<void> {
+  return invoke<void>('store_result_set', { resultId, response });
+}
+
+export async function queryResultSet(sql: string, maxRows?: number, maxCellSize?: number): Promise<QueryResponse> {
+  return invoke<QueryResponse>('query_result_set', { sql, maxRows: maxRows ?? null, maxCellSize: maxCellSize ?? null });
+}
+
+export async function dropResultSet(resultId: string): Promise<void> {
+  return invoke<void>('drop_result_set', { resultId });
+}
+
+// Query history
+export async function searchHistory(filter?: HistoryFilter): Promise<QueryHistoryEntry[]> {
+  return invoke<QueryHistoryEntry[]>('search_history', { filter: filter ?? null });
+}
+
+export async function pinHistoryEntry(id: number, pinned: boolean): Promise<void> {
+  return invoke<void>('pin_history_entry', { id, pinned });
+}
+
+export async function purgeHistory(before?: number, connectionId?: string, includePinned?: boolean): Promise<number> {
+  return invoke<number>('purge_history', { before: before ?? null, connectionId: connectionId ?? null, includePinned: includePinned ?? null });
+}
+
+// Audit log
+export async function exportAuditLog(filePath: string, since?: number, until?: number, connectionId?: string): Promise<number> {
+  return invoke<number>('export_audit_log', { filePath, since: since ?? null, until: until ?? null, connectionId: connectionId ?? null });
+}
+
 // Generic schema browsing (all databases)
 export async function getDatabaseCategory(connectionId: string): Promise<DatabaseCategory> {
   return invoke<DatabaseCategory>('get_database_category', { connectionId });
@@ -109,11 +230,18 @@ export async function getForeignKeys(connectionId: string, schema: string, table
   return invoke<ForeignKeyInfo[]>('get_foreign_keys', { connectionId, schema, table });
 }
 
-export async function getTableData(connectionId: string, schema: string, table: string, limit: number, offset: number, sortColumns?: SortColumn[], filters?: FilterCondition[]): Promise<QueryResponse> {
-  return invoke<QueryResponse>('get_table_data', {
+/** `after` is the last row of the previous page (column name -> value); when
+ *  paging forward it lets the backend seek on the primary key instead of OFFSET.
+ *  `key` and `selectList` come from an earlier page of the same table, so the
+ *  backend does not look them up again. */
+export async function getTableData(connectionId: string, schema: string, table: string, limit: number, offset: number, sortColumns?: SortColumn[], filters?: FilterCondition[], after?: Record<string, CellValue>, key?: KeyColumn[] | null, selectList?: string | null): Promise<TablePage> {
+  return invoke<TablePage>('get_table_data', {
     connectionId, schema, table, limit, offset,
     sortColumns: sortColumns && sortColumns.length > 0 ? sortColumns : null,
     filters: filters && filters.length > 0 ? filters : null,
+    after: after ?? null,
+    key: key ?? null,
+    selectList: selectList ?? null,
   });
 }
 
@@ -175,8 +303,8 @@ export async function updateDocument(connectionId: string, container: string, it
   return invoke<number>('update_document', { connectionId, container, item, filter, update });
 }
 
-export async function deleteDocuments(connectionId: string, container: string, item: string, filter: string): Promise<number> {
-  return invoke<number>('delete_documents', { connectionId, container, item, filter });
+export async function deleteDocuments(connectionId: string, container: string, item: string, filter: string, confirmToken?: string): Promise<number> {
+  return invoke<number>('delete_documents', { connectionId, container, item, filter, confirmToken: confirmToken ?? null });
 }
 
 // Key-value operations (Redis)
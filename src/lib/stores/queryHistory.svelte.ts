@@ -1,55 +1,47 @@
-import { load, type Store } from '@tauri-apps/plugin-store';
-import { v4 as uuidv4 } from 'uuid';
-import type { QueryHistoryEntry } from '$lib/types/query';
+import * as tauri from '$lib/services/tauri';
+import type { HistoryFilter, QueryHistoryEntry } from '$lib/types/query';
 
-const MAX_ENTRIES = 500;
+/** How many recent entries to keep loaded for the command palette. */
+const RECENT_LIMIT = 50;
 
+/**
+ * Query history is recorded by the backend into its own SQLite database;
+ * this store keeps the most recent entries loaded and forwards searches.
+ */
 class QueryHistoryStore {
   entries = $state<QueryHistoryEntry[]>([]);
-  private store: Store | null = null;
+  /** Bumped whenever history changes so open views can re-query. */
+  version = $state(0);
   private initialized = false;
 
   async init() {
     if (this.initialized) return;
-    this.store = await load('query-history.json');
-    const saved = await this.store.get<QueryHistoryEntry[]>('entries');
-    if (saved) {
-      this.entries = saved;
-    }
     this.initialized = true;
+    await this.refresh();
   }
 
-  private async persist() {
-    if (this.store) {
-      await this.store.set('entries', this.entries);
-      await this.store.save();
+  async refresh() {
+    try {
+      this.entries = await tauri.searchHistory({ limit: RECENT_LIMIT });
+      this.version++;
+    } catch (err) {
+      console.error('Failed to load query history:', err);
     }
   }
 
-  addEntry(entry: Omit<QueryHistoryEntry, 'id' | 'executedAt'>) {
-    const newEntry: QueryHistoryEntry = {
-      id: uuidv4(),
-      executedAt: Date.now(),
-      ...entry,
-    };
-    this.entries = [newEntry, ...this.entries].slice(0, MAX_ENTRIES);
-    this.persist();
-  }
-
-  getEntries(connectionId?: string): QueryHistoryEntry[] {
-    if (!connectionId) return this.entries;
-    return this.entries.filter(e => e.connectionId === connectionId);
+  search(filter: HistoryFilter): Promise<QueryHistoryEntry[]> {
+    return tauri.searchHistory(filter);
   }
 
-  search(query: string, connectionId?: string): QueryHistoryEntry[] {
-    const lower = query.toLowerCase();
-    const base = connectionId ? this.getEntries(connectionId) : this.entries;
-    return base.filter(e => e.sql.toLowerCase().includes(lower));
+  async setPinned(id: number, pinned: boolean) {
+    await tauri.pinHistoryEntry(id, pinned);
+    await this.refresh();
   }
 
-  clear() {
-    this.entries = [];
-    this.persist();
+  /** Remove unpinned history, for one connection or all of them. */
+  async clear(connectionId?: string) {
+    await tauri.purgeHistory(undefined, connectionId);
+    await this.refresh();
   }
 }
 
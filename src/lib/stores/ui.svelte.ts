@@ -27,6 +27,7 @@ class UiStore {
   showConfirmDialog = $state(false);
   confirmDialogMessage = $state('');
   confirmDialogCallback = $state<(() => void) | null>(null);
+  confirmDialogCancelCallback = $state<(() => void) | null>(null);
   isLoading = $state(false);
   loadingMessage = $state('');
   errorMessage = $state<string | null>(null);
@@ -93,12 +94,22 @@ class UiStore {
   confirm(message: string, callback: () => void) {
     this.confirmDialogMessage = message;
     this.confirmDialogCallback = callback;
+    this.confirmDialogCancelCallback = null;
     this.showConfirmDialog = true;
   }
 
+  /** Like `confirm`, resolving to whether the user accepted. */
+  ask(message: string): Promise<boolean> {
+    return new Promise((resolve) => {
+      this.confirm(message, () => resolve(true));
+      this.confirmDialogCancelCallback = () => resolve(false);
+    });
+  }
+
   closeConfirmDialog() {
     this.showConfirmDialog = false;
     this.confirmDialogCallback = null;
+    this.confirmDialogCancelCallback = null;
   }
 
   setLoading(loading: boolean, message = '') {
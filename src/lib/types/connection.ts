@@ -1,3 +1,5 @@
+export type GuardrailLevel = 'Off' | 'Standard' | 'Strict';
+
 export type DatabaseType =
   | 'PostgreSQL'
   | 'MySQL'
@@ -73,6 +75,17 @@ export interface ConnectionConfig {
   pool_max_connections?: number;
   pool_idle_timeout_secs?: number;
   pool_acquire_timeout_secs?: number;
+  // Reject writes in the backend
+  read_only?: boolean;
+  // Server-side limits set on every session
+  statement_timeout_ms?: number;
+  lock_timeout_ms?: number;
+  // Run on every new physical connection
+  init_sql?: string[];
+  default_schema?: string;
+  // Destructive-statement confirmation; defaults to Strict when tagged "production"
+  tags?: string[];
+  guardrails?: GuardrailLevel;
   // Connection group
   group?: string;
   // Connection color
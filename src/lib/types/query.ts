@@ -1,3 +1,5 @@
+import type { KeyColumn } from './schema';
+
 export type CellValue =
   | { type: 'Null' }
   | { type: 'Bool'; value: boolean }
@@ -7,6 +9,14 @@ export type CellValue =
   | { type: 'Timestamp'; value: string }
   | { type: 'Binary'; value: number[] }
   | { type: 'Json'; value: string }
+  | { type: 'Decimal'; value: { digits: string; scale: number } }
+  | { type: 'Uuid'; value: string }
+  | { type: 'Date'; value: string }
+  | { type: 'Time'; value: string }
+  | { type: 'DateTime'; value: { tz: string | null; micros: number } }
+  | { type: 'Interval'; value: { months: number; days: number; micros: number } }
+  | { type: 'Array'; value: CellValue[] }
+  | { type: 'Enum'; value: string }
   | { type: 'LargeText'; value: { preview: string; full_length: number } }
   | { type: 'LargeJson'; value: { preview: string; full_length: number } }
   | { type: 'LargeBinary'; value: { preview_length: number; full_length: number } };
@@ -24,6 +34,120 @@ export interface QueryResponse {
   affected_rows: number | null;
   truncated?: boolean;
   max_rows_limit?: number;
+  messages?: ServerMessage[];
+}
+
+/** A page of table data. `key` is the key the table is paged by, to pass back
+ *  with the next page; empty when the table has none, null when not paged by key.
+ *  `select_list` is passed back the same way. */
+export interface TablePage extends QueryResponse {
+  key: KeyColumn[] | null;
+  select_list: string | null;
+}
+
+export interface ServerMessage {
+  severity: 'error' | 'warning' | 'notice' | 'info' | 'debug';
+  code: string | null;
+  text: string;
+}
+
+export interface PlanNode {
+  operation: string;
+  object: string | null;
+  detail: string | null;
+  estimated_rows: number | null;
+  actual_rows: number | null;
+  cost: number | null;
+  actual_time_ms: number | null;
+  children: PlanNode[];
+}
+
+export interface QueryPlan {
+  root: PlanNode;
+  analyzed: boolean;
+  planning_time_ms: number | null;
+  execution_time_ms: number | null;
+  raw: string;
+}
+
+export interface QueryBatch {
+  batch_index: number;
+  columns: ColumnDef[] | null;
+  rows: CellValue[][];
+}
+
+export interface StreamSummary {
+  row_count: number;
+  execution_time_ms: number;
+  affected_rows: number | null;
+  truncated: boolean;
+  max_rows_limit: number | null;
+}
+
+export type QueryStreamEvent =
+  | { event: 'batch'; data: QueryBatch }
+  | { event: 'finished'; data: StreamSummary };
+
+export interface SourceSpan {
+  start: number;
+  end: number;
+  line: number;
+}
+
+export interface StatementError {
+  code: string;
+  message: string;
+}
+
+/** Error returned when a guardrail holds back a destructive statement. */
+export interface ConfirmationRequired {
+  code: 'CONFIRMATION_REQUIRED';
+  message: string;
+  summary: string;
+  estimated_rows: number | null;
+  /** Pass back with the same statement to run it. */
+  token: string;
+}
+
+export interface ScriptStatementResult {
+  index: number;
+  span: SourceSpan;
+  response: QueryResponse | null;
+  error: StatementError | null;
+}
+
+export interface ScriptSummary {
+  statement_count: number;
+  succeeded: number;
+  failed: number;
+  skipped: number;
+  execution_time_ms: number;
+}
+
+export type ScriptEvent =
+  | { event: 'statement'; data: ScriptStatementResult }
+  | { event: 'finished'; data: ScriptSummary };
+
+export interface ConnectionQueryResult {
+  connection_id: string;
+  response: QueryResponse | null;
+  error: StatementError | null;
+  confirm_token: string | null;
+  execution_time_ms: number;
+}
+
+export interface MultiQueryResponse {
+  merged: QueryResponse | null;
+  results: ConnectionQueryResult[];
+  succeeded: number;
+  failed: number;
+  execution_time_ms: number;
+}
+
+export interface QueryParameter {
+  name: string;
+  kind: 'Value' | 'Identifier';
+  occurrences: number;
 }
 
 export interface SortColumn {
@@ -46,13 +170,28 @@ export interface MultiStatementResult {
 }
 
 export interface QueryHistoryEntry {
-  id: string;
-  connectionId: string;
+  id: number;
+  connection_id: string;
   sql: string;
-  executedAt: number;
-  executionTimeMs: number;
-  rowCount: number;
-  error?: string;
+  duration_ms: number;
+  /** Rows returned, or rows affected for statements without a result set. */
+  row_count: number | null;
+  error_code: string | null;
+  error_message: string | null;
+  /** Unix timestamp in milliseconds. */
+  executed_at: number;
+  pinned: boolean;
+}
+
+export interface HistoryFilter {
+  text?: string;
+  connection_id?: string;
+  pinned_only?: boolean;
+  errors_only?: boolean;
+  since?: number;
+  until?: number;
+  limit?: number;
+  offset?: number;
 }
 
 export interface SavedQuery {
@@ -41,6 +41,12 @@ export interface ColumnInfo {
   ordinal_position: number;
 }
 
+/** A column of the key a table is paged by. */
+export interface KeyColumn {
+  name: string;
+  data_type: string;
+}
+
 export interface IndexInfo {
   name: string;
   columns: string[];
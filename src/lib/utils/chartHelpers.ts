@@ -1,5 +1,6 @@
 import type { CellValue, ColumnDef } from '$lib/types/query';
 import type { ChartDataPoint } from '$lib/types/chart';
+import { extractCellValue } from '$lib/utils/formatters';
 
 /**
  * Extract a numeric value from a CellValue, returning null if not numeric.
@@ -17,6 +18,7 @@ export function extractNumericValue(cell: CellValue): number | null {
       const n = parseFloat(cell.value.preview);
       return isNaN(n) ? null : n;
     }
+    case 'Decimal': return parseFloat(extractCellValue(cell));
     case 'Null': return null;
     default: return null;
   }
@@ -36,7 +38,8 @@ function extractLabelValue(cell: CellValue): string {
     case 'Json': return cell.value.substring(0, 40);
     case 'LargeText': return cell.value.preview.substring(0, 40);
     case 'LargeJson': return cell.value.preview.substring(0, 40);
-    default: return '';
+    case 'LargeBinary': return '';
+    default: return extractCellValue(cell).substring(0, 40);
   }
 }
 
@@ -1,5 +1,6 @@
 import type { CellValue } from '$lib/types/query';
 import type { DataDiffResult, RowDiff, RowDiffStatus } from '$lib/types/diff';
+import { extractCellValue } from '$lib/utils/formatters';
 
 function cellToString(cell: CellValue): string {
   switch (cell.type) {
@@ -14,6 +15,7 @@ function cellToString(cell: CellValue): string {
     case 'LargeText': return cell.value.preview;
     case 'LargeJson': return cell.value.preview;
     case 'LargeBinary': return `[${cell.value.full_length} bytes]`;
+    default: return extractCellValue(cell);
   }
 }
 
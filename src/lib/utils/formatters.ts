@@ -24,6 +24,14 @@ export function extractCellValue(cell: CellValue): string {
     case 'Timestamp': return cell.value;
     case 'Binary': return `[${cell.value.length} bytes]`;
     case 'Json': return cell.value;
+    case 'Decimal': return formatDecimal(cell.value.digits, cell.value.scale);
+    case 'Uuid': return cell.value;
+    case 'Date': return cell.value;
+    case 'Time': return cell.value;
+    case 'DateTime': return formatDateTime(cell.value.micros, cell.value.tz);
+    case 'Interval': return formatInterval(cell.value.months, cell.value.days, cell.value.micros);
+    case 'Array': return formatArray(cell.value);
+    case 'Enum': return cell.value;
     case 'LargeText': return cell.value.preview;
     case 'LargeJson': return cell.value.preview;
     case 'LargeBinary': return `[${cell.value.full_length} bytes]`;
@@ -31,6 +39,67 @@ export function extractCellValue(cell: CellValue): string {
   }
 }
 
+/** `digits * 10^-scale` written out without going through a float. */
+export function formatDecimal(digits: string, scale: number): string {
+  const negative = digits.startsWith('-');
+  const abs = negative ? digits.slice(1) : digits;
+  if (scale === 0) return digits;
+  const padded = abs.padStart(scale + 1, '0');
+  const split = padded.length - scale;
+  return `${negative ? '-' : ''}${padded.slice(0, split)}.${padded.slice(split)}`;
+}
+
+/** `YYYY-MM-DD HH:MM:SS[.ffffff][+HH:MM]`, matching the backend's text form. */
+export function formatDateTime(micros: number, tz: string | null): string {
+  let offsetMinutes = 0;
+  if (tz) {
+    const m = /^([+-])(\d{2}):(\d{2})$/.exec(tz);
+    if (m) offsetMinutes = (m[1] === '-' ? -1 : 1) * (Number(m[2]) * 60 + Number(m[3]));
+  }
+  const frac = ((micros % 1_000_000) + 1_000_000) % 1_000_000;
+  const date = new Date((micros - frac) / 1000 + offsetMinutes * 60_000);
+  if (isNaN(date.getTime())) return String(micros);
+  const text = date.toISOString().slice(0, 19).replace('T', ' ');
+  const digits = String(frac).padStart(6, '0');
+  const fraction = !frac ? '' : frac % 1000 ? `.${digits}` : `.${digits.slice(0, 3)}`;
+  return `${text}${fraction}${tz ?? ''}`;
+}
+
+/** ISO 8601 duration, e.g. `P1Y2M3DT4H5M6.5S`. */
+export function formatInterval(months: number, days: number, micros: number): string {
+  let out = 'P';
+  const years = Math.trunc(months / 12);
+  if (years) out += `${years}Y`;
+  if (months % 12) out += `${months % 12}M`;
+  if (days) out += `${days}D`;
+  if (micros) {
+    out += 'T';
+    const hours = Math.trunc(micros / 3_600_000_000);
+    const minutes = Math.trunc(micros / 60_000_000) % 60;
+    const seconds = micros % 60_000_000;
+    if (hours) out += `${hours}H`;
+    if (minutes) out += `${minutes}M`;
+    if (seconds) {
+      const abs = Math.abs(seconds);
+      const frac = `.${String(abs % 1_000_000).padStart(6, '0')}`.replace(/\.?0+$/, '');
+      out += `${seconds < 0 ? '-' : ''}${Math.trunc(abs / 1_000_000)}${frac}S`;
+    }
+  }
+  return out === 'P' ? 'PT0S' : out;
+}
+
+/** PostgreSQL-style array literal, e.g. `{1,NULL,"a b"}`. */
+export function formatArray(items: CellValue[]): string {
+  const elements = items.map(item => {
+    if (item.type === 'Null') return 'NULL';
+    if (item.type === 'Array') return formatArray(item.value);
+    const text = extractCellValue(item);
+    const plain = text !== '' && text.toUpperCase() !== 'NULL' && !/[{}",\\\s]/.test(text);
+    return plain ? text : `"${text.replace(/\\/g, '\\\\').replace(/"/g, '\\"')}"`;
+  });
+  return `{${elements.join(',')}}`;
+}
+
 export function formatDuration(ms: number): string {
   if (ms < 1000) return `${ms}ms`;
   return `${(ms / 1000).toFixed(2)}s`;
@@ -1,4 +1,5 @@
 import type { CellValue, ColumnDef } from '$lib/types/query';
+import { extractCellValue } from '$lib/utils/formatters';
 
 export interface ResultCompareRow {
   sourceRow: CellValue[] | null;
@@ -26,6 +27,7 @@ function cellToString(cell: CellValue): string {
     case 'LargeText': return `lt:${cell.value.preview}`;
     case 'LargeJson': return `lj:${cell.value.preview}`;
     case 'LargeBinary': return `lb:${cell.value.full_length}`;
+    default: return `${cell.type}:${extractCellValue(cell)}`;
   }
 }
 
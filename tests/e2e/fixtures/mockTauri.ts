@@ -48,6 +48,13 @@ const defaultMockResponses: Record<string, unknown> = {
   get_sequences: [],
   get_enums: [],
   cancel_query: true,
+  describe_parameters: [],
+  store_result_set: null,
+  drop_result_set: null,
+  search_history: [],
+  pin_history_entry: null,
+  purge_history: 0,
+  export_audit_log: 0,
   begin_transaction: null,
   commit_transaction: null,
   rollback_transaction: null,